@@ -0,0 +1,425 @@
+use {
+	crate::{
+		build_instructions,
+		compute_budget::ComputeBudget,
+		error::{format_client_error, format_transaction_error},
+		output::{print_json, OutputFormat},
+		Commands,
+	},
+	anyhow::{anyhow, Result},
+	clap::Parser,
+	serde::{Deserialize, Serialize},
+	solana_client::rpc_client::RpcClient,
+	solana_program::{instruction::Instruction, pubkey::Pubkey},
+	solana_sdk::{
+		compute_budget::ComputeBudgetInstruction, packet::PACKET_DATA_SIZE, signature::Signer,
+		transaction::Transaction,
+	},
+	std::{fs, path::Path},
+};
+
+/// Compute units budgeted for each operation of a batch
+pub const OPERATION_COMPUTE_UNITS: u32 = 200_000;
+
+/// The maximum number of compute units a transaction may request
+pub const MAX_TRANSACTION_COMPUTE_UNITS: u32 = 1_400_000;
+
+/// An operation of a batch file, either a command line or its arguments, without the `sclr`
+/// binary name and global options, e.g. `tokenize principal-yield <TOKENIZER> 100 <MINT>`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BatchEntry {
+	Line(String),
+	Args(Vec<String>),
+}
+
+impl BatchEntry {
+	fn args(&self) -> Vec<String> {
+		match self {
+			BatchEntry::Line(line) => line.split_whitespace().map(str::to_string).collect(),
+			BatchEntry::Args(args) => args.clone(),
+		}
+	}
+}
+
+#[derive(Debug, Parser)]
+#[command(no_binary_name = true)]
+struct BatchOperation {
+	#[command(subcommand)]
+	cmd: Commands,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+enum OperationStatus {
+	Success,
+	Failed,
+	Skipped,
+}
+
+#[derive(Debug, Serialize)]
+struct OperationResult {
+	operation: String,
+	/// Index of the transaction the operation was sent in, from one
+	transaction: Option<usize>,
+	signature: Option<String>,
+	status: OperationStatus,
+	error: Option<String>,
+}
+
+/// Options of the transactions sent by a batch
+pub struct BatchOptions {
+	pub compute_budget: ComputeBudget,
+	/// Simulate each transaction instead of sending it, transactions are simulated against
+	/// the current state so later transactions may fail on operations depending on earlier ones
+	pub simulate: bool,
+	pub output: OutputFormat,
+}
+
+struct BatchTransaction {
+	operations: Vec<usize>,
+	instructions: Vec<Instruction>,
+	compute_units: u32,
+}
+
+impl BatchTransaction {
+	fn new() -> Self {
+		BatchTransaction {
+			operations: vec![],
+			instructions: vec![],
+			compute_units: 0,
+		}
+	}
+
+	// Checks whether the operation fits within the size and compute limits of the transaction,
+	// including its compute budget instructions
+	fn fits(
+		&self,
+		payer: &Pubkey,
+		instructions: &[Instruction],
+		compute_units: u32,
+		priced: bool,
+	) -> bool {
+		let compute_units = self.compute_units + compute_units;
+		if compute_units > MAX_TRANSACTION_COMPUTE_UNITS {
+			return false;
+		}
+
+		let mut transaction_instructions =
+			vec![ComputeBudgetInstruction::set_compute_unit_limit(compute_units)];
+		if priced {
+			transaction_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(0));
+		}
+		transaction_instructions.extend_from_slice(&self.instructions);
+		transaction_instructions.extend_from_slice(instructions);
+
+		let transaction = Transaction::new_with_payer(&transaction_instructions, Some(payer));
+
+		bincode::serialized_size(&transaction)
+			.map(|size| size as usize <= PACKET_DATA_SIZE)
+			.unwrap_or(false)
+	}
+}
+
+// Sends a transaction of the batch, returning its signature or the error it failed with
+fn send_batch_transaction(
+	client: &RpcClient,
+	signers: &[&dyn Signer],
+	batch_transaction: &BatchTransaction,
+	options: &BatchOptions,
+) -> Result<(Option<String>, Option<String>)> {
+	let payer_pubkey = signers[0].pubkey();
+
+	let mut instructions = options.compute_budget.instructions(
+		client,
+		&batch_transaction.instructions,
+		Some(batch_transaction.compute_units),
+	)?;
+	instructions.extend_from_slice(&batch_transaction.instructions);
+
+	let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer_pubkey));
+	let latest_blockhash = client
+		.get_latest_blockhash()
+		.map_err(|err| anyhow!("Unable to get latest blockhash: {}", err))?;
+	transaction
+		.try_sign(signers, latest_blockhash)
+		.map_err(|err| anyhow!("Unable to sign transaction: {}", err))?;
+
+	if options.simulate {
+		let result = client
+			.simulate_transaction(&transaction)
+			.map_err(|err| anyhow!("Unable to simulate transaction: {}", err))?
+			.value;
+
+		let logs = result.logs.unwrap_or_default();
+
+		Ok((
+			None,
+			result
+				.err
+				.map(|err| format_transaction_error(&err, &transaction.message, &logs)),
+		))
+	} else {
+		match client.send_and_confirm_transaction_with_spinner(&transaction) {
+			Ok(signature) => Ok((Some(signature.to_string()), None)),
+			Err(err) => Ok((None, Some(format_client_error(&err, &transaction.message)))),
+		}
+	}
+}
+
+/// Reads the operations of a YAML or JSON batch file, packs them into as few transactions as
+/// fit within the size and compute limits and sends them in order, stopping at the first
+/// failed operation. Operations are built when they are reached so that they can read the
+/// accounts created by earlier operations
+pub fn process_batch(
+	client: &RpcClient,
+	path: &Path,
+	payer: &dyn Signer,
+	owner: &dyn Signer,
+	timestamp: Option<i64>,
+	options: &BatchOptions,
+) -> Result<()> {
+	let compute_budget = &options.compute_budget;
+
+	let contents = fs::read_to_string(path)
+		.map_err(|err| anyhow!("Unable to read batch file {}: {}", path.display(), err))?;
+	let entries: Vec<BatchEntry> = serde_yaml::from_str(&contents)
+		.map_err(|err| anyhow!("Unable to parse batch file {}: {}", path.display(), err))?;
+
+	// Every operation is parsed before any transaction is sent
+	let operations = entries
+		.iter()
+		.enumerate()
+		.map(|(index, entry)| {
+			let args = entry.args();
+			BatchOperation::try_parse_from(&args)
+				.map_err(|err| anyhow!("Invalid operation {}: {}", index + 1, err))?;
+
+			Ok(args)
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	let payer_pubkey = payer.pubkey();
+	let mut signers: Vec<&dyn Signer> = vec![payer];
+	if owner.pubkey() != payer_pubkey {
+		signers.push(owner);
+	}
+
+	let build_operation = |args: &[String]| -> Result<Vec<Instruction>> {
+		let operation = BatchOperation::try_parse_from(args)?;
+
+		build_instructions(
+			client,
+			operation.cmd,
+			payer_pubkey,
+			owner.pubkey(),
+			timestamp,
+			false,
+		)
+	};
+
+	// The compute unit limit given on the command line is budgeted for each operation
+	let operation_compute_units = compute_budget.unit_limit.unwrap_or(OPERATION_COMPUTE_UNITS);
+	let priced = compute_budget.unit_price.is_some() || compute_budget.auto_priority_fee;
+
+	let mut results = vec![];
+	let mut failed = false;
+	let mut transaction_count = 0;
+	let mut pending = BatchTransaction::new();
+
+	// Sends the pending transaction and records the result of its operations
+	let mut flush = |pending: &mut BatchTransaction,
+	                 results: &mut Vec<OperationResult>,
+	                 failed: &mut bool|
+	 -> Result<()> {
+		if pending.operations.is_empty() {
+			return Ok(());
+		}
+
+		let (signature, error) = send_batch_transaction(client, &signers, pending, options)?;
+		transaction_count += 1;
+
+		let status = if error.is_some() {
+			OperationStatus::Failed
+		} else {
+			OperationStatus::Success
+		};
+		*failed |= status == OperationStatus::Failed;
+
+		for &operation_index in &pending.operations {
+			results.push(OperationResult {
+				operation: operations[operation_index].join(" "),
+				transaction: Some(transaction_count),
+				signature: signature.clone(),
+				status,
+				error: error.clone(),
+			});
+		}
+
+		*pending = BatchTransaction::new();
+		Ok(())
+	};
+
+	for (index, args) in operations.iter().enumerate() {
+		// An operation reading accounts created by the pending transaction can only be built
+		// once that transaction has been sent
+		let mut instructions = if failed {
+			Ok(vec![])
+		} else {
+			build_operation(args)
+		};
+		if instructions.is_err() && !pending.operations.is_empty() {
+			flush(&mut pending, &mut results, &mut failed)?;
+			if !failed {
+				instructions = build_operation(args);
+			}
+		}
+
+		if let Ok(ref instructions) = instructions {
+			if !failed
+				&& !pending.operations.is_empty()
+				&& !pending.fits(&payer_pubkey, instructions, operation_compute_units, priced)
+			{
+				flush(&mut pending, &mut results, &mut failed)?;
+			}
+		}
+
+		let (status, error) = match instructions {
+			_ if failed => (OperationStatus::Skipped, None),
+			Ok(instructions)
+				if pending.fits(&payer_pubkey, &instructions, operation_compute_units, priced) =>
+			{
+				pending.operations.push(index);
+				pending.instructions.extend(instructions);
+				pending.compute_units += operation_compute_units;
+				continue;
+			}
+			Ok(_) => (
+				OperationStatus::Failed,
+				Some("The operation does not fit in a single transaction".to_string()),
+			),
+			Err(err) => (OperationStatus::Failed, Some(err.to_string())),
+		};
+
+		failed = true;
+		results.push(OperationResult {
+			operation: args.join(" "),
+			transaction: None,
+			signature: None,
+			status,
+			error,
+		});
+	}
+
+	flush(&mut pending, &mut results, &mut failed)?;
+
+	match options.output {
+		OutputFormat::Display => {
+			for (index, result) in results.iter().enumerate() {
+				match result.transaction {
+					Some(transaction) => println!(
+						"Operation {} (transaction {}): {:?}  {}",
+						index + 1,
+						transaction,
+						result.status,
+						result.operation
+					),
+					None => println!(
+						"Operation {}: {:?}  {}",
+						index + 1,
+						result.status,
+						result.operation
+					),
+				}
+
+				if let Some(ref signature) = result.signature {
+					println!("  Signature: {}", signature);
+				}
+
+				if let Some(ref error) = result.error {
+					println!("  Error: {}", error);
+				}
+			}
+		}
+		OutputFormat::Json => print_json(&results)?,
+	}
+
+	if failed {
+		return Err(anyhow!("Batch failed"));
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn instruction(data_len: usize) -> Instruction {
+		Instruction::new_with_bytes(sclr_token::id(), &vec![0; data_len], vec![])
+	}
+
+	fn push(transaction: &mut BatchTransaction, instructions: Vec<Instruction>) {
+		transaction.operations.push(transaction.operations.len());
+		transaction.instructions.extend(instructions);
+		transaction.compute_units += OPERATION_COMPUTE_UNITS;
+	}
+
+	#[test]
+	fn fits_within_compute_unit_limit() {
+		let payer = Pubkey::new_unique();
+		let operations = MAX_TRANSACTION_COMPUTE_UNITS / OPERATION_COMPUTE_UNITS;
+
+		let mut transaction = BatchTransaction::new();
+		for _ in 0..operations {
+			assert!(transaction.fits(&payer, &[instruction(1)], OPERATION_COMPUTE_UNITS, false));
+			push(&mut transaction, vec![instruction(1)]);
+		}
+
+		assert!(!transaction.fits(&payer, &[instruction(1)], OPERATION_COMPUTE_UNITS, false));
+		assert!(!BatchTransaction::new().fits(
+			&payer,
+			&[instruction(1)],
+			MAX_TRANSACTION_COMPUTE_UNITS + 1,
+			false
+		));
+	}
+
+	#[test]
+	fn fits_within_packet_size() {
+		let payer = Pubkey::new_unique();
+
+		let mut transaction = BatchTransaction::new();
+		push(&mut transaction, vec![instruction(600)]);
+
+		assert!(transaction.fits(&payer, &[instruction(400)], OPERATION_COMPUTE_UNITS, false));
+		assert!(!transaction.fits(&payer, &[instruction(700)], OPERATION_COMPUTE_UNITS, false));
+		assert!(!BatchTransaction::new().fits(
+			&payer,
+			&[instruction(PACKET_DATA_SIZE)],
+			OPERATION_COMPUTE_UNITS,
+			false
+		));
+	}
+
+	#[test]
+	fn fits_includes_compute_unit_price() {
+		let payer = Pubkey::new_unique();
+		let transaction = BatchTransaction::new();
+
+		// The largest operation that fits without a compute unit price
+		let data_len = (0..PACKET_DATA_SIZE)
+			.take_while(|&len| {
+				transaction.fits(&payer, &[instruction(len)], OPERATION_COMPUTE_UNITS, false)
+			})
+			.last()
+			.unwrap();
+
+		assert!(!transaction.fits(&payer, &[instruction(data_len)], OPERATION_COMPUTE_UNITS, true));
+		assert!(!transaction.fits(
+			&payer,
+			&[instruction(data_len + 1)],
+			OPERATION_COMPUTE_UNITS,
+			false
+		));
+	}
+}
@@ -0,0 +1,78 @@
+use {
+	anyhow::{anyhow, Result},
+	solana_client::rpc_client::RpcClient,
+	solana_program::{instruction::Instruction, pubkey::Pubkey},
+	solana_sdk::compute_budget::ComputeBudgetInstruction,
+};
+
+// The percentile of recent prioritization fees used by `--auto-priority-fee`
+const PRIORITY_FEE_PERCENTILE: usize = 75;
+
+// The maximum number of accounts accepted by `getRecentPrioritizationFees`
+const MAX_PRIORITIZATION_FEE_ACCOUNTS: usize = 128;
+
+/// Compute unit limit and price of the transactions sent by the CLI
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ComputeBudget {
+	pub unit_limit: Option<u32>,
+	/// Price of a compute unit in micro-lamports
+	pub unit_price: Option<u64>,
+	/// Estimate the price from the recent prioritization fees of the written accounts
+	pub auto_priority_fee: bool,
+}
+
+impl ComputeBudget {
+	/// Returns the compute budget instructions to prepend to the given instructions, the
+	/// limit may be overridden, e.g. by a batch budgeting compute per operation
+	pub fn instructions(
+		&self,
+		client: &RpcClient,
+		instructions: &[Instruction],
+		unit_limit: Option<u32>,
+	) -> Result<Vec<Instruction>> {
+		let mut budget_instructions = vec![];
+
+		if let Some(unit_limit) = unit_limit.or(self.unit_limit) {
+			budget_instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(unit_limit));
+		}
+
+		let unit_price = if self.auto_priority_fee {
+			Some(estimate_priority_fee(client, instructions)?)
+		} else {
+			self.unit_price
+		};
+
+		if let Some(unit_price) = unit_price {
+			budget_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(unit_price));
+		}
+
+		Ok(budget_instructions)
+	}
+}
+
+/// Estimates the compute unit price from the recent prioritization fees paid by transactions
+/// writing to the same accounts as the given instructions
+pub fn estimate_priority_fee(client: &RpcClient, instructions: &[Instruction]) -> Result<u64> {
+	let mut addresses: Vec<Pubkey> = vec![];
+	for account in instructions.iter().flat_map(|instruction| &instruction.accounts) {
+		if account.is_writable && !addresses.contains(&account.pubkey) {
+			addresses.push(account.pubkey);
+		}
+	}
+	addresses.truncate(MAX_PRIORITIZATION_FEE_ACCOUNTS);
+
+	let mut fees = client
+		.get_recent_prioritization_fees(&addresses)
+		.map_err(|err| anyhow!("Unable to get recent prioritization fees: {}", err))?
+		.into_iter()
+		.map(|fee| fee.prioritization_fee)
+		.collect::<Vec<_>>();
+
+	if fees.is_empty() {
+		return Ok(0);
+	}
+
+	fees.sort_unstable();
+
+	Ok(fees[(fees.len() - 1) * PRIORITY_FEE_PERCENTILE / 100])
+}
@@ -0,0 +1,465 @@
+use {
+	crate::output::{print_json, OutputFormat},
+	anyhow::{anyhow, Result},
+	base64::{engine::general_purpose::STANDARD as BASE64, Engine},
+	sclr_client::decode::{decode_message, DecodedInstruction},
+	serde::Serialize,
+	solana_account_decoder::UiAccountEncoding,
+	solana_client::{
+		rpc_client::RpcClient,
+		rpc_config::{
+			RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+			RpcTransactionConfig,
+		},
+	},
+	solana_program::{program_pack::Pack, pubkey::Pubkey},
+	solana_sdk::{signature::Signature, transaction::VersionedTransaction},
+	solana_transaction_status::{
+		UiLoadedAddresses, UiTransactionEncoding, UiTransactionTokenBalance,
+	},
+	std::str::FromStr,
+};
+
+#[derive(Debug, Serialize)]
+pub struct DecodedAccountOutput {
+	pub name: String,
+	pub label: String,
+	pub address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodedInstructionOutput {
+	pub index: usize,
+	pub name: String,
+	pub args: String,
+	pub accounts: Vec<DecodedAccountOutput>,
+}
+
+impl From<DecodedInstruction> for DecodedInstructionOutput {
+	fn from(decoded: DecodedInstruction) -> Self {
+		DecodedInstructionOutput {
+			index: decoded.index,
+			name: decoded.name,
+			args: format!("{:?}", decoded.instruction),
+			accounts: decoded
+				.accounts
+				.into_iter()
+				.map(|account| DecodedAccountOutput {
+					name: account.name,
+					label: account.label,
+					address: account.address.to_string(),
+				})
+				.collect(),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenBalanceChange {
+	pub account: String,
+	pub mint: String,
+	pub owner: Option<String>,
+	pub decimals: u8,
+	pub pre_balance: u64,
+	pub post_balance: u64,
+}
+
+impl TokenBalanceChange {
+	fn format_change(&self) -> String {
+		let (sign, change) = if self.post_balance >= self.pre_balance {
+			("+", self.post_balance - self.pre_balance)
+		} else {
+			("-", self.pre_balance - self.post_balance)
+		};
+
+		format!(
+			"{}{}",
+			sign,
+			spl_token::amount_to_ui_amount(change, self.decimals)
+		)
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodedTransaction {
+	pub signature: Option<String>,
+	pub slot: Option<u64>,
+	/// Whether the balance changes were simulated rather than executed
+	pub simulated: bool,
+	pub error: Option<String>,
+	pub instructions: Vec<DecodedInstructionOutput>,
+	pub token_balance_changes: Vec<TokenBalanceChange>,
+}
+
+impl DecodedTransaction {
+	pub fn print(&self) {
+		if let Some(signature) = &self.signature {
+			println!("Signature: {}", signature);
+		}
+		if let Some(slot) = self.slot {
+			println!("Slot:      {}", slot);
+		}
+		if let Some(error) = &self.error {
+			println!("Error:     {}", error);
+		}
+
+		if self.instructions.is_empty() {
+			println!("No tokenizer instructions");
+		}
+
+		for instruction in &self.instructions {
+			println!();
+			println!("Instruction #{}: {}", instruction.index, instruction.name);
+			println!("  {}", instruction.args);
+			for (position, account) in instruction.accounts.iter().enumerate() {
+				println!(
+					"  {:>2}. {:<44}  {}",
+					position, account.address, account.label
+				);
+			}
+		}
+
+		println!();
+		if self.simulated {
+			println!("Simulated token balance changes:");
+		} else {
+			println!("Token balance changes:");
+		}
+
+		if self.token_balance_changes.is_empty() {
+			println!("  None");
+		}
+
+		for change in &self.token_balance_changes {
+			println!(
+				"  {:<44}  {:<44}  {:>20}",
+				change.account,
+				change.mint,
+				change.format_change()
+			);
+		}
+	}
+}
+
+/// Decodes the tokenizer instructions of a transaction given by its signature, or as a base64
+/// encoded transaction such as the output of `--dump-transaction`, which is simulated to
+/// determine its token balance changes
+pub fn decode_transaction(client: &RpcClient, input: &str, output: OutputFormat) -> Result<()> {
+	let decoded = match Signature::from_str(input) {
+		Ok(signature) => fetch_transaction(client, &signature)?,
+		Err(_) => simulate_transaction(client, input)?,
+	};
+
+	match output {
+		OutputFormat::Display => decoded.print(),
+		OutputFormat::Json => print_json(&decoded)?,
+	}
+
+	Ok(())
+}
+
+fn decode_instructions(
+	transaction: &VersionedTransaction,
+	loaded_addresses: &[Pubkey],
+) -> Result<Vec<DecodedInstructionOutput>> {
+	Ok(decode_message(&transaction.message, loaded_addresses)
+		.map_err(|err| anyhow!("Unable to decode instructions: {}", err))?
+		.into_iter()
+		.map(DecodedInstructionOutput::from)
+		.collect())
+}
+
+fn parse_pubkeys(addresses: &[String]) -> Result<Vec<Pubkey>> {
+	addresses
+		.iter()
+		.map(|address| {
+			Pubkey::from_str(address).map_err(|err| anyhow!("Invalid address {}: {}", address, err))
+		})
+		.collect()
+}
+
+fn fetch_transaction(client: &RpcClient, signature: &Signature) -> Result<DecodedTransaction> {
+	let confirmed = client
+		.get_transaction_with_config(
+			signature,
+			RpcTransactionConfig {
+				encoding: Some(UiTransactionEncoding::Base64),
+				commitment: Some(client.commitment()),
+				max_supported_transaction_version: Some(0),
+			},
+		)
+		.map_err(|err| anyhow!("Unable to fetch transaction: {}", err))?;
+
+	let transaction = confirmed
+		.transaction
+		.transaction
+		.decode()
+		.ok_or(anyhow!("Unable to decode transaction {}", signature))?;
+	let meta = confirmed
+		.transaction
+		.meta
+		.ok_or(anyhow!("Transaction {} has no status metadata", signature))?;
+
+	// Addresses loaded from lookup tables follow the static keys, writable addresses first
+	let loaded_addresses = match Option::<UiLoadedAddresses>::from(meta.loaded_addresses) {
+		Some(loaded) => {
+			let mut addresses = parse_pubkeys(&loaded.writable)?;
+			addresses.extend(parse_pubkeys(&loaded.readonly)?);
+			addresses
+		}
+		None => vec![],
+	};
+
+	let account_keys = transaction
+		.message
+		.static_account_keys()
+		.iter()
+		.chain(&loaded_addresses)
+		.copied()
+		.collect::<Vec<_>>();
+
+	let pre_balances: Vec<UiTransactionTokenBalance> =
+		Option::from(meta.pre_token_balances).unwrap_or_default();
+	let post_balances: Vec<UiTransactionTokenBalance> =
+		Option::from(meta.post_token_balances).unwrap_or_default();
+
+	// Token accounts created or closed by the transaction only have a post or pre balance
+	let mut account_indexes = pre_balances
+		.iter()
+		.chain(&post_balances)
+		.map(|balance| balance.account_index)
+		.collect::<Vec<_>>();
+	account_indexes.sort_unstable();
+	account_indexes.dedup();
+
+	let mut token_balance_changes = vec![];
+	for account_index in account_indexes {
+		let find = |balances: &[UiTransactionTokenBalance]| {
+			balances
+				.iter()
+				.find(|balance| balance.account_index == account_index)
+				.cloned()
+		};
+		let pre = find(&pre_balances);
+		let post = find(&post_balances);
+		let Some(balance) = post.as_ref().or(pre.as_ref()) else {
+			continue;
+		};
+
+		let parse_amount = |balance: &Option<UiTransactionTokenBalance>| -> Result<u64> {
+			balance
+				.as_ref()
+				.map(|balance| {
+					balance
+						.ui_token_amount
+						.amount
+						.parse::<u64>()
+						.map_err(|err| anyhow!("Invalid token amount: {}", err))
+				})
+				.transpose()
+				.map(Option::unwrap_or_default)
+		};
+		let pre_balance = parse_amount(&pre)?;
+		let post_balance = parse_amount(&post)?;
+
+		if pre_balance == post_balance {
+			continue;
+		}
+
+		token_balance_changes.push(TokenBalanceChange {
+			account: account_keys
+				.get(account_index as usize)
+				.map(Pubkey::to_string)
+				.unwrap_or_default(),
+			mint: balance.mint.clone(),
+			owner: Option::from(balance.owner.clone()),
+			decimals: balance.ui_token_amount.decimals,
+			pre_balance,
+			post_balance,
+		});
+	}
+
+	Ok(DecodedTransaction {
+		signature: Some(signature.to_string()),
+		slot: Some(confirmed.slot),
+		simulated: false,
+		error: meta.err.map(|err| err.to_string()),
+		instructions: decode_instructions(&transaction, &loaded_addresses)?,
+		token_balance_changes,
+	})
+}
+
+fn unpack_token_account(data: &[u8]) -> Option<spl_token::state::Account> {
+	spl_token::state::Account::unpack(data).ok()
+}
+
+fn simulate_transaction(client: &RpcClient, input: &str) -> Result<DecodedTransaction> {
+	let serialized = BASE64
+		.decode(input.trim())
+		.map_err(|_| anyhow!("Input is neither a transaction signature nor base64"))?;
+	let transaction: VersionedTransaction = bincode::deserialize(&serialized)
+		.map_err(|err| anyhow!("Unable to deserialize transaction: {}", err))?;
+
+	// Only the static keys are known without resolving the lookup tables
+	let message = &transaction.message;
+	let writable_keys = message
+		.static_account_keys()
+		.iter()
+		.enumerate()
+		.filter(|(index, _)| message.is_maybe_writable(*index, None))
+		.map(|(_, key)| *key)
+		.collect::<Vec<_>>();
+
+	let pre_accounts = client
+		.get_multiple_accounts(&writable_keys)
+		.map_err(|err| anyhow!("Unable to fetch accounts: {}", err))?;
+
+	let simulation = client
+		.simulate_transaction_with_config(
+			&transaction,
+			RpcSimulateTransactionConfig {
+				sig_verify: false,
+				replace_recent_blockhash: true,
+				commitment: Some(client.commitment()),
+				accounts: Some(RpcSimulateTransactionAccountsConfig {
+					encoding: Some(UiAccountEncoding::Base64),
+					addresses: writable_keys.iter().map(Pubkey::to_string).collect(),
+				}),
+				..RpcSimulateTransactionConfig::default()
+			},
+		)
+		.map_err(|err| anyhow!("Unable to simulate transaction: {}", err))?
+		.value;
+
+	let post_accounts = simulation.accounts.unwrap_or_default();
+
+	let mut token_balance_changes = vec![];
+	for (index, address) in writable_keys.iter().enumerate() {
+		let pre = pre_accounts
+			.get(index)
+			.and_then(Option::as_ref)
+			.filter(|account| account.owner == spl_token::id())
+			.and_then(|account| unpack_token_account(&account.data));
+		let post = post_accounts
+			.get(index)
+			.and_then(Option::as_ref)
+			.filter(|account| account.owner == spl_token::id().to_string())
+			.and_then(|account| account.data.decode())
+			.and_then(|data| unpack_token_account(&data));
+
+		let Some(token_account) = post.or(pre) else {
+			continue;
+		};
+
+		let pre_balance = pre.map(|account| account.amount).unwrap_or_default();
+		let post_balance = post.map(|account| account.amount).unwrap_or_default();
+		if pre_balance == post_balance {
+			continue;
+		}
+
+		let decimals = client
+			.get_token_supply(&token_account.mint)
+			.map(|supply| supply.decimals)
+			.map_err(|err| anyhow!("Unable to fetch mint {}: {}", token_account.mint, err))?;
+
+		token_balance_changes.push(TokenBalanceChange {
+			account: address.to_string(),
+			mint: token_account.mint.to_string(),
+			owner: Some(token_account.owner.to_string()),
+			decimals,
+			pre_balance,
+			post_balance,
+		});
+	}
+
+	Ok(DecodedTransaction {
+		signature: None,
+		slot: None,
+		simulated: true,
+		error: simulation.err.map(|err| err.to_string()),
+		instructions: decode_instructions(&transaction, &[])?,
+		token_balance_changes,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		sclr_client::decode::decode_instruction,
+		sclr_token::instruction::{self, CheckpointAccounts},
+		solana_sdk::{message::Message, transaction::Transaction},
+	};
+
+	fn balance_change(pre_balance: u64, post_balance: u64) -> TokenBalanceChange {
+		TokenBalanceChange {
+			account: Pubkey::new_unique().to_string(),
+			mint: Pubkey::new_unique().to_string(),
+			owner: None,
+			decimals: 6,
+			pre_balance,
+			post_balance,
+		}
+	}
+
+	#[test]
+	fn balance_changes_are_signed() {
+		assert_eq!(balance_change(1_000_000, 3_500_000).format_change(), "+2.5");
+		assert_eq!(balance_change(3_500_000, 1_000_000).format_change(), "-2.5");
+		assert_eq!(balance_change(1, 1).format_change(), "+0");
+	}
+
+	#[test]
+	fn instruction_output_keeps_account_labels() {
+		let tokenizer = Pubkey::new_unique();
+		let stake_pool = Pubkey::new_unique();
+		let checkpoint =
+			instruction::checkpoint(&CheckpointAccounts::resolve(&tokenizer, &stake_pool))
+				.unwrap();
+
+		let output = DecodedInstructionOutput::from(
+			decode_instruction(3, &[tokenizer, stake_pool], &checkpoint.data).unwrap(),
+		);
+
+		assert_eq!(output.index, 3);
+		assert_eq!(output.name, "checkpoint");
+		assert_eq!(output.args, "Checkpoint");
+		assert_eq!(output.accounts.len(), 2);
+		assert_eq!(output.accounts[0].name, "tokenizer");
+		assert_eq!(output.accounts[0].address, tokenizer.to_string());
+		assert_eq!(output.accounts[1].name, "stakePool");
+		assert_eq!(output.accounts[1].address, stake_pool.to_string());
+	}
+
+	#[test]
+	fn dumped_transactions_decode() {
+		let payer = Pubkey::new_unique();
+		let tokenizer = Pubkey::new_unique();
+		let stake_pool = Pubkey::new_unique();
+		let checkpoint =
+			instruction::checkpoint(&CheckpointAccounts::resolve(&tokenizer, &stake_pool))
+				.unwrap();
+
+		// The same encoding as `--dump-transaction`
+		let transaction = Transaction::new_unsigned(Message::new(&[checkpoint], Some(&payer)));
+		let serialized = BASE64.encode(bincode::serialize(&transaction).unwrap());
+		let transaction: VersionedTransaction =
+			bincode::deserialize(&BASE64.decode(serialized).unwrap()).unwrap();
+
+		let instructions = decode_instructions(&transaction, &[]).unwrap();
+		assert_eq!(instructions.len(), 1);
+		assert_eq!(instructions[0].name, "checkpoint");
+	}
+
+	#[test]
+	fn invalid_input_is_rejected_before_simulating() {
+		let client = RpcClient::new_mock("fails".to_string());
+
+		assert_eq!(
+			simulate_transaction(&client, "not a transaction")
+				.unwrap_err()
+				.to_string(),
+			"Input is neither a transaction signature nor base64"
+		);
+	}
+}
@@ -0,0 +1,233 @@
+use {
+	num_traits::FromPrimitive,
+	sclr_token::error::TokenizerError,
+	solana_client::{
+		client_error::{ClientError, ClientErrorKind},
+		rpc_request::{RpcError, RpcResponseErrorData},
+	},
+	solana_sdk::{
+		instruction::InstructionError, message::Message, pubkey::Pubkey,
+		transaction::TransactionError,
+	},
+	std::str::FromStr,
+};
+
+// Returns the program that raised the custom error of an instruction, the innermost program
+// that failed with a custom error in the logs, or the program of the instruction when the
+// logs are not available. Errors of programs invoked by the tokenizer are passed through as
+// the error of the tokenizer instruction
+fn custom_error_program(message: &Message, index: u8, logs: &[String]) -> Option<Pubkey> {
+	logs.iter()
+		.find_map(|log| {
+			let (program_id, _) = log
+				.strip_prefix("Program ")?
+				.split_once(" failed: custom program error")?;
+
+			Pubkey::from_str(program_id).ok()
+		})
+		.or_else(|| message.program_id(index as usize).copied())
+}
+
+/// Formats a transaction error, decoding custom program errors raised by the tokenizer
+/// program into the `TokenizerError` they were raised from
+pub fn format_transaction_error(
+	err: &TransactionError,
+	message: &Message,
+	logs: &[String],
+) -> String {
+	match err {
+		TransactionError::InstructionError(index, InstructionError::Custom(code))
+			if custom_error_program(message, *index, logs) == Some(sclr_token::id()) =>
+		{
+			match TokenizerError::from_u32(*code) {
+				Some(tokenizer_error) => format!(
+					"Instruction {} failed: {} ({:?})",
+					index, tokenizer_error, tokenizer_error
+				),
+				None => err.to_string(),
+			}
+		}
+		_ => err.to_string(),
+	}
+}
+
+/// Formats an RPC client error sending the given message, decoding the transaction error if
+/// there is one and appending the tokenizer program logs of a failed preflight simulation
+pub fn format_client_error(err: &ClientError, transaction_message: &Message) -> String {
+	let simulation_logs = match err.kind() {
+		ClientErrorKind::RpcError(RpcError::RpcResponseError {
+			data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+			..
+		}) => result.logs.as_deref().unwrap_or_default(),
+		_ => &[],
+	};
+
+	let mut message = match err.get_transaction_error() {
+		Some(transaction_error) => {
+			format_transaction_error(&transaction_error, transaction_message, simulation_logs)
+		}
+		None => err.to_string(),
+	};
+
+	let logs = program_logs(simulation_logs);
+
+	if !logs.is_empty() {
+		message.push_str("\nProgram logs:");
+		for log in logs {
+			message.push_str("\n  ");
+			message.push_str(log);
+		}
+	}
+
+	message
+}
+
+/// Returns the log lines emitted while the tokenizer program was executing
+pub fn program_logs(logs: &[String]) -> Vec<&str> {
+	let program_id = sclr_token::id().to_string();
+	let mut depth = 0;
+
+	logs.iter()
+		.filter(|log| {
+			if log.starts_with(&format!("Program {} invoke", program_id)) {
+				depth += 1;
+			}
+
+			let relevant = depth > 0;
+
+			if log.starts_with(&format!("Program {} success", program_id))
+				|| log.starts_with(&format!("Program {} failed", program_id))
+			{
+				depth -= 1;
+			}
+
+			relevant
+		})
+		.map(String::as_str)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		solana_client::rpc_response::RpcSimulateTransactionResult,
+		solana_sdk::{compute_budget::ComputeBudgetInstruction, instruction::Instruction},
+	};
+
+	fn tokenizer_message() -> Message {
+		Message::new(
+			&[
+				ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+				Instruction::new_with_bytes(sclr_token::id(), &[0], vec![]),
+			],
+			None,
+		)
+	}
+
+	fn custom_error(index: u8, code: u32) -> TransactionError {
+		TransactionError::InstructionError(index, InstructionError::Custom(code))
+	}
+
+	fn preflight_failure(err: TransactionError, logs: Vec<String>) -> ClientError {
+		ClientErrorKind::RpcError(RpcError::RpcResponseError {
+			code: -32002,
+			message: "Transaction simulation failed".to_string(),
+			data: RpcResponseErrorData::SendTransactionPreflightFailure(
+				RpcSimulateTransactionResult {
+					err: Some(err),
+					logs: Some(logs),
+					accounts: None,
+					units_consumed: None,
+					return_data: None,
+					inner_instructions: None,
+					replacement_blockhash: None,
+				},
+			),
+		})
+		.into()
+	}
+
+	#[test]
+	fn tokenizer_errors_are_decoded() {
+		let message = tokenizer_message();
+		let code = TokenizerError::VaultNotEmpty as u32;
+
+		assert_eq!(
+			format_transaction_error(&custom_error(1, code), &message, &[]),
+			format!(
+				"Instruction 1 failed: {} (VaultNotEmpty)",
+				TokenizerError::VaultNotEmpty
+			)
+		);
+	}
+
+	#[test]
+	fn errors_of_other_programs_are_not_decoded() {
+		let message = tokenizer_message();
+
+		// Raised by the compute budget instruction
+		let err = custom_error(0, 1);
+		assert_eq!(format_transaction_error(&err, &message, &[]), err.to_string());
+
+		// Raised by spl-token invoked by the tokenizer and passed through as its error
+		let err = custom_error(1, 1);
+		let logs = vec![
+			format!("Program {} invoke [1]", sclr_token::id()),
+			format!("Program {} invoke [2]", spl_token::id()),
+			format!("Program {} failed: custom program error: 0x1", spl_token::id()),
+			format!("Program {} failed: custom program error: 0x1", sclr_token::id()),
+		];
+		assert_eq!(format_transaction_error(&err, &message, &logs), err.to_string());
+
+		let err = TransactionError::InstructionError(1, InstructionError::InvalidAccountData);
+		assert_eq!(format_transaction_error(&err, &message, &[]), err.to_string());
+	}
+
+	#[test]
+	fn client_errors_include_tokenizer_logs() {
+		let message = tokenizer_message();
+		let code = TokenizerError::VaultNotEmpty as u32;
+		let logs = vec![
+			"Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+			"Program ComputeBudget111111111111111111111111111111 success".to_string(),
+			format!("Program {} invoke [1]", sclr_token::id()),
+			"Program log: Instruction: Terminate".to_string(),
+			format!("Program {} failed: custom program error: {:#x}", sclr_token::id(), code),
+		];
+
+		assert_eq!(
+			format_client_error(&preflight_failure(custom_error(1, code), logs.clone()), &message),
+			format!(
+				"Instruction 1 failed: {} (VaultNotEmpty)\nProgram logs:\n  {}\n  {}\n  {}",
+				TokenizerError::VaultNotEmpty,
+				logs[2],
+				logs[3],
+				logs[4]
+			)
+		);
+
+		let err: ClientError = ClientErrorKind::Custom("Connection refused".to_string()).into();
+		assert_eq!(format_client_error(&err, &message), err.to_string());
+	}
+
+	#[test]
+	fn program_logs_follow_nested_invocations() {
+		let other_program = Pubkey::new_unique();
+		let logs = vec![
+			format!("Program {} invoke [1]", other_program),
+			format!("Program {} invoke [2]", sclr_token::id()),
+			format!("Program {} invoke [3]", spl_token::id()),
+			format!("Program {} success", spl_token::id()),
+			format!("Program {} success", sclr_token::id()),
+			format!("Program {} success", other_program),
+			format!("Program {} invoke [1]", sclr_token::id()),
+			format!("Program {} failed: custom program error: 0x0", sclr_token::id()),
+		];
+
+		assert_eq!(
+			program_logs(&logs),
+			[&logs[1], &logs[2], &logs[3], &logs[4], &logs[6], &logs[7]]
+		);
+	}
+}
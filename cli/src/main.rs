@@ -1,28 +1,119 @@
+mod batch;
+mod compute_budget;
+mod decode;
+mod error;
+mod output;
+mod position;
+mod signer;
+
 use {
 	anyhow::{anyhow, Result},
-	clap::{command, Args, Parser, Subcommand},
+	base64::{engine::general_purpose::STANDARD as BASE64, Engine},
+	clap::{Args, Parser, Subcommand, ValueEnum},
+	borsh::BorshDeserialize,
 	sclr_token::{
-		get_principal_mint_address, get_tokenizer_address, get_yield_mint_address, instruction,
+		get_principal_mint_address, get_tokenizer_address, get_yield_mint_address,
+		instruction::{
+			self, CheckpointAccounts, ClaimEscrowYieldAccounts, ClaimYieldAccounts,
+			DepositAndTokenizeAccounts, DepositUnderlyingAccounts, InitializeMintsAccounts,
+			InitializeTokenizerAccounts, RedeemEscrowPrincipalAccounts,
+			RedeemMaturePrincipalAccounts, RedeemPrincipalAndYieldAccounts,
+			ResetYieldIndexAccounts, SettleAccounts, SweepToEscrowAccounts, TerminateAccounts,
+			TerminateMintsAccounts, TerminateTokenizerAccounts, TokenizePrincipalAccounts,
+			TokenizeYieldAccounts,
+		},
+		state::{
+			PostExpiryYieldRecipient, TokenizerState, AUTHORITY_OFFSET, STATE_SIZE,
+			UNDERLYING_MINT_OFFSET,
+		},
 		Expiry,
 	},
-	solana_cli_config,
-	solana_client::rpc_client::RpcClient,
-	solana_program::{instruction::Instruction, pubkey::Pubkey},
+	batch::{process_batch, BatchOptions},
+	compute_budget::ComputeBudget,
+	decode::decode_transaction,
+	error::{format_client_error, format_transaction_error},
+	output::{print_json, print_tokenizer_table, OutputFormat, TokenizerInfo, TokenizerSummary},
+	position::{print_position_table, Position},
+	sclr_client::{
+		position::{token_balances_by_mint, TokenizerStatus},
+		pricing::{apy_to_bps, implied_apy_from_principal_price, years_to_expiry},
+	},
+	signer::{get_nonce_blockhash, parse_presigner, print_sign_only, signer_from_path},
+	solana_cli_config::{self, ConfigInput},
+	solana_account_decoder::UiAccountEncoding,
+	solana_client::{
+		rpc_client::RpcClient,
+		rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+		rpc_filter::{Memcmp, RpcFilterType},
+		rpc_request::TokenAccountsFilter,
+	},
+	solana_program::{
+		hash::Hash, instruction::Instruction, pubkey::Pubkey, system_instruction,
+	},
 	solana_sdk::{
+		account::Account,
 		commitment_config::CommitmentConfig,
-		signature::{read_keypair_file, Signer},
+		signature::{Signature, Signer},
 		transaction::Transaction,
 	},
+	std::path::PathBuf,
 };
 
+// Default number of seconds after expiry before the underlying can be swept to the claims escrow
+const DEFAULT_GRACE_PERIOD: i64 = 2592000;
+
+// Expiry dates are aligned to the start of a day
+const SECONDS_PER_DAY: i64 = 86400;
+
 #[derive(Parser, Debug)]
 struct Cli {
-	#[arg(short, long)]
+	/// Path to the Solana CLI configuration file
+	#[arg(short, long, global = true)]
 	config: Option<String>,
-	#[arg(short, long)]
+	/// RPC endpoint URL or moniker: localhost, devnet, testnet or mainnet-beta
+	#[arg(short, long, global = true)]
 	rpc: Option<String>,
-	#[arg(short, long)]
+	/// Keypair file of the fee payer
+	#[arg(short, long, global = true)]
 	payer: Option<String>,
+	/// Keypair file of the wallet that owns the token accounts, defaults to the fee payer
+	#[arg(short, long, global = true)]
+	owner: Option<String>,
+	/// Output format of commands that display accounts
+	#[arg(long, global = true, value_enum, default_value_t = OutputFormat::Display)]
+	output: OutputFormat,
+	/// Simulate the transaction and print its logs instead of sending it
+	#[arg(long, global = true, conflicts_with = "dump_transaction")]
+	simulate: bool,
+	/// Print the base64 encoded unsigned transaction instead of sending it, the payer and
+	/// owner may be given as pubkeys
+	#[arg(long, global = true)]
+	dump_transaction: bool,
+	/// Sign the transaction offline and print the signatures instead of sending it, the payer
+	/// and owner may be given as pubkeys to be signed for by other parties
+	#[arg(long, global = true, requires = "blockhash")]
+	sign_only: bool,
+	/// Blockhash to sign the transaction with, the nonce blockhash when using a durable nonce
+	#[arg(long, global = true)]
+	blockhash: Option<Hash>,
+	/// Durable nonce account to use in place of a recent blockhash
+	#[arg(long, global = true)]
+	nonce: Option<Pubkey>,
+	/// Keypair file or pubkey of the nonce authority, defaults to the fee payer
+	#[arg(long, global = true, requires = "nonce")]
+	nonce_authority: Option<String>,
+	/// Signature produced by an offline signer as PUBKEY=SIGNATURE
+	#[arg(long = "signer", global = true, value_parser = parse_presigner)]
+	signers: Vec<(Pubkey, Signature)>,
+	/// Compute unit limit of the transaction, the limit of each operation of a batch
+	#[arg(long, global = true)]
+	compute_unit_limit: Option<u32>,
+	/// Compute unit price of the transaction in micro-lamports
+	#[arg(long, global = true)]
+	compute_unit_price: Option<u64>,
+	/// Set the compute unit price from the recent prioritization fees of the written accounts
+	#[arg(long, global = true, conflicts_with_all = ["compute_unit_price", "sign_only"])]
+	auto_priority_fee: bool,
 	#[command(subcommand)]
 	cmd: Commands,
 }
@@ -39,13 +130,22 @@ enum Commands {
 	Terminate(Terminate),
 	#[command(subcommand)]
 	Swap(Swap),
+	Settle(SettleFields),
+	Checkpoint(CheckpointFields),
+	ResetYieldIndex(ResetYieldIndexFields),
+	#[command(subcommand)]
+	Show(Show),
+	List(ListFields),
+	Position(PositionFields),
+	Batch(BatchFields),
+	Decode(DecodeFields),
 }
 
 #[derive(Subcommand, Debug)]
 enum Initialize {
-	Tokenizer(InitializeCommonFields),
+	Tokenizer(InitializeTokenizerFields),
 	Mints(InitializeCommonFields),
-	TokenizerMints(InitializeCommonFields),
+	TokenizerMints(InitializeTokenizerFields),
 	Amm(InitializeCommonFields),
 }
 
@@ -62,6 +162,8 @@ enum Redeem {
 	Principal(InstructionCommonFields),
 	Yield(InstructionCommonFields),
 	PrincipalYield(InstructionCommonFields),
+	EscrowPrincipal(InstructionCommonFields),
+	EscrowYield(InstructionCommonFields),
 }
 
 #[derive(Subcommand, Debug)]
@@ -69,6 +171,12 @@ enum Terminate {
 	Terminate(TerminateCommonFields),
 	TerminateTokenizer(TerminateCommonFields),
 	TerminateMints(TerminateCommonFields),
+	SweepToEscrow(SweepToEscrowFields),
+}
+
+#[derive(Subcommand, Debug)]
+enum Show {
+	Tokenizer(ShowTokenizerFields),
 }
 
 #[derive(Subcommand, Debug)]
@@ -82,6 +190,47 @@ enum Swap {
 struct InitializeCommonFields {
 	underlying_mint_address: Pubkey,
 	expiry: i64,
+	/// Unix timestamp of the expiry date the program derives when the transaction lands,
+	/// required with `--sign-only` as the cluster clock can not be read offline
+	#[arg(long)]
+	expiry_date: Option<i64>,
+}
+
+#[derive(Args, Debug)]
+struct InitializeTokenizerFields {
+	#[command(flatten)]
+	common: InitializeCommonFields,
+	stake_pool_address: Pubkey,
+	#[arg(long, default_value_t = DEFAULT_GRACE_PERIOD)]
+	grace_period: i64,
+	#[arg(long, value_enum, default_value_t = PostExpiryYield::Treasury)]
+	post_expiry_yield: PostExpiryYield,
+	/// Defaults to the wallet initializing the tokenizer
+	#[arg(long)]
+	treasury: Option<Pubkey>,
+	/// Fixed APY of the principal token as a decimal fraction, e.g. 0.05 for 5%, recorded in
+	/// basis points
+	#[arg(long, required_unless_present = "principal_price", conflicts_with = "principal_price")]
+	fixed_apy: Option<f64>,
+	/// Price of the principal token in units of the underlying, the fixed APY is the APY it
+	/// implies until the expiry date. Requires the cluster clock
+	#[arg(long)]
+	principal_price: Option<f64>,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum PostExpiryYield {
+	Treasury,
+	PrincipalHolders,
+}
+
+impl From<PostExpiryYield> for PostExpiryYieldRecipient {
+	fn from(recipient: PostExpiryYield) -> Self {
+		match recipient {
+			PostExpiryYield::Treasury => PostExpiryYieldRecipient::Treasury,
+			PostExpiryYield::PrincipalHolders => PostExpiryYieldRecipient::PrincipalHolders,
+		}
+	}
 }
 
 #[derive(Args, Debug)]
@@ -91,74 +240,464 @@ struct InstructionCommonFields {
 	underlying_mint_address: Pubkey,
 }
 
+#[derive(Args, Debug)]
+#[group(required = true)]
+struct ShowTokenizerFields {
+	#[arg(conflicts_with_all = ["underlying_mint", "expiry_date"])]
+	lysergic_tokenizer_address: Option<Pubkey>,
+	/// Looks up the tokenizer by its underlying mint and expiry date
+	#[arg(long, requires = "expiry_date")]
+	underlying_mint: Option<Pubkey>,
+	/// Unix timestamp of the expiry date
+	#[arg(long, requires = "underlying_mint")]
+	expiry_date: Option<i64>,
+}
+
+#[derive(Args, Debug)]
+struct ListFields {
+	/// Only list tokenizers of the given underlying mint
+	#[arg(long)]
+	underlying_mint: Option<Pubkey>,
+	/// Only list tokenizers created by the given authority
+	#[arg(long)]
+	authority: Option<Pubkey>,
+	#[arg(long, value_enum)]
+	status: Option<ListStatus>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ListStatus {
+	/// Tokenizers whose expiry date has not elapsed
+	Active,
+	/// Tokenizers whose expiry date has elapsed
+	Expired,
+}
+
+#[derive(Args, Debug)]
+struct PositionFields {
+	/// Defaults to the owner wallet
+	wallet: Option<Pubkey>,
+}
+
+#[derive(Args, Debug)]
+struct BatchFields {
+	/// YAML or JSON list of operations, each a command line without the binary name
+	file: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct DecodeFields {
+	/// Signature of a transaction, or a base64 encoded transaction to simulate
+	input: String,
+}
+
 #[derive(Args, Debug)]
 struct TerminateCommonFields {
 	lysergic_tokenizer_address: Pubkey,
 	underlying_mint_address: Pubkey,
 }
 
-fn main() -> Result<()> {
-	let args = Cli::parse();
+#[derive(Args, Debug)]
+struct SweepToEscrowFields {
+	lysergic_tokenizer_address: Pubkey,
+	underlying_mint_address: Pubkey,
+	/// Unix timestamp of the expiry date of the tokenizer, checked against the tokenizer
+	/// address. Read from the tokenizer when omitted, required with `--sign-only`
+	#[arg(long)]
+	expiry_date: Option<i64>,
+	/// Read from the tokenizer when omitted, required with `--sign-only`
+	#[arg(long)]
+	stake_pool: Option<Pubkey>,
+	/// Read from the tokenizer when omitted, required with `--sign-only`
+	#[arg(long)]
+	treasury: Option<Pubkey>,
+}
 
-	let solana_config_file = if let Some(ref config) = *solana_cli_config::CONFIG_FILE {
-		solana_cli_config::Config::load(config).unwrap_or_default()
+#[derive(Args, Debug)]
+struct SettleFields {
+	lysergic_tokenizer_address: Pubkey,
+	underlying_mint_address: Pubkey,
+	/// Read from the tokenizer when omitted, required with `--sign-only`
+	#[arg(long)]
+	stake_pool: Option<Pubkey>,
+	/// Read from the tokenizer when omitted, required with `--sign-only`
+	#[arg(long)]
+	treasury: Option<Pubkey>,
+}
+
+#[derive(Args, Debug)]
+struct CheckpointFields {
+	lysergic_tokenizer_address: Pubkey,
+	/// Read from the tokenizer when omitted, required with `--sign-only`
+	#[arg(long)]
+	stake_pool: Option<Pubkey>,
+}
+
+#[derive(Args, Debug)]
+struct ResetYieldIndexFields {
+	lysergic_tokenizer_address: Pubkey,
+	/// Read from the tokenizer when omitted, required with `--sign-only`
+	#[arg(long)]
+	stake_pool: Option<Pubkey>,
+}
+
+fn get_tokenizer_state(client: &RpcClient, tokenizer_address: &Pubkey) -> Result<TokenizerState> {
+	let data = client
+		.get_account_data(tokenizer_address)
+		.map_err(|err| anyhow!("Unable to fetch tokenizer account: {}", err))?;
+
+	TokenizerState::try_from_slice(&data)
+		.map_err(|err| anyhow!("Unable to deserialize tokenizer state: {}", err))
+}
+
+// Fetches the state of a tokenizer for the accounts that were not given, which can not be
+// read offline
+fn get_missing_tokenizer_state(
+	client: &RpcClient,
+	tokenizer_address: &Pubkey,
+	offline: bool,
+	missing: &str,
+) -> Result<TokenizerState> {
+	if offline {
+		return Err(anyhow!("{} required to sign offline", missing));
+	}
+
+	get_tokenizer_state(client, tokenizer_address)
+}
+
+// Returns the expiry date of a tokenizer initialized now, the expiry date given for an
+// offline transaction must be one the program can derive from the expiry
+fn resolve_expiry_date(
+	expiry: &Expiry,
+	expiry_date: Option<i64>,
+	timestamp: Option<i64>,
+) -> Result<i64> {
+	let derived_expiry_date = timestamp.and_then(|timestamp| expiry.to_expiry_date(timestamp));
+
+	match (expiry_date, derived_expiry_date) {
+		(Some(expiry_date), _) if expiry_date % SECONDS_PER_DAY != 0 => Err(anyhow!(
+			"The expiry date {} is not the start of a day",
+			expiry_date
+		)),
+		(Some(expiry_date), Some(derived_expiry_date)) if expiry_date != derived_expiry_date => {
+			Err(anyhow!(
+				"The expiry date {} does not match the expiry date {} derived from the cluster clock",
+				expiry_date,
+				derived_expiry_date
+			))
+		}
+		(Some(expiry_date), _) => Ok(expiry_date),
+		(None, Some(derived_expiry_date)) => Ok(derived_expiry_date),
+		(None, None) => Err(anyhow!(
+			"An `--expiry-date` is required to initialize a tokenizer offline"
+		)),
+	}
+}
+
+// Returns the fixed APY of a tokenizer in basis points, given directly or implied by the
+// price of the principal token at the cluster time
+fn resolve_fixed_apy(
+	fixed_apy: Option<f64>,
+	principal_price: Option<f64>,
+	expiry_date: i64,
+	timestamp: Option<i64>,
+) -> Result<u64> {
+	let apy = match (fixed_apy, principal_price) {
+		(Some(fixed_apy), _) => fixed_apy,
+		(None, Some(principal_price)) => {
+			let timestamp = timestamp.ok_or(anyhow!(
+				"A `--fixed-apy` is required to initialize a tokenizer offline"
+			))?;
+
+			implied_apy_from_principal_price(
+				principal_price,
+				years_to_expiry(expiry_date, timestamp),
+			)
+			.ok_or(anyhow!(
+				"Unable to imply an APY from the principal price {}",
+				principal_price
+			))?
+		}
+		(None, None) => {
+			return Err(anyhow!("A `--fixed-apy` or `--principal-price` is required"));
+		}
+	};
+
+	apy_to_bps(apy)
+		.and_then(|bps| u64::try_from(bps).ok())
+		.ok_or(anyhow!("The fixed APY {} is negative or out of range", apy))
+}
+
+// Returns the block time of the current slot
+fn get_cluster_timestamp(client: &RpcClient) -> Result<i64> {
+	let slot = client
+		.get_slot()
+		.map_err(|err| anyhow!("Unable to get slot: {}", err))?;
+
+	client
+		.get_block_time(slot)
+		.map_err(|err| anyhow!("Unable to get block time: {}", err))
+}
+
+fn show_tokenizer(
+	client: &RpcClient,
+	fields: &ShowTokenizerFields,
+	output: OutputFormat,
+	timestamp: i64,
+) -> Result<()> {
+	let tokenizer_address = match (
+		fields.lysergic_tokenizer_address,
+		fields.underlying_mint,
+		fields.expiry_date,
+	) {
+		(Some(address), _, _) => address,
+		(None, Some(underlying_mint), Some(expiry_date)) => {
+			get_tokenizer_address(&underlying_mint, expiry_date).0
+		}
+		_ => {
+			return Err(anyhow!(
+				"A tokenizer address or an underlying mint and expiry date are required"
+			))
+		}
+	};
+
+	let tokenizer_state = get_tokenizer_state(client, &tokenizer_address)?;
+	let tokenizer_info =
+		TokenizerInfo::fetch(client, &tokenizer_address, &tokenizer_state, timestamp);
+
+	match output {
+		OutputFormat::Display => tokenizer_info.print(),
+		OutputFormat::Json => print_json(&tokenizer_info)?,
+	}
+
+	Ok(())
+}
+
+fn get_program_accounts(
+	client: &RpcClient,
+	program_id: &Pubkey,
+	filters: Vec<RpcFilterType>,
+) -> Result<Vec<(Pubkey, Account)>> {
+	client
+		.get_program_accounts_with_config(
+			program_id,
+			RpcProgramAccountsConfig {
+				filters: Some(filters),
+				account_config: RpcAccountInfoConfig {
+					encoding: Some(UiAccountEncoding::Base64),
+					..RpcAccountInfoConfig::default()
+				},
+				..RpcProgramAccountsConfig::default()
+			},
+		)
+		.map_err(|err| anyhow!("Unable to fetch program accounts: {}", err))
+}
+
+// Fetches every tokenizer matching the filters, in addition to the tokenizer state size
+fn get_tokenizers(
+	client: &RpcClient,
+	mut filters: Vec<RpcFilterType>,
+) -> Result<Vec<(Pubkey, TokenizerState)>> {
+	filters.push(RpcFilterType::DataSize(STATE_SIZE as u64));
+
+	Ok(get_program_accounts(client, &sclr_token::id(), filters)?
+		.into_iter()
+		.filter_map(|(address, account)| {
+			TokenizerState::try_from_slice(&account.data)
+				.ok()
+				.map(|state| (address, state))
+		})
+		.collect())
+}
+
+// Returns instructions creating the owner's associated token accounts for the given mints
+// that do not exist yet, the accounts cannot be checked when signing offline so an idempotent
+// instruction is returned for every mint
+fn create_missing_token_accounts(
+	client: &RpcClient,
+	payer: &Pubkey,
+	owner: &Pubkey,
+	mints: &[Pubkey],
+	offline: bool,
+) -> Result<Vec<Instruction>> {
+	let addresses = mints
+		.iter()
+		.map(|mint| spl_associated_token_account::get_associated_token_address(owner, mint))
+		.collect::<Vec<_>>();
+
+	let accounts = if offline {
+		vec![None; addresses.len()]
 	} else {
-		solana_cli_config::Config::default()
+		client
+			.get_multiple_accounts(&addresses)
+			.map_err(|err| anyhow!("Unable to fetch token accounts: {}", err))?
 	};
 
-	let wallet_keypair = read_keypair_file(&solana_config_file.keypair_path)
-		.map_err(|err| anyhow!("Unable to read keypair file: {}", err))?;
-	let wallet_pubkey = wallet_keypair.pubkey();
+	Ok(mints
+		.iter()
+		.zip(accounts)
+		.filter(|(_, account)| account.is_none())
+		.map(|(mint, _)| {
+			spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+				payer,
+				owner,
+				mint,
+				&spl_token::id(),
+			)
+		})
+		.collect())
+}
 
-	let client = RpcClient::new_with_commitment(
-		solana_config_file.json_rpc_url.to_string(),
-		CommitmentConfig::confirmed(),
-	);
+fn list_tokenizers(
+	client: &RpcClient,
+	fields: &ListFields,
+	output: OutputFormat,
+	timestamp: i64,
+) -> Result<()> {
+	let mut filters = vec![];
+
+	if let Some(underlying_mint) = fields.underlying_mint {
+		filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+			UNDERLYING_MINT_OFFSET,
+			underlying_mint.as_ref(),
+		)));
+	}
+
+	if let Some(authority) = fields.authority {
+		filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+			AUTHORITY_OFFSET,
+			authority.as_ref(),
+		)));
+	}
+
+	// The expiry date is compared against the cluster time so status is filtered locally
+	let mut tokenizers = get_tokenizers(client, filters)?
+		.iter()
+		.map(|(address, state)| TokenizerSummary::new(address, state, timestamp))
+		.filter(|tokenizer| match fields.status {
+			Some(ListStatus::Active) => tokenizer.status == TokenizerStatus::Active,
+			Some(ListStatus::Expired) => tokenizer.status != TokenizerStatus::Active,
+			None => true,
+		})
+		.collect::<Vec<_>>();
+
+	tokenizers.sort_by(|a, b| {
+		a.underlying_mint
+			.cmp(&b.underlying_mint)
+			.then(a.expiry_date.cmp(&b.expiry_date))
+	});
+
+	match output {
+		OutputFormat::Display => print_tokenizer_table(&tokenizers),
+		OutputFormat::Json => print_json(&tokenizers)?,
+	}
+
+	Ok(())
+}
+
+fn show_position(
+	client: &RpcClient,
+	wallet: &Pubkey,
+	output: OutputFormat,
+	timestamp: i64,
+) -> Result<()> {
+	let token_accounts = client
+		.get_token_accounts_by_owner(wallet, TokenAccountsFilter::ProgramId(spl_token::id()))
+		.map_err(|err| anyhow!("Unable to fetch token accounts: {}", err))?;
+	let balances = token_balances_by_mint(&token_accounts);
+
+	let mut positions = vec![];
+	for (address, state) in get_tokenizers(client, vec![])? {
+		let principal_balance = balances
+			.get(&get_principal_mint_address(&address).0)
+			.copied()
+			.unwrap_or_default();
+		let yield_balance = balances
+			.get(&get_yield_mint_address(&address).0)
+			.copied()
+			.unwrap_or_default();
+
+		if principal_balance == 0 && yield_balance == 0 {
+			continue;
+		}
+
+		positions.push(Position::fetch(
+			client,
+			&address,
+			&state,
+			principal_balance,
+			yield_balance,
+			timestamp,
+		)?);
+	}
+
+	positions.sort_by_key(|position| position.seconds_to_maturity);
+
+	match output {
+		OutputFormat::Display => print_position_table(&positions),
+		OutputFormat::Json => print_json(&positions)?,
+	}
+
+	Ok(())
+}
+
+// Encodes a transaction as base64 of its wire format, as read by `sclr decode`
+fn encode_transaction(transaction: &Transaction) -> Result<String> {
+	let serialized = bincode::serialize(transaction)
+		.map_err(|err| anyhow!("Unable to serialize transaction: {}", err))?;
 
-	let slot = client.get_slot()?;
-	let timestamp = client.get_block_time(slot)? as i64;
+	Ok(BASE64.encode(serialized))
+}
 
-	let instruction: Instruction = match args.cmd {
+// Builds the instructions of a command. The addresses it derives are printed to stderr so that
+// stdout only carries the output of the command, e.g. the JSON of a batch
+fn build_instructions(
+	client: &RpcClient,
+	cmd: Commands,
+	payer_pubkey: Pubkey,
+	wallet_pubkey: Pubkey,
+	timestamp: Option<i64>,
+	offline: bool,
+) -> Result<Vec<Instruction>> {
+	let mut setup_instructions = vec![];
+
+	let instruction: Instruction = match cmd {
 		Commands::Init(init) => match init {
-			Initialize::Tokenizer(common_fields) => {
+			Initialize::Tokenizer(tokenizer_fields) => {
+				let common_fields = tokenizer_fields.common;
 				let expiry = Expiry::from_i64(common_fields.expiry).map_err(|err| {
 					anyhow!("Unable to parse the given value to `Expiry`: {}", err)
 				})?;
 
-				let expiry_date = expiry.to_expiry_date(timestamp).expect("Invalid");
+				let expiry_date =
+					resolve_expiry_date(&expiry, common_fields.expiry_date, timestamp)?;
 
-				let (lysergic_tokenizer_address, _) =
-					get_tokenizer_address(&common_fields.underlying_mint_address, expiry_date);
-
-				let underlying_vault_address =
-					spl_associated_token_account::get_associated_token_address(
-						&lysergic_tokenizer_address,
-						&common_fields.underlying_mint_address,
-					);
-
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&lysergic_tokenizer_address);
-
-				let (yield_mint_address, _) = get_yield_mint_address(&lysergic_tokenizer_address);
+				let accounts = InitializeTokenizerAccounts::resolve(
+					&common_fields.underlying_mint_address,
+					expiry_date,
+					&wallet_pubkey,
+					&tokenizer_fields.stake_pool_address,
+				);
 
-				//TODO: Calculation methodology for the fixed APY of the principal token
-				//NOTE: placeholder
-				let fixed_apy = 0;
+				let fixed_apy = resolve_fixed_apy(
+					tokenizer_fields.fixed_apy,
+					tokenizer_fields.principal_price,
+					expiry_date,
+					timestamp,
+				)?;
 
-				println!("Tokenizer Address: {}", lysergic_tokenizer_address);
-				println!("Vault Address: {}", underlying_vault_address);
-				println!("Principal Mint Address: {}", principal_mint_address);
-				println!("Yield Mint Address: {}", yield_mint_address);
+				eprintln!("Tokenizer Address: {}", accounts.tokenizer);
+				eprintln!("Vault Address: {}", accounts.underlying_vault);
+				eprintln!("Principal Mint Address: {}", accounts.principal_token_mint);
+				eprintln!("Yield Mint Address: {}", accounts.yield_token_mint);
 
 				instruction::init_tokenizer(
-					&lysergic_tokenizer_address,
-					&wallet_pubkey,
-					&underlying_vault_address,
-					&common_fields.underlying_mint_address,
-					&principal_mint_address,
-					&yield_mint_address,
+					&accounts,
+					&tokenizer_fields.treasury.unwrap_or(wallet_pubkey),
 					expiry,
 					fixed_apy,
+					tokenizer_fields.grace_period,
+					tokenizer_fields.post_expiry_yield.into(),
 				)
 				.map_err(|err| anyhow!("Unable to create init instruction: {}", err))?
 			}
@@ -167,69 +706,56 @@ fn main() -> Result<()> {
 					anyhow!("Unable to parse the given value to `Expiry`: {}", err)
 				})?;
 
-				let expiry_date = expiry
-					.to_expiry_date(timestamp)
-					.expect("Unable to convert expiry to expiry date");
-
-				let (lysergic_tokenizer_address, _) =
-					get_tokenizer_address(&common_fields.underlying_mint_address, expiry_date);
+				let expiry_date =
+					resolve_expiry_date(&expiry, common_fields.expiry_date, timestamp)?;
 
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&lysergic_tokenizer_address);
-
-				let (yield_mint_address, _) = get_yield_mint_address(&lysergic_tokenizer_address);
+				let accounts = InitializeMintsAccounts::resolve(
+					&common_fields.underlying_mint_address,
+					expiry_date,
+					&wallet_pubkey,
+				);
 
-				println!("Principal Mint Address: {}", principal_mint_address);
-				println!("Yield Mint Address: {}", yield_mint_address);
+				eprintln!("Principal Mint Address: {}", accounts.principal_token_mint);
+				eprintln!("Yield Mint Address: {}", accounts.yield_token_mint);
 
-				instruction::init_mints(
-					&lysergic_tokenizer_address,
-					&wallet_pubkey,
-					&common_fields.underlying_mint_address,
-					&principal_mint_address,
-					&yield_mint_address,
-					expiry,
-				)
-				.map_err(|err| anyhow!("Unable to create `Initialize` instruction: {}", err))?
+				instruction::init_mints(&accounts, expiry)
+					.map_err(|err| anyhow!("Unable to create `Initialize` instruction: {}", err))?
 			}
-			Initialize::TokenizerMints(common_fields) => {
+			Initialize::TokenizerMints(tokenizer_fields) => {
+				let common_fields = tokenizer_fields.common;
 				let expiry = Expiry::from_i64(common_fields.expiry).map_err(|err| {
 					anyhow!("Unable to parse the given value to `Expiry`: {}", err)
 				})?;
 
-				let expiry_date = expiry.to_expiry_date(timestamp).expect("Invalid");
-				let (lysergic_tokenizer_address, _) =
-					get_tokenizer_address(&common_fields.underlying_mint_address, expiry_date);
-
-				let underlying_vault_address =
-					spl_associated_token_account::get_associated_token_address(
-						&lysergic_tokenizer_address,
-						&common_fields.underlying_mint_address,
-					);
-
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&lysergic_tokenizer_address);
+				let expiry_date =
+					resolve_expiry_date(&expiry, common_fields.expiry_date, timestamp)?;
 
-				let (yield_mint_address, _) = get_yield_mint_address(&lysergic_tokenizer_address);
+				let accounts = InitializeTokenizerAccounts::resolve(
+					&common_fields.underlying_mint_address,
+					expiry_date,
+					&wallet_pubkey,
+					&tokenizer_fields.stake_pool_address,
+				);
 
-				//TODO: Calculation methodology for the fixed APY of the principal token
-				//NOTE: placeholder
-				let fixed_apy = 0;
+				let fixed_apy = resolve_fixed_apy(
+					tokenizer_fields.fixed_apy,
+					tokenizer_fields.principal_price,
+					expiry_date,
+					timestamp,
+				)?;
 
-				println!("Tokenizer Address: {}", lysergic_tokenizer_address);
-				println!("Vault Address: {}", underlying_vault_address);
-				println!("Principal Mint Address: {}", principal_mint_address);
-				println!("Yield Mint Address: {}", yield_mint_address);
+				eprintln!("Tokenizer Address: {}", accounts.tokenizer);
+				eprintln!("Vault Address: {}", accounts.underlying_vault);
+				eprintln!("Principal Mint Address: {}", accounts.principal_token_mint);
+				eprintln!("Yield Mint Address: {}", accounts.yield_token_mint);
 
 				instruction::init_tokenizer_and_mints(
-					&lysergic_tokenizer_address,
-					&wallet_pubkey,
-					&underlying_vault_address,
-					&common_fields.underlying_mint_address,
-					&principal_mint_address,
-					&yield_mint_address,
-					Expiry::from_i64(common_fields.expiry)?,
+					&accounts,
+					&tokenizer_fields.treasury.unwrap_or(wallet_pubkey),
+					expiry,
 					fixed_apy,
+					tokenizer_fields.grace_period,
+					tokenizer_fields.post_expiry_yield.into(),
 				)
 				.map_err(|err| {
 					anyhow!(
@@ -242,273 +768,356 @@ fn main() -> Result<()> {
 		},
 		Commands::Tokenize(tokenize) => match tokenize {
 			Tokenize::Deposit(common_fields) => {
-				let underlying_vault = spl_associated_token_account::get_associated_token_address(
-					&common_fields.lysergic_tokenizer_address,
-					&common_fields.underlying_mint_address,
-				);
+				let tokenizer_state =
+					get_tokenizer_state(client, &common_fields.lysergic_tokenizer_address)?;
 
-				instruction::deposit_underlying(
+				let accounts = DepositUnderlyingAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
+					&tokenizer_state,
 					&wallet_pubkey,
-					&underlying_vault,
-					&common_fields.underlying_mint_address,
-					common_fields.amount,
-				)
-				.map_err(|err| anyhow!("Unable to create `Deposit` instruction: {}", err))?
+				);
+
+				instruction::deposit_underlying(&accounts, common_fields.amount)
+					.map_err(|err| anyhow!("Unable to create `Deposit` instruction: {}", err))?
 			}
 			Tokenize::Principal(common_fields) => {
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&common_fields.lysergic_tokenizer_address);
+				let tokenizer_state =
+					get_tokenizer_state(client, &common_fields.lysergic_tokenizer_address)?;
 
-				let user_principal_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&principal_mint_address,
-					);
-
-				instruction::tokenize_principal(
+				let accounts = TokenizePrincipalAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
-					&principal_mint_address,
+					&tokenizer_state,
 					&wallet_pubkey,
-					&user_principal_token_address,
-					common_fields.amount,
-				)
-				.map_err(|err| {
+				);
+
+				setup_instructions.extend(create_missing_token_accounts(
+					client,
+					&payer_pubkey,
+					&wallet_pubkey,
+					&[accounts.principal_token_mint],
+					offline,
+				)?);
+
+				instruction::tokenize_principal(&accounts, common_fields.amount).map_err(|err| {
 					anyhow!("Unable to create `TokenizePrincipal` instruction: {}", err)
 				})?
 			}
 			Tokenize::Yield(common_fields) => {
-				let (yield_mint_address, _) =
-					get_yield_mint_address(&common_fields.lysergic_tokenizer_address);
-
-				let user_yield_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&yield_mint_address,
-					);
+				let tokenizer_state =
+					get_tokenizer_state(client, &common_fields.lysergic_tokenizer_address)?;
 
-				instruction::tokenize_yield(
+				let accounts = TokenizeYieldAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
-					&yield_mint_address,
+					&tokenizer_state,
 					&wallet_pubkey,
-					&user_yield_token_address,
-					common_fields.amount,
-				)
-				.map_err(|err| anyhow!("Unable to create `TokenizeYield` instruction: {}", err))?
-			}
-			Tokenize::PrincipalYield(common_fields) => {
-				let underlying_vault = spl_associated_token_account::get_associated_token_address(
-					&common_fields.lysergic_tokenizer_address,
-					&common_fields.underlying_mint_address,
 				);
 
-				println!("Underlying vault: {}", underlying_vault);
-
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&common_fields.lysergic_tokenizer_address);
-
-				let (yield_mint_address, _) =
-					get_yield_mint_address(&common_fields.lysergic_tokenizer_address);
+				setup_instructions.extend(create_missing_token_accounts(
+					client,
+					&payer_pubkey,
+					&wallet_pubkey,
+					&[accounts.yield_token_mint],
+					offline,
+				)?);
 
-				let user_underlying_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&common_fields.underlying_mint_address,
-					);
+				instruction::tokenize_yield(&accounts, common_fields.amount)
+					.map_err(|err| anyhow!("Unable to create `TokenizeYield` instruction: {}", err))?
+			}
+			Tokenize::PrincipalYield(common_fields) => {
+				let tokenizer_state =
+					get_tokenizer_state(client, &common_fields.lysergic_tokenizer_address)?;
 
-				let user_principal_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&principal_mint_address,
-					);
+				let accounts = DepositAndTokenizeAccounts::resolve(
+					&common_fields.lysergic_tokenizer_address,
+					&tokenizer_state,
+					&wallet_pubkey,
+				);
 
-				let user_yield_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&yield_mint_address,
-					);
+				eprintln!("Underlying vault: {}", accounts.underlying_vault);
 
-				instruction::deposit_and_tokenize(
-					&common_fields.lysergic_tokenizer_address,
-					&underlying_vault,
-					&principal_mint_address,
-					&yield_mint_address,
+				setup_instructions.extend(create_missing_token_accounts(
+					client,
+					&payer_pubkey,
 					&wallet_pubkey,
-					&user_underlying_token_address,
-					&user_principal_token_address,
-					&user_yield_token_address,
-					common_fields.amount,
-				)
-				.map_err(|err| {
+					&[accounts.principal_token_mint, accounts.yield_token_mint],
+					offline,
+				)?);
+
+				instruction::deposit_and_tokenize(&accounts, common_fields.amount).map_err(|err| {
 					anyhow!("Unable to create `DepositAndTokenize` instruction: {}", err)
 				})?
 			}
 		},
 		Commands::Redeem(redeem) => match redeem {
 			Redeem::Principal(common_fields) => {
-				let underlying_vault_address =
-					spl_associated_token_account::get_associated_token_address(
-						&common_fields.lysergic_tokenizer_address,
-						&common_fields.underlying_mint_address,
-					);
-
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&common_fields.lysergic_tokenizer_address);
-
-				let user_underlying_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&common_fields.underlying_mint_address,
-					);
-
-				let user_principal_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&principal_mint_address,
-					);
-
-				instruction::redeem_mature_principal(
+				let tokenizer_state =
+					get_tokenizer_state(client, &common_fields.lysergic_tokenizer_address)?;
+
+				let accounts = RedeemMaturePrincipalAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
-					&underlying_vault_address,
-					&common_fields.underlying_mint_address,
-					&principal_mint_address,
+					&tokenizer_state,
 					&wallet_pubkey,
-					&user_underlying_token_address,
-					&user_principal_token_address,
-					common_fields.amount,
-				)
-				.map_err(|err| {
-					anyhow!(
-						"Unable to create `RedeemPrincipalOnly` instruction: {}",
-						err
-					)
-				})?
+				);
+
+				setup_instructions.extend(create_missing_token_accounts(
+					client,
+					&payer_pubkey,
+					&wallet_pubkey,
+					&[accounts.underlying_mint],
+					offline,
+				)?);
+
+				instruction::redeem_mature_principal(&accounts, common_fields.amount).map_err(
+					|err| {
+						anyhow!(
+							"Unable to create `RedeemPrincipalOnly` instruction: {}",
+							err
+						)
+					},
+				)?
 			}
 			Redeem::Yield(common_fields) => {
-				let (yield_mint_address, _) =
-					get_yield_mint_address(&common_fields.lysergic_tokenizer_address);
-
-				let user_underlying_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&common_fields.underlying_mint_address,
-					);
-
-				let user_yield_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&yield_mint_address,
-					);
-
-				instruction::claim_yield(
+				let tokenizer_state =
+					get_tokenizer_state(client, &common_fields.lysergic_tokenizer_address)?;
+
+				let accounts = ClaimYieldAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
-					&common_fields.underlying_mint_address,
-					&yield_mint_address,
+					&tokenizer_state,
 					&wallet_pubkey,
-					&user_underlying_token_address,
-					&user_yield_token_address,
-					common_fields.amount,
-				)
-				.map_err(|err| anyhow!("Unable to create `ClaimYield` instruction: {}", err))?
+				);
+
+				setup_instructions.extend(create_missing_token_accounts(
+					client,
+					&payer_pubkey,
+					&wallet_pubkey,
+					&[accounts.underlying_mint],
+					offline,
+				)?);
+
+				instruction::claim_yield(&accounts, common_fields.amount)
+					.map_err(|err| anyhow!("Unable to create `ClaimYield` instruction: {}", err))?
 			}
 			Redeem::PrincipalYield(common_fields) => {
-				let underlying_vault_address =
-					spl_associated_token_account::get_associated_token_address(
-						&common_fields.lysergic_tokenizer_address,
-						&common_fields.underlying_mint_address,
-					);
-
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&common_fields.lysergic_tokenizer_address);
-
-				let (yield_mint_address, _) =
-					get_yield_mint_address(&common_fields.lysergic_tokenizer_address);
-
-				let user_underlying_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&common_fields.underlying_mint_address,
-					);
-
-				let user_principal_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&principal_mint_address,
-					);
-
-				let user_yield_token_address =
-					spl_associated_token_account::get_associated_token_address(
-						&wallet_pubkey,
-						&yield_mint_address,
-					);
-
-				instruction::redeem_principal_and_yield(
+				let tokenizer_state =
+					get_tokenizer_state(client, &common_fields.lysergic_tokenizer_address)?;
+
+				let accounts = RedeemPrincipalAndYieldAccounts::resolve(
+					&common_fields.lysergic_tokenizer_address,
+					&tokenizer_state,
+					&wallet_pubkey,
+				);
+
+				setup_instructions.extend(create_missing_token_accounts(
+					client,
+					&payer_pubkey,
+					&wallet_pubkey,
+					&[accounts.underlying_mint],
+					offline,
+				)?);
+
+				instruction::redeem_principal_and_yield(&accounts, common_fields.amount).map_err(
+					|err| {
+						anyhow!(
+							"Unable to create `RedeemPrincipalAndYield` instruction: {}",
+							err
+						)
+					},
+				)?
+			}
+			Redeem::EscrowPrincipal(common_fields) => {
+				let accounts = RedeemEscrowPrincipalAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
-					&underlying_vault_address,
 					&common_fields.underlying_mint_address,
-					&principal_mint_address,
-					&yield_mint_address,
 					&wallet_pubkey,
-					&user_underlying_token_address,
-					&user_principal_token_address,
-					&user_yield_token_address,
-					common_fields.amount,
-				)
-				.map_err(|err| {
-					anyhow!(
-						"Unable to create `RedeemPrincipalAndYield` instruction: {}",
-						err
-					)
+				);
+
+				setup_instructions.extend(create_missing_token_accounts(
+					client,
+					&payer_pubkey,
+					&wallet_pubkey,
+					&[common_fields.underlying_mint_address],
+					offline,
+				)?);
+
+				instruction::redeem_escrow_principal(&accounts, common_fields.amount).map_err(
+					|err| {
+						anyhow!(
+							"Unable to create `RedeemEscrowPrincipal` instruction: {}",
+							err
+						)
+					},
+				)?
+			}
+			Redeem::EscrowYield(common_fields) => {
+				let accounts = ClaimEscrowYieldAccounts::resolve(
+					&common_fields.lysergic_tokenizer_address,
+					&common_fields.underlying_mint_address,
+					&wallet_pubkey,
+				);
+
+				setup_instructions.extend(create_missing_token_accounts(
+					client,
+					&payer_pubkey,
+					&wallet_pubkey,
+					&[common_fields.underlying_mint_address],
+					offline,
+				)?);
+
+				instruction::claim_escrow_yield(&accounts, common_fields.amount).map_err(|err| {
+					anyhow!("Unable to create `ClaimEscrowYield` instruction: {}", err)
 				})?
 			}
 		},
 		Commands::Terminate(terminate) => match terminate {
 			Terminate::Terminate(common_fields) => {
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&common_fields.lysergic_tokenizer_address);
-				let (yield_mint_address, _) =
-					get_yield_mint_address(&common_fields.lysergic_tokenizer_address);
-				instruction::terminate(
+				instruction::terminate(&TerminateAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
+					&common_fields.underlying_mint_address,
 					&wallet_pubkey,
-					&spl_associated_token_account::get_associated_token_address(
-						&common_fields.lysergic_tokenizer_address,
-						&common_fields.underlying_mint_address,
-					),
-					&spl_associated_token_account::get_associated_token_address(
-						&common_fields.lysergic_tokenizer_address,
-						&principal_mint_address,
-					),
-					&spl_associated_token_account::get_associated_token_address(
-						&common_fields.lysergic_tokenizer_address,
-						&yield_mint_address,
-					),
-				)
+				))
 				.map_err(|err| anyhow!("Unable to create `Terminate` instruction: {}", err))?
 			}
-			Terminate::TerminateTokenizer(common_fields) => instruction::terminate_tokenizer(
-				&common_fields.lysergic_tokenizer_address,
-				&wallet_pubkey,
-				&spl_associated_token_account::get_associated_token_address(
+			Terminate::TerminateTokenizer(common_fields) => {
+				instruction::terminate_tokenizer(&TerminateTokenizerAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
 					&common_fields.underlying_mint_address,
-				),
-			)
-			.map_err(|err| anyhow!("Unable to create `TerminateTokenizer` instruction: {}", err))?,
+					&wallet_pubkey,
+				))
+				.map_err(|err| {
+					anyhow!("Unable to create `TerminateTokenizer` instruction: {}", err)
+				})?
+			}
 			Terminate::TerminateMints(common_fields) => {
-				let (principal_mint_address, _) =
-					get_principal_mint_address(&common_fields.lysergic_tokenizer_address);
-				let (yield_mint_address, _) =
-					get_yield_mint_address(&common_fields.lysergic_tokenizer_address);
-				instruction::terminate_mints(
+				instruction::terminate_mints(&TerminateMintsAccounts::resolve(
 					&common_fields.lysergic_tokenizer_address,
 					&wallet_pubkey,
-					&principal_mint_address,
-					&yield_mint_address,
-				)
+				))
 				.map_err(|err| anyhow!("Unable to create `TerminateMints` instruction: {}", err))?
 			}
+			Terminate::SweepToEscrow(fields) => {
+				let (expiry_date, stake_pool, treasury) =
+					match (fields.expiry_date, fields.stake_pool, fields.treasury) {
+						(Some(expiry_date), Some(stake_pool), Some(treasury)) => {
+							(expiry_date, stake_pool, treasury)
+						}
+						(expiry_date, stake_pool, treasury) => {
+							let tokenizer_state = get_missing_tokenizer_state(
+								client,
+								&fields.lysergic_tokenizer_address,
+								offline,
+								"An `--expiry-date`, `--stake-pool` and `--treasury` are",
+							)?;
+
+							(
+								expiry_date.unwrap_or(tokenizer_state.expiry_date),
+								stake_pool.unwrap_or(tokenizer_state.stake_pool),
+								treasury.unwrap_or(tokenizer_state.treasury),
+							)
+						}
+					};
+
+				// The tokenizer address is derived from its underlying mint and expiry date, which
+				// catches an underlying mint that is not the tokenizer's before it is signed
+				if get_tokenizer_address(&fields.underlying_mint_address, expiry_date).0
+					!= fields.lysergic_tokenizer_address
+				{
+					return Err(anyhow!(
+						"The tokenizer {} is not derived from the underlying mint {} and expiry date {}",
+						fields.lysergic_tokenizer_address,
+						fields.underlying_mint_address,
+						expiry_date
+					));
+				}
+
+				let accounts = SweepToEscrowAccounts::resolve(
+					&fields.lysergic_tokenizer_address,
+					&fields.underlying_mint_address,
+					&wallet_pubkey,
+					&stake_pool,
+					&treasury,
+				);
+
+				eprintln!("Escrow Address: {}", accounts.escrow);
+				eprintln!("Escrow Vault Address: {}", accounts.escrow_vault);
+
+				instruction::sweep_to_escrow(&accounts)
+					.map_err(|err| anyhow!("Unable to create `SweepToEscrow` instruction: {}", err))?
+			}
 		},
+		Commands::Settle(fields) => {
+			let (stake_pool, treasury) = match (fields.stake_pool, fields.treasury) {
+				(Some(stake_pool), Some(treasury)) => (stake_pool, treasury),
+				(stake_pool, treasury) => {
+					let tokenizer_state = get_missing_tokenizer_state(
+						client,
+						&fields.lysergic_tokenizer_address,
+						offline,
+						"A `--stake-pool` and `--treasury` are",
+					)?;
+
+					(
+						stake_pool.unwrap_or(tokenizer_state.stake_pool),
+						treasury.unwrap_or(tokenizer_state.treasury),
+					)
+				}
+			};
+
+			instruction::settle(&SettleAccounts::resolve(
+				&fields.lysergic_tokenizer_address,
+				&fields.underlying_mint_address,
+				&stake_pool,
+				&treasury,
+			))
+			.map_err(|err| anyhow!("Unable to create `Settle` instruction: {}", err))?
+		}
+		Commands::Checkpoint(fields) => {
+			let stake_pool = match fields.stake_pool {
+				Some(stake_pool) => stake_pool,
+				None => {
+					get_missing_tokenizer_state(
+						client,
+						&fields.lysergic_tokenizer_address,
+						offline,
+						"A `--stake-pool` is",
+					)?
+					.stake_pool
+				}
+			};
+
+			instruction::checkpoint(&CheckpointAccounts::resolve(
+				&fields.lysergic_tokenizer_address,
+				&stake_pool,
+			))
+			.map_err(|err| anyhow!("Unable to create `Checkpoint` instruction: {}", err))?
+		}
+		Commands::ResetYieldIndex(fields) => {
+			let stake_pool = match fields.stake_pool {
+				Some(stake_pool) => stake_pool,
+				None => {
+					get_missing_tokenizer_state(
+						client,
+						&fields.lysergic_tokenizer_address,
+						offline,
+						"A `--stake-pool` is",
+					)?
+					.stake_pool
+				}
+			};
+
+			instruction::reset_yield_index(&ResetYieldIndexAccounts::resolve(
+				&fields.lysergic_tokenizer_address,
+				&wallet_pubkey,
+				&stake_pool,
+			))
+			.map_err(|err| anyhow!("Unable to create `ResetYieldIndex` instruction: {}", err))?
+		}
+		Commands::Show(_)
+		| Commands::List(_)
+		| Commands::Position(_)
+		| Commands::Batch(_)
+		| Commands::Decode(_) => {
+			return Err(anyhow!("The command does not create a transaction"));
+		}
 		Commands::Swap(swap) => match swap {
 			Swap::Principal(_common_fields) => unimplemented!(),
 			Swap::Yield(_common_fields) => unimplemented!(),
@@ -516,15 +1125,435 @@ fn main() -> Result<()> {
 		},
 	};
 
-	let mut transaction = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
-	let latest_blockchash = client
-		.get_latest_blockhash()
-		.map_err(|err| anyhow!("Unable to get latest blockhash: {}", err))?;
+	setup_instructions.push(instruction);
+
+	Ok(setup_instructions)
+}
+
+fn main() -> Result<()> {
+	let args = Cli::parse();
+
+	let solana_config_file = if let Some(ref config) = args.config {
+		solana_cli_config::Config::load(config)
+			.map_err(|err| anyhow!("Unable to load config file {}: {}", config, err))?
+	} else if let Some(ref config) = *solana_cli_config::CONFIG_FILE {
+		solana_cli_config::Config::load(config).unwrap_or_default()
+	} else {
+		solana_cli_config::Config::default()
+	};
+
+	// Command line flags take precedence over the config file, monikers are resolved to URLs
+	let (_, json_rpc_url) = ConfigInput::compute_json_rpc_url_setting(
+		args.rpc.as_deref().unwrap_or_default(),
+		&solana_config_file.json_rpc_url,
+	);
+
+	let client = RpcClient::new_with_commitment(json_rpc_url, CommitmentConfig::confirmed());
+
+	// Commands that only read accounts do not require a keypair
+	match args.cmd {
+		Commands::Show(Show::Tokenizer(ref fields)) => {
+			let timestamp = get_cluster_timestamp(&client)?;
+			return show_tokenizer(&client, fields, args.output, timestamp);
+		}
+		Commands::List(ref fields) => {
+			let timestamp = get_cluster_timestamp(&client)?;
+			return list_tokenizers(&client, fields, args.output, timestamp);
+		}
+		Commands::Decode(ref fields) => {
+			return decode_transaction(&client, &fields.input, args.output);
+		}
+		_ => {}
+	}
+
+	let (_, payer_keypair_path) = ConfigInput::compute_keypair_path_setting(
+		args.payer.as_deref().unwrap_or_default(),
+		&solana_config_file.keypair_path,
+	);
+
+	if let Commands::Position(ref fields) = args.cmd {
+		let wallet = match fields.wallet {
+			Some(wallet) => wallet,
+			None => signer_from_path(
+				args.owner.as_deref().unwrap_or(&payer_keypair_path),
+				&args.signers,
+				true,
+			)?
+			.pubkey(),
+		};
+
+		let timestamp = get_cluster_timestamp(&client)?;
+		return show_position(&client, &wallet, args.output, timestamp);
+	}
+
+	// Signers given as pubkeys are left absent when the transaction is signed offline or
+	// dumped unsigned
+	let allow_absent_signers = args.sign_only || args.dump_transaction;
+
+	let payer_keypair =
+		signer_from_path(&payer_keypair_path, &args.signers, allow_absent_signers)?;
+	let payer_pubkey = payer_keypair.pubkey();
+
+	let wallet_keypair = signer_from_path(
+		args.owner.as_deref().unwrap_or(&payer_keypair_path),
+		&args.signers,
+		allow_absent_signers,
+	)?;
+	let wallet_pubkey = wallet_keypair.pubkey();
+
+	let nonce_authority = args
+		.nonce_authority
+		.as_deref()
+		.map(|path| signer_from_path(path, &args.signers, allow_absent_signers))
+		.transpose()?;
+
+	// The cluster may not be reachable when signing offline
+	let timestamp = if args.sign_only {
+		None
+	} else {
+		Some(get_cluster_timestamp(&client)?)
+	};
+
+	let compute_budget = ComputeBudget {
+		unit_limit: args.compute_unit_limit,
+		unit_price: args.compute_unit_price,
+		auto_priority_fee: args.auto_priority_fee,
+	};
+
+	if let Commands::Batch(ref fields) = args.cmd {
+		if args.sign_only || args.dump_transaction || args.nonce.is_some() {
+			return Err(anyhow!(
+				"Batches can not be signed offline, dumped or use a durable nonce"
+			));
+		}
+
+		return process_batch(
+			&client,
+			&fields.file,
+			payer_keypair.as_ref(),
+			wallet_keypair.as_ref(),
+			timestamp,
+			&BatchOptions {
+				compute_budget,
+				simulate: args.simulate,
+				output: args.output,
+			},
+		);
+	}
+
+	let mut instructions = build_instructions(
+		&client,
+		args.cmd,
+		payer_pubkey,
+		wallet_pubkey,
+		timestamp,
+		args.sign_only,
+	)?;
+
+	let budget_instructions = compute_budget.instructions(&client, &instructions, None)?;
+	instructions.splice(0..0, budget_instructions);
+
+	// A durable nonce transaction must advance the nonce in its first instruction
+	if let Some(nonce) = args.nonce {
+		let nonce_authority_pubkey = nonce_authority
+			.as_ref()
+			.map(|signer| signer.pubkey())
+			.unwrap_or(payer_pubkey);
+
+		instructions.insert(
+			0,
+			system_instruction::advance_nonce_account(&nonce, &nonce_authority_pubkey),
+		);
+	}
+
+	let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer_pubkey));
+	let latest_blockchash = match (args.blockhash, args.nonce) {
+		(Some(blockhash), _) => blockhash,
+		(None, Some(nonce)) => get_nonce_blockhash(&client, &nonce)?,
+		(None, None) => client
+			.get_latest_blockhash()
+			.map_err(|err| anyhow!("Unable to get latest blockhash: {}", err))?,
+	};
+
+	// The unsigned transaction is printed for review by the signers, e.g. of a multisig. It is
+	// the only line on stdout so that it can be piped to a decoder or signer
+	if args.dump_transaction {
+		transaction.message.recent_blockhash = latest_blockchash;
+		println!("{}", encode_transaction(&transaction)?);
+
+		return Ok(());
+	}
+
+	let mut signers: Vec<&dyn Signer> = vec![payer_keypair.as_ref()];
+	for signer in [Some(&wallet_keypair), nonce_authority.as_ref()].into_iter().flatten() {
+		if signers.iter().all(|existing| existing.pubkey() != signer.pubkey()) {
+			signers.push(signer.as_ref());
+		}
+	}
+
+	if args.sign_only {
+		transaction
+			.try_partial_sign(&signers, latest_blockchash)
+			.map_err(|err| anyhow!("Unable to sign transaction: {}", err))?;
+		print_sign_only(&transaction);
+
+		return Ok(());
+	}
+
+	transaction
+		.try_sign(&signers, latest_blockchash)
+		.map_err(|err| anyhow!("Unable to sign transaction: {}", err))?;
+
+	if args.simulate {
+		let result = client
+			.simulate_transaction(&transaction)
+			.map_err(|err| anyhow!("Unable to simulate transaction: {}", err))?
+			.value;
+
+		let logs = result.logs.unwrap_or_default();
+		for log in &logs {
+			println!("{}", log);
+		}
+
+		if let Some(units_consumed) = result.units_consumed {
+			println!("Compute units consumed: {}", units_consumed);
+		}
+
+		return match result.err {
+			Some(err) => Err(anyhow!(
+				"Simulation failed: {}",
+				format_transaction_error(&err, &transaction.message, &logs)
+			)),
+			None => {
+				println!("Simulation succeeded");
+				Ok(())
+			}
+		};
+	}
 
-	transaction.sign(&[&wallet_keypair], latest_blockchash);
 	client
 		.send_and_confirm_transaction_with_spinner(&transaction)
-		.map_err(|err| anyhow!("Unable to send transaction: {}", err))?;
+		.map_err(|err| {
+			anyhow!(
+				"Unable to send transaction: {}",
+				format_client_error(&err, &transaction.message)
+			)
+		})?;
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Builds the instructions of a command offline against a client whose requests all fail
+	fn build_offline(args: &[&str], wallet_pubkey: &Pubkey) -> Result<Vec<Instruction>> {
+		let cli = Cli::try_parse_from([&["sclr"], args].concat()).unwrap();
+		let client = RpcClient::new_mock("fails".to_string());
+
+		build_instructions(&client, cli.cmd, *wallet_pubkey, *wallet_pubkey, None, true)
+	}
+
+	#[test]
+	fn dumped_transaction_is_a_single_base64_line() {
+		let payer = Pubkey::new_unique();
+		let tokenizer = Pubkey::new_unique();
+		let underlying_mint = Pubkey::new_unique();
+		let instructions = build_offline(
+			&["terminate", "terminate", &tokenizer.to_string(), &underlying_mint.to_string()],
+			&payer,
+		)
+		.unwrap();
+		let transaction = Transaction::new_with_payer(&instructions, Some(&payer));
+
+		let dumped = encode_transaction(&transaction).unwrap();
+		assert_eq!(dumped.lines().count(), 1);
+
+		let decoded: Transaction = bincode::deserialize(&BASE64.decode(&dumped).unwrap()).unwrap();
+		assert_eq!(decoded, transaction);
+	}
+
+	#[test]
+	fn fixed_apy_is_recorded_in_bps() {
+		let expiry_date = 1_735_689_600;
+		let year = 31_536_000;
+
+		assert_eq!(resolve_fixed_apy(Some(0.05), None, expiry_date, None).unwrap(), 500);
+		assert_eq!(resolve_fixed_apy(Some(0.0), None, expiry_date, None).unwrap(), 0);
+		assert_eq!(
+			resolve_fixed_apy(None, Some(1.0 / 1.05), expiry_date, Some(expiry_date - year))
+				.unwrap(),
+			500
+		);
+
+		assert_eq!(
+			resolve_fixed_apy(None, Some(0.95), expiry_date, None)
+				.unwrap_err()
+				.to_string(),
+			"A `--fixed-apy` is required to initialize a tokenizer offline"
+		);
+		assert!(resolve_fixed_apy(Some(-0.01), None, expiry_date, None).is_err());
+		assert!(resolve_fixed_apy(Some(f64::NAN), None, expiry_date, None).is_err());
+		// A principal token can not be priced once expired
+		assert!(resolve_fixed_apy(None, Some(0.95), expiry_date, Some(expiry_date)).is_err());
+	}
+
+	#[test]
+	fn terminate_resolves_accounts_offline() {
+		let tokenizer = Pubkey::new_unique();
+		let underlying_mint = Pubkey::new_unique();
+		let authority = Pubkey::new_unique();
+		let tokenizer_arg = tokenizer.to_string();
+		let underlying_mint_arg = underlying_mint.to_string();
+
+		let instructions = build_offline(
+			&["terminate", "terminate", &tokenizer_arg, &underlying_mint_arg],
+			&authority,
+		)
+		.unwrap();
+		assert_eq!(
+			instructions,
+			vec![instruction::terminate(&TerminateAccounts::resolve(
+				&tokenizer,
+				&underlying_mint,
+				&authority
+			))
+			.unwrap()]
+		);
+
+		let instructions = build_offline(
+			&["terminate", "terminate-mints", &tokenizer_arg, &underlying_mint_arg],
+			&authority,
+		)
+		.unwrap();
+		assert_eq!(
+			instructions,
+			vec![instruction::terminate_mints(&TerminateMintsAccounts::resolve(
+				&tokenizer,
+				&authority
+			))
+			.unwrap()]
+		);
+	}
+
+	#[test]
+	fn sweep_to_escrow_resolves_accounts_offline() {
+		let underlying_mint = Pubkey::new_unique();
+		let expiry_date = 1_735_689_600;
+		let (tokenizer, _) = get_tokenizer_address(&underlying_mint, expiry_date);
+		let stake_pool = Pubkey::new_unique();
+		let treasury = Pubkey::new_unique();
+		let authority = Pubkey::new_unique();
+		let tokenizer_arg = tokenizer.to_string();
+		let underlying_mint_arg = underlying_mint.to_string();
+		let expiry_date_arg = expiry_date.to_string();
+		let stake_pool_arg = stake_pool.to_string();
+		let treasury_arg = treasury.to_string();
+		let mut args = vec![
+			"terminate",
+			"sweep-to-escrow",
+			&tokenizer_arg,
+			&underlying_mint_arg,
+			"--expiry-date",
+			&expiry_date_arg,
+			"--stake-pool",
+			&stake_pool_arg,
+			"--treasury",
+			&treasury_arg,
+		];
+
+		assert_eq!(
+			build_offline(&args, &authority).unwrap(),
+			vec![instruction::sweep_to_escrow(&SweepToEscrowAccounts::resolve(
+				&tokenizer,
+				&underlying_mint,
+				&authority,
+				&stake_pool,
+				&treasury
+			))
+			.unwrap()]
+		);
+
+		let error = build_offline(&args[..8], &authority).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"An `--expiry-date`, `--stake-pool` and `--treasury` are required to sign offline"
+		);
+
+		// An underlying mint that does not derive the tokenizer address
+		let other_mint_arg = Pubkey::new_unique().to_string();
+		args[3] = &other_mint_arg;
+		assert!(build_offline(&args, &authority).is_err());
+	}
+
+	#[test]
+	fn settle_requires_stake_pool_and_treasury_offline() {
+		let tokenizer = Pubkey::new_unique();
+		let underlying_mint = Pubkey::new_unique();
+		let stake_pool = Pubkey::new_unique();
+		let treasury = Pubkey::new_unique();
+		let wallet = Pubkey::new_unique();
+		let tokenizer_arg = tokenizer.to_string();
+		let underlying_mint_arg = underlying_mint.to_string();
+		let stake_pool_arg = stake_pool.to_string();
+
+		let error = build_offline(
+			&["settle", &tokenizer_arg, &underlying_mint_arg, "--stake-pool", &stake_pool_arg],
+			&wallet,
+		)
+		.unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"A `--stake-pool` and `--treasury` are required to sign offline"
+		);
+
+		let instructions = build_offline(
+			&[
+				"settle",
+				&tokenizer_arg,
+				&underlying_mint_arg,
+				"--stake-pool",
+				&stake_pool_arg,
+				"--treasury",
+				&treasury.to_string(),
+			],
+			&wallet,
+		)
+		.unwrap();
+		assert_eq!(
+			instructions,
+			vec![instruction::settle(&SettleAccounts::resolve(
+				&tokenizer,
+				&underlying_mint,
+				&stake_pool,
+				&treasury
+			))
+			.unwrap()]
+		);
+	}
+
+	#[test]
+	fn checkpoint_requires_stake_pool_offline() {
+		let tokenizer = Pubkey::new_unique();
+		let stake_pool = Pubkey::new_unique();
+		let wallet = Pubkey::new_unique();
+		let tokenizer_arg = tokenizer.to_string();
+
+		let error = build_offline(&["checkpoint", &tokenizer_arg], &wallet).unwrap_err();
+		assert_eq!(error.to_string(), "A `--stake-pool` is required to sign offline");
+
+		let instructions = build_offline(
+			&["checkpoint", &tokenizer_arg, "--stake-pool", &stake_pool.to_string()],
+			&wallet,
+		)
+		.unwrap();
+		assert_eq!(
+			instructions,
+			vec![
+				instruction::checkpoint(&CheckpointAccounts::resolve(&tokenizer, &stake_pool))
+					.unwrap()
+			]
+		);
+	}
+}
@@ -0,0 +1,200 @@
+use {
+	anyhow::{anyhow, Result},
+	chrono::DateTime,
+	clap::ValueEnum,
+	sclr_client::position::TokenizerStatus,
+	sclr_token::state::{PostExpiryYieldRecipient, TokenizerState},
+	serde::Serialize,
+	solana_client::rpc_client::RpcClient,
+	solana_program::{program_pack::Pack, pubkey::Pubkey},
+};
+
+// Decimals of the principal, yield and underlying tokens
+const TOKEN_DECIMALS: u8 = 6;
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum OutputFormat {
+	#[default]
+	Display,
+	Json,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenizerInfo {
+	pub address: String,
+	pub authority: String,
+	pub underlying_mint: String,
+	pub principal_token_mint: String,
+	pub yield_token_mint: String,
+	pub underlying_vault: String,
+	pub stake_pool: String,
+	pub treasury: String,
+	pub vault_balance: Option<u64>,
+	pub principal_supply: Option<u64>,
+	pub yield_supply: Option<u64>,
+	pub expiry_date: i64,
+	pub expiry: String,
+	pub seconds_to_maturity: i64,
+	pub grace_period: i64,
+	pub fixed_apy: u64,
+	pub post_expiry_yield: String,
+	pub yield_index: String,
+	pub last_update_timestamp: i64,
+	pub status: TokenizerStatus,
+}
+
+impl TokenizerInfo {
+	/// Collects the tokenizer state along with the vault balance and token supplies, the
+	/// balances are left empty for accounts that have not been created
+	pub fn fetch(
+		client: &RpcClient,
+		address: &Pubkey,
+		state: &TokenizerState,
+		timestamp: i64,
+	) -> Self {
+		let vault_balance = client
+			.get_account_data(&state.underlying_vault)
+			.ok()
+			.and_then(|data| spl_token::state::Account::unpack(&data).ok())
+			.map(|account| account.amount);
+
+		let get_supply = |mint: &Pubkey| {
+			client
+				.get_account_data(mint)
+				.ok()
+				.and_then(|data| spl_token::state::Mint::unpack(&data).ok())
+				.map(|mint| mint.supply)
+		};
+
+		TokenizerInfo {
+			address: address.to_string(),
+			authority: state.authority.to_string(),
+			underlying_mint: state.underlying_mint.to_string(),
+			principal_token_mint: state.principal_token_mint.to_string(),
+			yield_token_mint: state.yield_token_mint.to_string(),
+			underlying_vault: state.underlying_vault.to_string(),
+			stake_pool: state.stake_pool.to_string(),
+			treasury: state.treasury.to_string(),
+			vault_balance,
+			principal_supply: get_supply(&state.principal_token_mint),
+			yield_supply: get_supply(&state.yield_token_mint),
+			expiry_date: state.expiry_date,
+			expiry: format_timestamp(state.expiry_date),
+			seconds_to_maturity: state.expiry_date.saturating_sub(timestamp).max(0),
+			grace_period: state.grace_period,
+			fixed_apy: state.fixed_apy,
+			post_expiry_yield: match state.post_expiry_yield {
+				PostExpiryYieldRecipient::Treasury => "Treasury".to_string(),
+				PostExpiryYieldRecipient::PrincipalHolders => "PrincipalHolders".to_string(),
+			},
+			yield_index: state.yield_index.to_string(),
+			last_update_timestamp: state.last_update_timestamp,
+			status: TokenizerStatus::from_state(state, timestamp),
+		}
+	}
+
+	pub fn print(&self) {
+		println!("Tokenizer:            {}", self.address);
+		println!("Authority:            {}", self.authority);
+		println!("Underlying mint:      {}", self.underlying_mint);
+		println!("Principal token mint: {}", self.principal_token_mint);
+		println!("Yield token mint:     {}", self.yield_token_mint);
+		println!("Underlying vault:     {}", self.underlying_vault);
+		println!("Stake pool:           {}", self.stake_pool);
+		println!("Treasury:             {}", self.treasury);
+		println!("Vault balance:        {}", format_amount(self.vault_balance));
+		println!("Principal supply:     {}", format_amount(self.principal_supply));
+		println!("Yield supply:         {}", format_amount(self.yield_supply));
+		println!("Expiry:               {}", self.expiry);
+		println!("Time to maturity:     {}", format_duration(self.seconds_to_maturity));
+		println!("Grace period:         {}", format_duration(self.grace_period));
+		println!("Post expiry yield:    {}", self.post_expiry_yield);
+		println!("Status:               {:?}", self.status);
+	}
+}
+
+/// Prints a value as pretty printed JSON
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+	let json = serde_json::to_string_pretty(value)
+		.map_err(|err| anyhow!("Unable to serialize output: {}", err))?;
+	println!("{}", json);
+
+	Ok(())
+}
+
+/// Formats a unix timestamp as a UTC date
+pub fn format_timestamp(timestamp: i64) -> String {
+	DateTime::from_timestamp(timestamp, 0)
+		.map(|date| date.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+		.unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Formats a number of seconds as days, hours and minutes
+pub fn format_duration(seconds: i64) -> String {
+	if seconds <= 0 {
+		return "0m".to_string();
+	}
+
+	let days = seconds / 86400;
+	let hours = (seconds % 86400) / 3600;
+	let minutes = (seconds % 3600) / 60;
+
+	if days > 0 {
+		format!("{}d {}h {}m", days, hours, minutes)
+	} else if hours > 0 {
+		format!("{}h {}m", hours, minutes)
+	} else {
+		format!("{}m", minutes)
+	}
+}
+
+/// Formats a token amount in UI units, or a placeholder if the account does not exist
+pub fn format_amount(amount: Option<u64>) -> String {
+	amount
+		.map(|amount| spl_token::amount_to_ui_amount(amount, TOKEN_DECIMALS).to_string())
+		.unwrap_or_else(|| "-".to_string())
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenizerSummary {
+	pub address: String,
+	pub authority: String,
+	pub underlying_mint: String,
+	pub principal_token_mint: String,
+	pub yield_token_mint: String,
+	pub stake_pool: String,
+	pub expiry_date: i64,
+	pub expiry: String,
+	pub status: TokenizerStatus,
+}
+
+impl TokenizerSummary {
+	pub fn new(address: &Pubkey, state: &TokenizerState, timestamp: i64) -> Self {
+		TokenizerSummary {
+			address: address.to_string(),
+			authority: state.authority.to_string(),
+			underlying_mint: state.underlying_mint.to_string(),
+			principal_token_mint: state.principal_token_mint.to_string(),
+			yield_token_mint: state.yield_token_mint.to_string(),
+			stake_pool: state.stake_pool.to_string(),
+			expiry_date: state.expiry_date,
+			expiry: format_timestamp(state.expiry_date),
+			status: TokenizerStatus::from_state(state, timestamp),
+		}
+	}
+}
+
+/// Prints the tokenizers as a table, one row per tokenizer
+pub fn print_tokenizer_table(tokenizers: &[TokenizerSummary]) {
+	println!(
+		"{:<44}  {:<44}  {:<23}  Status",
+		"Tokenizer", "Underlying mint", "Expiry"
+	);
+
+	for tokenizer in tokenizers {
+		println!(
+			"{:<44}  {:<44}  {:<23}  {:?}",
+			tokenizer.address, tokenizer.underlying_mint, tokenizer.expiry, tokenizer.status
+		);
+	}
+}
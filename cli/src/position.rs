@@ -0,0 +1,79 @@
+use {
+	crate::output::{format_amount, format_duration},
+	anyhow::{anyhow, Result},
+	sclr_client::position::{redeemable_amounts, TokenizerBalances, TokenizerStatus},
+	sclr_token::state::TokenizerState,
+	serde::Serialize,
+	solana_client::rpc_client::RpcClient,
+	solana_program::pubkey::Pubkey,
+};
+
+#[derive(Debug, Serialize)]
+pub struct Position {
+	pub tokenizer: String,
+	pub underlying_mint: String,
+	pub principal_balance: u64,
+	pub yield_balance: u64,
+	/// Underlying received for the principal tokens, before expiry only principal paired
+	/// with yield tokens can be redeemed
+	pub redeemable_principal: u64,
+	/// Underlying received for claiming the yield tokens
+	pub claimable_yield: u64,
+	pub seconds_to_maturity: i64,
+	pub status: TokenizerStatus,
+}
+
+impl Position {
+	/// Calculates the underlying the wallet would receive if it redeemed its principal and
+	/// yield tokens now, mirroring the calculations of the tokenizer program
+	pub fn fetch(
+		client: &RpcClient,
+		address: &Pubkey,
+		state: &TokenizerState,
+		principal_balance: u64,
+		yield_balance: u64,
+		timestamp: i64,
+	) -> Result<Self> {
+		let accounts = client
+			.get_multiple_accounts(&TokenizerBalances::addresses(address, state))
+			.map_err(|err| anyhow!("Unable to fetch tokenizer accounts: {}", err))?;
+		let balances = TokenizerBalances::from_accounts(state, &accounts)
+			.map_err(|err| anyhow!("Unable to read tokenizer accounts: {}", err))?;
+
+		let redeemable =
+			redeemable_amounts(state, &balances, principal_balance, yield_balance, timestamp)
+				.map_err(|err| anyhow!("Unable to calculate redeemable amount: {}", err))?;
+
+		Ok(Position {
+			tokenizer: address.to_string(),
+			underlying_mint: state.underlying_mint.to_string(),
+			principal_balance,
+			yield_balance,
+			redeemable_principal: redeemable.principal,
+			claimable_yield: redeemable.yield_claim,
+			seconds_to_maturity: state.expiry_date.saturating_sub(timestamp).max(0),
+			status: TokenizerStatus::from_state(state, timestamp),
+		})
+	}
+}
+
+/// Prints the positions as a table, one row per tokenizer
+pub fn print_position_table(positions: &[Position]) {
+	println!(
+		"{:<44}  {:>14}  {:>14}  {:>14}  {:>14}  {:<12}  Status",
+		"Tokenizer", "Principal", "Yield", "Redeemable", "Claimable", "Maturity"
+	);
+
+	for position in positions {
+		println!(
+			"{:<44}  {:>14}  {:>14}  {:>14}  {:>14}  {:<12}  {:?}",
+			position.tokenizer,
+			format_amount(Some(position.principal_balance)),
+			format_amount(Some(position.yield_balance)),
+			format_amount(Some(position.redeemable_principal)),
+			format_amount(Some(position.claimable_yield)),
+			format_duration(position.seconds_to_maturity),
+			position.status
+		);
+	}
+}
@@ -0,0 +1,96 @@
+use {
+	anyhow::{anyhow, Result},
+	solana_client::rpc_client::RpcClient,
+	solana_program::{hash::Hash, pubkey::Pubkey},
+	solana_sdk::{
+		nonce::state::{State, Versions},
+		signature::{read_keypair_file, Signature, Signer},
+		signer::{null_signer::NullSigner, presigner::Presigner},
+		transaction::Transaction,
+	},
+	std::str::FromStr,
+};
+
+/// Parses a `PUBKEY=SIGNATURE` pair produced by a `--sign-only` invocation
+pub fn parse_presigner(value: &str) -> Result<(Pubkey, Signature), String> {
+	let (pubkey, signature) = value
+		.split_once('=')
+		.ok_or_else(|| "Expected PUBKEY=SIGNATURE".to_string())?;
+
+	Ok((
+		Pubkey::from_str(pubkey).map_err(|err| format!("Invalid pubkey: {}", err))?,
+		Signature::from_str(signature).map_err(|err| format!("Invalid signature: {}", err))?,
+	))
+}
+
+/// Resolves a signer from either a keypair file or a pubkey. A pubkey is resolved to the
+/// matching presigner, or when the transaction is not sent to a signer that is left absent
+pub fn signer_from_path(
+	path: &str,
+	presigners: &[(Pubkey, Signature)],
+	allow_absent: bool,
+) -> Result<Box<dyn Signer>> {
+	if let Ok(pubkey) = Pubkey::from_str(path) {
+		if let Some((_, signature)) = presigners.iter().find(|(signer, _)| signer == &pubkey) {
+			return Ok(Box::new(Presigner::new(&pubkey, signature)));
+		}
+
+		if allow_absent {
+			return Ok(Box::new(NullSigner::new(&pubkey)));
+		}
+
+		return Err(anyhow!("No signature provided for signer {}", pubkey));
+	}
+
+	let keypair = read_keypair_file(path)
+		.map_err(|err| anyhow!("Unable to read keypair file {}: {}", path, err))?;
+
+	Ok(Box::new(keypair))
+}
+
+/// Returns the blockhash stored in a durable nonce account
+pub fn get_nonce_blockhash(client: &RpcClient, nonce_address: &Pubkey) -> Result<Hash> {
+	let account = client
+		.get_account(nonce_address)
+		.map_err(|err| anyhow!("Unable to fetch nonce account: {}", err))?;
+
+	let versions: Versions = bincode::deserialize(&account.data)
+		.map_err(|err| anyhow!("Unable to deserialize nonce account: {}", err))?;
+
+	match versions.state() {
+		State::Initialized(data) => Ok(data.blockhash()),
+		State::Uninitialized => Err(anyhow!(
+			"Nonce account {} is not initialized",
+			nonce_address
+		)),
+	}
+}
+
+/// Prints the signatures of a partially signed transaction in the `PUBKEY=SIGNATURE` form
+/// accepted by `--signer`, followed by the signers whose signatures are still absent
+pub fn print_sign_only(transaction: &Transaction) {
+	let signer_keys = &transaction.message.account_keys
+		[..transaction.message.header.num_required_signatures as usize];
+
+	println!("Blockhash: {}", transaction.message.recent_blockhash);
+
+	println!("Signers (Pubkey=Signature):");
+	for (pubkey, signature) in signer_keys.iter().zip(&transaction.signatures) {
+		if signature != &Signature::default() {
+			println!("  {}={}", pubkey, signature);
+		}
+	}
+
+	let absent_signers = signer_keys
+		.iter()
+		.zip(&transaction.signatures)
+		.filter(|(_, signature)| *signature == &Signature::default())
+		.collect::<Vec<_>>();
+
+	if !absent_signers.is_empty() {
+		println!("Absent Signers (Pubkey):");
+		for (pubkey, _) in absent_signers {
+			println!("  {}", pubkey);
+		}
+	}
+}
@@ -0,0 +1,278 @@
+use {
+	sclr_token::{
+		get_escrow_address,
+		instruction::{
+			self, CheckpointAccounts, ClaimEscrowYieldAccounts, ClaimYieldAccounts,
+			DepositAndTokenizeAccounts, RedeemEscrowPrincipalAccounts,
+			RedeemMaturePrincipalAccounts, RedeemPrincipalAndYieldAccounts,
+			ResetYieldIndexAccounts, SettleAccounts, SweepToEscrowAccounts,
+			TokenizePrincipalAccounts, TokenizeYieldAccounts,
+		},
+		state::{ClaimsEscrowState, TokenizerState},
+	},
+	solana_program::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+	spl_associated_token_account::{
+		get_associated_token_address, instruction::create_associated_token_account_idempotent,
+	},
+};
+
+/// The accounts of a tokenizer, resolved from its address and state by the account
+/// constructors of the tokenizer program
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenizerAccounts {
+	pub tokenizer: Pubkey,
+	/// The state of the tokenizer, including the stake pool required by every instruction
+	/// that reads the exchange rate
+	pub state: TokenizerState,
+}
+
+/// The accounts of the claims escrow of a tokenizer, derived from the tokenizer address and
+/// underlying mint so that they resolve once the tokenizer has been terminated
+#[derive(Clone, Debug, PartialEq)]
+pub struct EscrowAccounts {
+	pub tokenizer: Pubkey,
+	pub underlying_mint: Pubkey,
+}
+
+/// The associated token accounts of a wallet for the tokens of a tokenizer
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserTokenAccounts {
+	pub owner: Pubkey,
+	pub underlying: Pubkey,
+	pub principal: Pubkey,
+	pub yield_token: Pubkey,
+}
+
+// Creates the owner's associated token accounts for the given mints if they do not exist
+fn create_token_accounts(payer: &Pubkey, owner: &Pubkey, mints: &[&Pubkey]) -> Vec<Instruction> {
+	mints
+		.iter()
+		.map(|mint| create_associated_token_account_idempotent(payer, owner, mint, &spl_token::id()))
+		.collect()
+}
+
+impl TokenizerAccounts {
+	pub fn new(tokenizer: &Pubkey, state: &TokenizerState) -> Self {
+		TokenizerAccounts {
+			tokenizer: *tokenizer,
+			state: state.clone(),
+		}
+	}
+
+	pub fn user(&self, owner: &Pubkey) -> UserTokenAccounts {
+		UserTokenAccounts {
+			owner: *owner,
+			underlying: get_associated_token_address(owner, &self.state.underlying_mint),
+			principal: get_associated_token_address(owner, &self.state.principal_token_mint),
+			yield_token: get_associated_token_address(owner, &self.state.yield_token_mint),
+		}
+	}
+
+	/// Returns the accounts of the claims escrow of the tokenizer
+	pub fn escrow(&self) -> EscrowAccounts {
+		EscrowAccounts {
+			tokenizer: self.tokenizer,
+			underlying_mint: self.state.underlying_mint,
+		}
+	}
+
+	/// Returns the instructions depositing the underlying and minting principal and yield
+	/// tokens to the owner
+	pub fn deposit_and_tokenize(
+		&self,
+		payer: &Pubkey,
+		owner: &Pubkey,
+		amount: u64,
+	) -> Result<Vec<Instruction>, ProgramError> {
+		let mut instructions = create_token_accounts(
+			payer,
+			owner,
+			&[&self.state.principal_token_mint, &self.state.yield_token_mint],
+		);
+
+		instructions.push(instruction::deposit_and_tokenize(
+			&DepositAndTokenizeAccounts::resolve(&self.tokenizer, &self.state, owner),
+			amount,
+		)?);
+
+		Ok(instructions)
+	}
+
+	/// Returns the instructions minting principal tokens to the owner
+	pub fn tokenize_principal(
+		&self,
+		payer: &Pubkey,
+		owner: &Pubkey,
+		amount: u64,
+	) -> Result<Vec<Instruction>, ProgramError> {
+		let mut instructions =
+			create_token_accounts(payer, owner, &[&self.state.principal_token_mint]);
+
+		instructions.push(instruction::tokenize_principal(
+			&TokenizePrincipalAccounts::resolve(&self.tokenizer, &self.state, owner),
+			amount,
+		)?);
+
+		Ok(instructions)
+	}
+
+	/// Returns the instructions minting yield tokens to the owner
+	pub fn tokenize_yield(
+		&self,
+		payer: &Pubkey,
+		owner: &Pubkey,
+		amount: u64,
+	) -> Result<Vec<Instruction>, ProgramError> {
+		let mut instructions = create_token_accounts(payer, owner, &[&self.state.yield_token_mint]);
+
+		instructions.push(instruction::tokenize_yield(
+			&TokenizeYieldAccounts::resolve(&self.tokenizer, &self.state, owner),
+			amount,
+		)?);
+
+		Ok(instructions)
+	}
+
+	/// Returns the instructions redeeming an equal amount of principal and yield tokens for
+	/// the underlying
+	pub fn redeem(
+		&self,
+		payer: &Pubkey,
+		owner: &Pubkey,
+		amount: u64,
+	) -> Result<Vec<Instruction>, ProgramError> {
+		let mut instructions = create_token_accounts(payer, owner, &[&self.state.underlying_mint]);
+
+		instructions.push(instruction::redeem_principal_and_yield(
+			&RedeemPrincipalAndYieldAccounts::resolve(&self.tokenizer, &self.state, owner),
+			amount,
+		)?);
+
+		Ok(instructions)
+	}
+
+	/// Returns the instructions redeeming mature principal tokens for the underlying
+	pub fn redeem_mature_principal(
+		&self,
+		payer: &Pubkey,
+		owner: &Pubkey,
+		principal_amount: u64,
+	) -> Result<Vec<Instruction>, ProgramError> {
+		let mut instructions = create_token_accounts(payer, owner, &[&self.state.underlying_mint]);
+
+		instructions.push(instruction::redeem_mature_principal(
+			&RedeemMaturePrincipalAccounts::resolve(&self.tokenizer, &self.state, owner),
+			principal_amount,
+		)?);
+
+		Ok(instructions)
+	}
+
+	/// Returns the instructions claiming the yield of yield tokens
+	pub fn claim_yield(
+		&self,
+		payer: &Pubkey,
+		owner: &Pubkey,
+		yield_amount: u64,
+	) -> Result<Vec<Instruction>, ProgramError> {
+		let mut instructions = create_token_accounts(payer, owner, &[&self.state.underlying_mint]);
+
+		instructions.push(instruction::claim_yield(
+			&ClaimYieldAccounts::resolve(&self.tokenizer, &self.state, owner),
+			yield_amount,
+		)?);
+
+		Ok(instructions)
+	}
+
+	/// Returns the instruction sweeping the underlying to the claims escrow
+	pub fn sweep_to_escrow(&self) -> Result<Instruction, ProgramError> {
+		instruction::sweep_to_escrow(&SweepToEscrowAccounts::resolve(
+			&self.tokenizer,
+			&self.state.underlying_mint,
+			&self.state.authority,
+			&self.state.stake_pool,
+			&self.state.treasury,
+		))
+	}
+
+	/// Returns the instruction settling the expiry and sweeping post expiry yield
+	pub fn settle(&self) -> Result<Instruction, ProgramError> {
+		instruction::settle(&SettleAccounts::resolve(
+			&self.tokenizer,
+			&self.state.underlying_mint,
+			&self.state.stake_pool,
+			&self.state.treasury,
+		))
+	}
+
+	/// Returns the instruction refreshing the yield index
+	pub fn checkpoint(&self) -> Result<Instruction, ProgramError> {
+		instruction::checkpoint(&CheckpointAccounts::resolve(
+			&self.tokenizer,
+			&self.state.stake_pool,
+		))
+	}
+
+	/// Returns the instruction resetting the yield index to the current exchange rate
+	pub fn reset_yield_index(&self) -> Result<Instruction, ProgramError> {
+		instruction::reset_yield_index(&ResetYieldIndexAccounts::resolve(
+			&self.tokenizer,
+			&self.state.authority,
+			&self.state.stake_pool,
+		))
+	}
+}
+
+impl EscrowAccounts {
+	pub fn new(tokenizer: &Pubkey, underlying_mint: &Pubkey) -> Self {
+		EscrowAccounts {
+			tokenizer: *tokenizer,
+			underlying_mint: *underlying_mint,
+		}
+	}
+
+	/// Returns the accounts of the claims escrow of a tokenizer from the escrow state
+	pub fn from_state(tokenizer: &Pubkey, state: &ClaimsEscrowState) -> Self {
+		EscrowAccounts::new(tokenizer, &state.underlying_mint)
+	}
+
+	/// Returns the address of the claims escrow
+	pub fn escrow(&self) -> Pubkey {
+		get_escrow_address(&self.tokenizer).0
+	}
+
+	/// Returns the instructions redeeming principal tokens from the claims escrow
+	pub fn redeem_escrow_principal(
+		&self,
+		payer: &Pubkey,
+		owner: &Pubkey,
+		principal_amount: u64,
+	) -> Result<Vec<Instruction>, ProgramError> {
+		let mut instructions = create_token_accounts(payer, owner, &[&self.underlying_mint]);
+
+		instructions.push(instruction::redeem_escrow_principal(
+			&RedeemEscrowPrincipalAccounts::resolve(&self.tokenizer, &self.underlying_mint, owner),
+			principal_amount,
+		)?);
+
+		Ok(instructions)
+	}
+
+	/// Returns the instructions claiming the yield of yield tokens from the claims escrow
+	pub fn claim_escrow_yield(
+		&self,
+		payer: &Pubkey,
+		owner: &Pubkey,
+		yield_amount: u64,
+	) -> Result<Vec<Instruction>, ProgramError> {
+		let mut instructions = create_token_accounts(payer, owner, &[&self.underlying_mint]);
+
+		instructions.push(instruction::claim_escrow_yield(
+			&ClaimEscrowYieldAccounts::resolve(&self.tokenizer, &self.underlying_mint, owner),
+			yield_amount,
+		)?);
+
+		Ok(instructions)
+	}
+}
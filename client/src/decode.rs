@@ -0,0 +1,145 @@
+use {
+	crate::error::{Result, TokenizerClientError},
+	borsh::BorshDeserialize,
+	sclr_token::instruction::TokenizerInstruction,
+	serde::Deserialize,
+	solana_program::pubkey::Pubkey,
+	solana_sdk::message::VersionedMessage,
+	std::sync::OnceLock,
+};
+
+// The account lists documented on `TokenizerInstruction`, as exported by `sclr-idl`
+const IDL: &str = include_str!("../idl/scalar_tokenizer.json");
+
+#[derive(Deserialize)]
+struct Idl {
+	instructions: Vec<IdlInstruction>,
+}
+
+#[derive(Deserialize)]
+struct IdlInstruction {
+	name: String,
+	accounts: Vec<IdlAccount>,
+	discriminant: IdlDiscriminant,
+}
+
+#[derive(Deserialize)]
+struct IdlAccount {
+	name: String,
+	docs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct IdlDiscriminant {
+	value: u8,
+}
+
+fn idl() -> Result<&'static Idl> {
+	static PARSED_IDL: OnceLock<Option<Idl>> = OnceLock::new();
+	PARSED_IDL
+		.get_or_init(|| serde_json::from_str(IDL).ok())
+		.as_ref()
+		.ok_or(TokenizerClientError::InvalidIdl)
+}
+
+/// An account of a decoded instruction, labelled as documented on `TokenizerInstruction`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedAccount {
+	pub name: String,
+	pub label: String,
+	pub address: Pubkey,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DecodedInstruction {
+	/// Index of the instruction within its transaction
+	pub index: usize,
+	pub name: String,
+	pub instruction: TokenizerInstruction,
+	pub accounts: Vec<DecodedAccount>,
+}
+
+/// Decodes the data of a tokenizer instruction and labels its accounts, accounts beyond
+/// those documented are labelled as remaining accounts
+pub fn decode_instruction(
+	index: usize,
+	accounts: &[Pubkey],
+	data: &[u8],
+) -> Result<DecodedInstruction> {
+	let instruction = TokenizerInstruction::try_from_slice(data)
+		.map_err(|_| TokenizerClientError::InvalidInstructionData)?;
+
+	let idl_instruction = idl()?
+		.instructions
+		.iter()
+		.find(|idl_instruction| Some(&idl_instruction.discriminant.value) == data.first())
+		.ok_or(TokenizerClientError::InvalidInstructionData)?;
+
+	let accounts = accounts
+		.iter()
+		.enumerate()
+		.map(|(position, address)| {
+			let (name, label) = idl_instruction
+				.accounts
+				.get(position)
+				.map(|account| {
+					(
+						account.name.clone(),
+						account.docs.first().cloned().unwrap_or_default(),
+					)
+				})
+				.unwrap_or_else(|| ("remaining".to_string(), "Remaining account".to_string()));
+
+			DecodedAccount {
+				name,
+				label,
+				address: *address,
+			}
+		})
+		.collect();
+
+	Ok(DecodedInstruction {
+		index,
+		name: idl_instruction.name.clone(),
+		instruction,
+		accounts,
+	})
+}
+
+/// Decodes the tokenizer instructions of a transaction message, the addresses loaded from
+/// lookup tables must be given for versioned messages, writable addresses first
+pub fn decode_message(
+	message: &VersionedMessage,
+	loaded_addresses: &[Pubkey],
+) -> Result<Vec<DecodedInstruction>> {
+	let account_keys = message
+		.static_account_keys()
+		.iter()
+		.chain(loaded_addresses)
+		.collect::<Vec<_>>();
+
+	let get_key = |index: &u8| {
+		account_keys
+			.get(*index as usize)
+			.map(|key| **key)
+			.ok_or(TokenizerClientError::InvalidInstructionData)
+	};
+
+	message
+		.instructions()
+		.iter()
+		.enumerate()
+		.filter(|(_, instruction)| {
+			get_key(&instruction.program_id_index).ok() == Some(sclr_token::id())
+		})
+		.map(|(index, instruction)| {
+			let accounts = instruction
+				.accounts
+				.iter()
+				.map(get_key)
+				.collect::<Result<Vec<_>>>()?;
+
+			decode_instruction(index, &accounts, &instruction.data)
+		})
+		.collect()
+}
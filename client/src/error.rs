@@ -0,0 +1,32 @@
+use {
+	solana_client::client_error::ClientError,
+	solana_program::{program_error::ProgramError, pubkey::Pubkey},
+	solana_sdk::signer::SignerError,
+	thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum TokenizerClientError {
+	#[error("RPC Error: {0}")]
+	Rpc(Box<ClientError>),
+	#[error("Program Error: {0}")]
+	Program(#[from] ProgramError),
+	#[error("Signer Error: {0}")]
+	Signer(#[from] SignerError),
+	#[error("Account Not Found: {0}")]
+	AccountNotFound(Pubkey),
+	#[error("Invalid Account Data: {0}")]
+	InvalidAccountData(Pubkey),
+	#[error("Invalid Instruction Data")]
+	InvalidInstructionData,
+	#[error("Invalid IDL")]
+	InvalidIdl,
+}
+
+impl From<ClientError> for TokenizerClientError {
+	fn from(err: ClientError) -> Self {
+		TokenizerClientError::Rpc(Box::new(err))
+	}
+}
+
+pub type Result<T> = std::result::Result<T, TokenizerClientError>;
@@ -0,0 +1,54 @@
+use {
+	base64::{engine::general_purpose::STANDARD as BASE64, Engine},
+	sclr_token::event::TokenizerEvent,
+	solana_program::pubkey::Pubkey,
+	std::str::FromStr,
+};
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Parses the events emitted by the tokenizer program from the log messages of a transaction.
+/// The invocation stack is followed so that data logged by other programs, including programs
+/// invoked by the tokenizer, is never mistaken for an event
+pub fn parse_events(log_messages: &[String]) -> Vec<TokenizerEvent> {
+	let mut invocations: Vec<Pubkey> = vec![];
+	let mut events = vec![];
+
+	for log in log_messages {
+		if let Some(data) = log.strip_prefix(PROGRAM_DATA_PREFIX) {
+			if invocations.last() != Some(&sclr_token::id()) {
+				continue;
+			}
+
+			let Ok(fields) = data
+				.split_whitespace()
+				.map(|field| BASE64.decode(field))
+				.collect::<Result<Vec<_>, _>>()
+			else {
+				continue;
+			};
+			let fields = fields.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+			events.extend(TokenizerEvent::from_log_data(&fields));
+		} else if let Some(rest) = log.strip_prefix("Program ") {
+			let mut words = rest.split_whitespace();
+			let (Some(program), Some(status)) = (words.next(), words.next()) else {
+				continue;
+			};
+
+			match status {
+				"invoke" => {
+					if let Ok(program_id) = Pubkey::from_str(program) {
+						invocations.push(program_id);
+					}
+				}
+				"success" | "failed:" => {
+					invocations.pop();
+				}
+				_ => {}
+			}
+		}
+	}
+
+	events
+}
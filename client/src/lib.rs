@@ -0,0 +1,215 @@
+pub mod accounts;
+pub mod decode;
+pub mod error;
+pub mod event;
+pub mod nonblocking;
+pub mod operation;
+pub mod position;
+pub mod pricing;
+
+use {
+	crate::{
+		accounts::{EscrowAccounts, TokenizerAccounts},
+		error::{Result, TokenizerClientError},
+		operation::{Operation, OperationAccounts},
+	},
+	borsh::BorshDeserialize,
+	sclr_token::{
+		get_escrow_address, get_tokenizer_address,
+		state::{ClaimsEscrowState, TokenizerState},
+	},
+	solana_client::rpc_client::RpcClient,
+	solana_program::{instruction::Instruction, pubkey::Pubkey},
+	solana_sdk::{
+		account::Account,
+		signature::{Signature, Signer},
+		transaction::Transaction,
+	},
+	std::sync::Arc,
+};
+
+/// Deserializes an account of the tokenizer program
+pub fn deserialize_account<T: BorshDeserialize>(address: &Pubkey, account: &Account) -> Result<T> {
+	if account.owner != sclr_token::id() {
+		return Err(TokenizerClientError::InvalidAccountData(*address));
+	}
+
+	T::try_from_slice(&account.data).map_err(|_| TokenizerClientError::InvalidAccountData(*address))
+}
+
+/// A client for the tokenizer program, deriving the accounts of each instruction from the
+/// state of the tokenizer. The payer signs and pays for every transaction and owns the tokens
+pub struct TokenizerClient {
+	rpc_client: RpcClient,
+	payer: Arc<dyn Signer + Send + Sync>,
+}
+
+impl TokenizerClient {
+	pub fn new(rpc_client: RpcClient, payer: Arc<dyn Signer + Send + Sync>) -> Self {
+		TokenizerClient { rpc_client, payer }
+	}
+
+	pub fn rpc_client(&self) -> &RpcClient {
+		&self.rpc_client
+	}
+
+	pub fn payer(&self) -> Pubkey {
+		self.payer.pubkey()
+	}
+
+	/// Returns the address of the tokenizer of an underlying mint and expiry date
+	pub fn tokenizer_address(underlying_mint: &Pubkey, expiry_date: i64) -> Pubkey {
+		get_tokenizer_address(underlying_mint, expiry_date).0
+	}
+
+	/// Fetches and deserializes the state of a tokenizer
+	pub fn fetch_state(&self, tokenizer: &Pubkey) -> Result<TokenizerState> {
+		let account = self
+			.rpc_client
+			.get_account_with_commitment(tokenizer, self.rpc_client.commitment())?
+			.value
+			.ok_or(TokenizerClientError::AccountNotFound(*tokenizer))?;
+
+		deserialize_account(tokenizer, &account)
+	}
+
+	/// Fetches and deserializes the claims escrow of a tokenizer, returns `None` if the
+	/// underlying has not been swept to the escrow
+	pub fn fetch_escrow_state(&self, tokenizer: &Pubkey) -> Result<Option<ClaimsEscrowState>> {
+		let (escrow, _) = get_escrow_address(tokenizer);
+		let Some(account) = self
+			.rpc_client
+			.get_account_with_commitment(&escrow, self.rpc_client.commitment())?
+			.value
+		else {
+			return Ok(None);
+		};
+
+		deserialize_account(&escrow, &account).map(Some)
+	}
+
+	/// Fetches the state of a tokenizer and derives its accounts
+	pub fn fetch_accounts(&self, tokenizer: &Pubkey) -> Result<TokenizerAccounts> {
+		let state = self.fetch_state(tokenizer)?;
+
+		Ok(TokenizerAccounts::new(tokenizer, &state))
+	}
+
+	/// Fetches the claims escrow of a tokenizer and derives its accounts, the tokenizer
+	/// itself may already be terminated
+	pub fn fetch_escrow_accounts(&self, tokenizer: &Pubkey) -> Result<EscrowAccounts> {
+		let (escrow, _) = get_escrow_address(tokenizer);
+		let escrow_state = self
+			.fetch_escrow_state(tokenizer)?
+			.ok_or(TokenizerClientError::AccountNotFound(escrow))?;
+
+		Ok(EscrowAccounts::from_state(tokenizer, &escrow_state))
+	}
+
+	/// Fetches the accounts an operation is built from
+	pub fn fetch_operation_accounts(
+		&self,
+		tokenizer: &Pubkey,
+		operation: &Operation,
+	) -> Result<OperationAccounts> {
+		if operation.uses_escrow() {
+			Ok(OperationAccounts::Escrow(self.fetch_escrow_accounts(tokenizer)?))
+		} else {
+			Ok(OperationAccounts::Tokenizer(Box::new(
+				self.fetch_accounts(tokenizer)?,
+			)))
+		}
+	}
+
+	/// Builds an operation on a tokenizer and sends it, the payer owns the tokens
+	pub fn execute(&self, tokenizer: &Pubkey, operation: Operation) -> Result<Signature> {
+		let payer = self.payer();
+		let accounts = self.fetch_operation_accounts(tokenizer, &operation)?;
+		let instructions = operation.instructions(&accounts, &payer, &payer)?;
+
+		self.send(&instructions)
+	}
+
+	/// Deposits the underlying and mints an equal amount of principal and yield tokens
+	pub fn deposit_and_tokenize(&self, tokenizer: &Pubkey, amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::DepositAndTokenize { amount })
+	}
+
+	/// Mints principal tokens
+	pub fn tokenize_principal(&self, tokenizer: &Pubkey, amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::TokenizePrincipal { amount })
+	}
+
+	/// Mints yield tokens
+	pub fn tokenize_yield(&self, tokenizer: &Pubkey, amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::TokenizeYield { amount })
+	}
+
+	/// Redeems an equal amount of principal and yield tokens for the underlying
+	pub fn redeem(&self, tokenizer: &Pubkey, amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::Redeem { amount })
+	}
+
+	/// Redeems principal tokens for the underlying after expiry
+	pub fn redeem_mature_principal(
+		&self,
+		tokenizer: &Pubkey,
+		principal_amount: u64,
+	) -> Result<Signature> {
+		self.execute(tokenizer, Operation::RedeemMaturePrincipal { principal_amount })
+	}
+
+	/// Claims the yield of yield tokens
+	pub fn claim_yield(&self, tokenizer: &Pubkey, yield_amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::ClaimYield { yield_amount })
+	}
+
+	/// Redeems principal tokens from the claims escrow of a terminated tokenizer
+	pub fn redeem_escrow_principal(
+		&self,
+		tokenizer: &Pubkey,
+		principal_amount: u64,
+	) -> Result<Signature> {
+		self.execute(tokenizer, Operation::RedeemEscrowPrincipal { principal_amount })
+	}
+
+	/// Claims the yield of yield tokens from the claims escrow of a terminated tokenizer
+	pub fn claim_escrow_yield(&self, tokenizer: &Pubkey, yield_amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::ClaimEscrowYield { yield_amount })
+	}
+
+	/// Sweeps the underlying of a tokenizer to its claims escrow, signed by the payer as the
+	/// tokenizer authority
+	pub fn sweep_to_escrow(&self, tokenizer: &Pubkey) -> Result<Signature> {
+		self.execute(tokenizer, Operation::SweepToEscrow)
+	}
+
+	/// Refreshes the yield index of a tokenizer
+	pub fn checkpoint(&self, tokenizer: &Pubkey) -> Result<Signature> {
+		self.execute(tokenizer, Operation::Checkpoint)
+	}
+
+	/// Resets the yield index of a tokenizer to the current exchange rate, signed by the payer as
+	/// the tokenizer authority
+	pub fn reset_yield_index(&self, tokenizer: &Pubkey) -> Result<Signature> {
+		self.execute(tokenizer, Operation::ResetYieldIndex)
+	}
+
+	/// Settles the expiry of a tokenizer and sweeps post expiry yield to the treasury
+	pub fn settle(&self, tokenizer: &Pubkey) -> Result<Signature> {
+		self.execute(tokenizer, Operation::Settle)
+	}
+
+	/// Signs the instructions with the payer and sends them in a transaction
+	pub fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+		let latest_blockhash = self.rpc_client.get_latest_blockhash()?;
+		let transaction = Transaction::new_signed_with_payer(
+			instructions,
+			Some(&self.payer.pubkey()),
+			&[&self.payer],
+			latest_blockhash,
+		);
+
+		Ok(self.rpc_client.send_and_confirm_transaction(&transaction)?)
+	}
+}
@@ -0,0 +1,245 @@
+use {
+	crate::{
+		accounts::{EscrowAccounts, TokenizerAccounts},
+		deserialize_account,
+		error::{Result, TokenizerClientError},
+		operation::{Operation, OperationAccounts},
+	},
+	futures::future::try_join_all,
+	sclr_token::{
+		get_escrow_address, get_tokenizer_address,
+		state::{ClaimsEscrowState, TokenizerState},
+	},
+	solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::MAX_MULTIPLE_ACCOUNTS},
+	solana_program::{instruction::Instruction, pubkey::Pubkey},
+	solana_sdk::{
+		signature::{Signature, Signer},
+		transaction::Transaction,
+	},
+	std::sync::Arc,
+};
+
+/// An async client for the tokenizer program, deriving the accounts of each instruction from
+/// the state of the tokenizer. The payer signs and pays for every transaction and owns the
+/// tokens
+pub struct TokenizerClient {
+	rpc_client: RpcClient,
+	payer: Arc<dyn Signer + Send + Sync>,
+}
+
+impl TokenizerClient {
+	pub fn new(rpc_client: RpcClient, payer: Arc<dyn Signer + Send + Sync>) -> Self {
+		TokenizerClient { rpc_client, payer }
+	}
+
+	pub fn rpc_client(&self) -> &RpcClient {
+		&self.rpc_client
+	}
+
+	pub fn payer(&self) -> Pubkey {
+		self.payer.pubkey()
+	}
+
+	/// Returns the address of the tokenizer of an underlying mint and expiry date
+	pub fn tokenizer_address(underlying_mint: &Pubkey, expiry_date: i64) -> Pubkey {
+		get_tokenizer_address(underlying_mint, expiry_date).0
+	}
+
+	/// Fetches and deserializes the state of a tokenizer
+	pub async fn fetch_state(&self, tokenizer: &Pubkey) -> Result<TokenizerState> {
+		let account = self
+			.rpc_client
+			.get_account_with_commitment(tokenizer, self.rpc_client.commitment())
+			.await?
+			.value
+			.ok_or(TokenizerClientError::AccountNotFound(*tokenizer))?;
+
+		deserialize_account(tokenizer, &account)
+	}
+
+	/// Fetches and deserializes the states of many tokenizers, the accounts are requested
+	/// concurrently in chunks of the maximum accepted by `getMultipleAccounts`
+	pub async fn fetch_states(&self, tokenizers: &[Pubkey]) -> Result<Vec<TokenizerState>> {
+		let chunks = try_join_all(tokenizers.chunks(MAX_MULTIPLE_ACCOUNTS).map(|chunk| {
+			self.rpc_client
+				.get_multiple_accounts_with_commitment(chunk, self.rpc_client.commitment())
+		}))
+		.await?;
+
+		chunks
+			.into_iter()
+			.flat_map(|response| response.value)
+			.zip(tokenizers)
+			.map(|(account, tokenizer)| {
+				let account = account.ok_or(TokenizerClientError::AccountNotFound(*tokenizer))?;
+
+				deserialize_account(tokenizer, &account)
+			})
+			.collect()
+	}
+
+	/// Fetches and deserializes the claims escrow of a tokenizer, returns `None` if the
+	/// underlying has not been swept to the escrow
+	pub async fn fetch_escrow_state(
+		&self,
+		tokenizer: &Pubkey,
+	) -> Result<Option<ClaimsEscrowState>> {
+		let (escrow, _) = get_escrow_address(tokenizer);
+		let Some(account) = self
+			.rpc_client
+			.get_account_with_commitment(&escrow, self.rpc_client.commitment())
+			.await?
+			.value
+		else {
+			return Ok(None);
+		};
+
+		deserialize_account(&escrow, &account).map(Some)
+	}
+
+	/// Fetches the state of a tokenizer and derives its accounts
+	pub async fn fetch_accounts(&self, tokenizer: &Pubkey) -> Result<TokenizerAccounts> {
+		let state = self.fetch_state(tokenizer).await?;
+
+		Ok(TokenizerAccounts::new(tokenizer, &state))
+	}
+
+	/// Fetches the states of many tokenizers and derives their accounts
+	pub async fn fetch_all_accounts(
+		&self,
+		tokenizers: &[Pubkey],
+	) -> Result<Vec<TokenizerAccounts>> {
+		let states = self.fetch_states(tokenizers).await?;
+
+		Ok(tokenizers
+			.iter()
+			.zip(&states)
+			.map(|(tokenizer, state)| TokenizerAccounts::new(tokenizer, state))
+			.collect())
+	}
+
+	/// Fetches the claims escrow of a tokenizer and derives its accounts, the tokenizer
+	/// itself may already be terminated
+	pub async fn fetch_escrow_accounts(&self, tokenizer: &Pubkey) -> Result<EscrowAccounts> {
+		let (escrow, _) = get_escrow_address(tokenizer);
+		let escrow_state = self
+			.fetch_escrow_state(tokenizer).await?
+			.ok_or(TokenizerClientError::AccountNotFound(escrow))?;
+
+		Ok(EscrowAccounts::from_state(tokenizer, &escrow_state))
+	}
+
+	/// Fetches the accounts an operation is built from
+	pub async fn fetch_operation_accounts(
+		&self,
+		tokenizer: &Pubkey,
+		operation: &Operation,
+	) -> Result<OperationAccounts> {
+		if operation.uses_escrow() {
+			Ok(OperationAccounts::Escrow(self.fetch_escrow_accounts(tokenizer).await?))
+		} else {
+			Ok(OperationAccounts::Tokenizer(Box::new(
+				self.fetch_accounts(tokenizer).await?,
+			)))
+		}
+	}
+
+	/// Builds an operation on a tokenizer and sends it, the payer owns the tokens
+	pub async fn execute(&self, tokenizer: &Pubkey, operation: Operation) -> Result<Signature> {
+		let payer = self.payer();
+		let accounts = self.fetch_operation_accounts(tokenizer, &operation).await?;
+		let instructions = operation.instructions(&accounts, &payer, &payer)?;
+
+		self.send(&instructions).await
+	}
+
+	/// Deposits the underlying and mints an equal amount of principal and yield tokens
+	pub async fn deposit_and_tokenize(&self, tokenizer: &Pubkey, amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::DepositAndTokenize { amount }).await
+	}
+
+	/// Mints principal tokens
+	pub async fn tokenize_principal(&self, tokenizer: &Pubkey, amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::TokenizePrincipal { amount }).await
+	}
+
+	/// Mints yield tokens
+	pub async fn tokenize_yield(&self, tokenizer: &Pubkey, amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::TokenizeYield { amount }).await
+	}
+
+	/// Redeems an equal amount of principal and yield tokens for the underlying
+	pub async fn redeem(&self, tokenizer: &Pubkey, amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::Redeem { amount }).await
+	}
+
+	/// Redeems principal tokens for the underlying after expiry
+	pub async fn redeem_mature_principal(
+		&self,
+		tokenizer: &Pubkey,
+		principal_amount: u64,
+	) -> Result<Signature> {
+		self.execute(tokenizer, Operation::RedeemMaturePrincipal { principal_amount }).await
+	}
+
+	/// Claims the yield of yield tokens
+	pub async fn claim_yield(&self, tokenizer: &Pubkey, yield_amount: u64) -> Result<Signature> {
+		self.execute(tokenizer, Operation::ClaimYield { yield_amount }).await
+	}
+
+	/// Redeems principal tokens from the claims escrow of a terminated tokenizer
+	pub async fn redeem_escrow_principal(
+		&self,
+		tokenizer: &Pubkey,
+		principal_amount: u64,
+	) -> Result<Signature> {
+		self.execute(tokenizer, Operation::RedeemEscrowPrincipal { principal_amount }).await
+	}
+
+	/// Claims the yield of yield tokens from the claims escrow of a terminated tokenizer
+	pub async fn claim_escrow_yield(
+		&self,
+		tokenizer: &Pubkey,
+		yield_amount: u64,
+	) -> Result<Signature> {
+		self.execute(tokenizer, Operation::ClaimEscrowYield { yield_amount }).await
+	}
+
+	/// Sweeps the underlying of a tokenizer to its claims escrow, signed by the payer as the
+	/// tokenizer authority
+	pub async fn sweep_to_escrow(&self, tokenizer: &Pubkey) -> Result<Signature> {
+		self.execute(tokenizer, Operation::SweepToEscrow).await
+	}
+
+	/// Refreshes the yield index of a tokenizer
+	pub async fn checkpoint(&self, tokenizer: &Pubkey) -> Result<Signature> {
+		self.execute(tokenizer, Operation::Checkpoint).await
+	}
+
+	/// Resets the yield index of a tokenizer to the current exchange rate, signed by the payer as
+	/// the tokenizer authority
+	pub async fn reset_yield_index(&self, tokenizer: &Pubkey) -> Result<Signature> {
+		self.execute(tokenizer, Operation::ResetYieldIndex).await
+	}
+
+	/// Settles the expiry of a tokenizer and sweeps post expiry yield to the treasury
+	pub async fn settle(&self, tokenizer: &Pubkey) -> Result<Signature> {
+		self.execute(tokenizer, Operation::Settle).await
+	}
+
+	/// Signs the instructions with the payer and sends them in a transaction
+	pub async fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+		let latest_blockhash = self.rpc_client.get_latest_blockhash().await?;
+		let transaction = Transaction::new_signed_with_payer(
+			instructions,
+			Some(&self.payer.pubkey()),
+			&[&self.payer],
+			latest_blockhash,
+		);
+
+		Ok(self
+			.rpc_client
+			.send_and_confirm_transaction(&transaction)
+			.await?)
+	}
+}
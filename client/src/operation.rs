@@ -0,0 +1,112 @@
+use {
+	crate::{
+		accounts::{EscrowAccounts, TokenizerAccounts},
+		error::{Result, TokenizerClientError},
+	},
+	solana_program::{instruction::Instruction, pubkey::Pubkey},
+};
+
+/// An operation of the tokenizer program, built into instructions the same way by the
+/// blocking and async clients
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+	/// Deposits the underlying and mints an equal amount of principal and yield tokens
+	DepositAndTokenize { amount: u64 },
+	/// Mints principal tokens
+	TokenizePrincipal { amount: u64 },
+	/// Mints yield tokens
+	TokenizeYield { amount: u64 },
+	/// Redeems an equal amount of principal and yield tokens for the underlying
+	Redeem { amount: u64 },
+	/// Redeems principal tokens for the underlying after expiry
+	RedeemMaturePrincipal { principal_amount: u64 },
+	/// Claims the yield of yield tokens
+	ClaimYield { yield_amount: u64 },
+	/// Redeems principal tokens from the claims escrow of a terminated tokenizer
+	RedeemEscrowPrincipal { principal_amount: u64 },
+	/// Claims the yield of yield tokens from the claims escrow of a terminated tokenizer
+	ClaimEscrowYield { yield_amount: u64 },
+	/// Sweeps the underlying to the claims escrow, signed by the tokenizer authority
+	SweepToEscrow,
+	/// Settles the expiry and sweeps post expiry yield to the treasury
+	Settle,
+	/// Refreshes the yield index
+	Checkpoint,
+	/// Resets the yield index to the current exchange rate, signed by the tokenizer authority
+	ResetYieldIndex,
+}
+
+/// The accounts an operation is built from, fetched by the client
+pub enum OperationAccounts {
+	Tokenizer(Box<TokenizerAccounts>),
+	Escrow(EscrowAccounts),
+}
+
+impl Operation {
+	/// Whether the operation is built from the claims escrow rather than the tokenizer,
+	/// which is closed once terminated
+	pub fn uses_escrow(&self) -> bool {
+		matches!(
+			self,
+			Operation::RedeemEscrowPrincipal { .. } | Operation::ClaimEscrowYield { .. }
+		)
+	}
+
+	/// Returns the instructions of the operation, the payer creates the owner's token
+	/// accounts where the operation pays out to them
+	pub fn instructions(
+		&self,
+		accounts: &OperationAccounts,
+		payer: &Pubkey,
+		owner: &Pubkey,
+	) -> Result<Vec<Instruction>> {
+		let instructions = match (*self, accounts) {
+			(Operation::DepositAndTokenize { amount }, OperationAccounts::Tokenizer(tokenizer)) => {
+				tokenizer.deposit_and_tokenize(payer, owner, amount)?
+			}
+			(Operation::TokenizePrincipal { amount }, OperationAccounts::Tokenizer(tokenizer)) => {
+				tokenizer.tokenize_principal(payer, owner, amount)?
+			}
+			(Operation::TokenizeYield { amount }, OperationAccounts::Tokenizer(tokenizer)) => {
+				tokenizer.tokenize_yield(payer, owner, amount)?
+			}
+			(Operation::Redeem { amount }, OperationAccounts::Tokenizer(tokenizer)) => {
+				tokenizer.redeem(payer, owner, amount)?
+			}
+			(
+				Operation::RedeemMaturePrincipal { principal_amount },
+				OperationAccounts::Tokenizer(tokenizer),
+			) => tokenizer.redeem_mature_principal(payer, owner, principal_amount)?,
+			(Operation::ClaimYield { yield_amount }, OperationAccounts::Tokenizer(tokenizer)) => {
+				tokenizer.claim_yield(payer, owner, yield_amount)?
+			}
+			(Operation::SweepToEscrow, OperationAccounts::Tokenizer(tokenizer)) => {
+				vec![tokenizer.sweep_to_escrow()?]
+			}
+			(Operation::Settle, OperationAccounts::Tokenizer(tokenizer)) => {
+				vec![tokenizer.settle()?]
+			}
+			(Operation::Checkpoint, OperationAccounts::Tokenizer(tokenizer)) => {
+				vec![tokenizer.checkpoint()?]
+			}
+			(Operation::ResetYieldIndex, OperationAccounts::Tokenizer(tokenizer)) => {
+				vec![tokenizer.reset_yield_index()?]
+			}
+			(
+				Operation::RedeemEscrowPrincipal { principal_amount },
+				OperationAccounts::Escrow(escrow),
+			) => escrow.redeem_escrow_principal(payer, owner, principal_amount)?,
+			(Operation::ClaimEscrowYield { yield_amount }, OperationAccounts::Escrow(escrow)) => {
+				escrow.claim_escrow_yield(payer, owner, yield_amount)?
+			}
+			(_, OperationAccounts::Tokenizer(tokenizer)) => {
+				return Err(TokenizerClientError::AccountNotFound(tokenizer.escrow().escrow()));
+			}
+			(_, OperationAccounts::Escrow(escrow)) => {
+				return Err(TokenizerClientError::AccountNotFound(escrow.tokenizer));
+			}
+		};
+
+		Ok(instructions)
+	}
+}
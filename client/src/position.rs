@@ -0,0 +1,208 @@
+use {
+	crate::error::{Result, TokenizerClientError},
+	borsh::BorshDeserialize,
+	sclr_token::{
+		get_escrow_address,
+		rate::{
+			asset_to_underlying, asset_to_underlying_ceil, exchange_rate_from_data, pro_rata_share,
+		},
+		state::{ClaimsEscrowState, PostExpiryYieldRecipient, TokenizerState},
+	},
+	serde::Serialize,
+	solana_account_decoder::{parse_token::TokenAccountType, UiAccountData},
+	solana_client::rpc_response::RpcKeyedAccount,
+	solana_program::{program_pack::Pack, pubkey::Pubkey},
+	solana_sdk::account::Account,
+	std::{collections::HashMap, str::FromStr},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum TokenizerStatus {
+	/// Accepting deposits, the expiry date has not elapsed
+	Active,
+	/// Matured, principal and yield can be redeemed from the tokenizer
+	Expired,
+	/// The grace period has elapsed and the underlying can be swept to the claims escrow
+	SettlementWindowElapsed,
+}
+
+impl TokenizerStatus {
+	pub fn from_state(state: &TokenizerState, timestamp: i64) -> Self {
+		if state.expiry_date >= timestamp {
+			TokenizerStatus::Active
+		} else if state.expiry_date.saturating_add(state.grace_period) >= timestamp {
+			TokenizerStatus::Expired
+		} else {
+			TokenizerStatus::SettlementWindowElapsed
+		}
+	}
+}
+
+/// The balances of a tokenizer that the principal and yield tokens are valued against
+#[derive(Debug, PartialEq)]
+pub struct TokenizerBalances {
+	/// Balance of the underlying vault, zero once the vault has been closed
+	pub vault_balance: u64,
+	pub principal_supply: u64,
+	pub yield_supply: u64,
+	/// The current exchange rate of the stake pool, scaled by `WAD`
+	pub exchange_rate: u128,
+	/// The claims escrow, once the underlying has been swept to it
+	pub escrow: Option<ClaimsEscrowState>,
+}
+
+impl TokenizerBalances {
+	/// Returns the accounts to fetch for `from_accounts`: the underlying vault, the principal
+	/// and yield mints, the stake pool and the claims escrow
+	pub fn addresses(address: &Pubkey, state: &TokenizerState) -> [Pubkey; 5] {
+		[
+			state.underlying_vault,
+			state.principal_token_mint,
+			state.yield_token_mint,
+			state.stake_pool,
+			get_escrow_address(address).0,
+		]
+	}
+
+	/// Reads the balances from the accounts returned for `addresses`, in the same order
+	pub fn from_accounts(state: &TokenizerState, accounts: &[Option<Account>]) -> Result<Self> {
+		let [vault, principal_mint, yield_mint, stake_pool, escrow] = accounts else {
+			return Err(TokenizerClientError::InvalidAccountData(
+				state.underlying_vault,
+			));
+		};
+
+		let get_supply = |mint: &Option<Account>| {
+			mint.as_ref()
+				.and_then(|account| spl_token::state::Mint::unpack(&account.data).ok())
+				.map(|mint| mint.supply)
+				.unwrap_or_default()
+		};
+
+		let stake_pool = stake_pool
+			.as_ref()
+			.ok_or(TokenizerClientError::AccountNotFound(state.stake_pool))?;
+
+		Ok(TokenizerBalances {
+			vault_balance: vault
+				.as_ref()
+				.and_then(|account| spl_token::state::Account::unpack(&account.data).ok())
+				.map(|account| account.amount)
+				.unwrap_or_default(),
+			principal_supply: get_supply(principal_mint),
+			yield_supply: get_supply(yield_mint),
+			exchange_rate: exchange_rate_from_data(&stake_pool.data, &state.underlying_mint)?,
+			escrow: escrow
+				.as_ref()
+				.filter(|account| account.owner == sclr_token::id())
+				.and_then(|account| ClaimsEscrowState::try_from_slice(&account.data).ok()),
+		})
+	}
+}
+
+/// Underlying received for redeeming principal tokens and claiming yield tokens
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RedeemableAmounts {
+	/// Before expiry only principal paired with yield tokens can be redeemed
+	pub principal: u64,
+	pub yield_claim: u64,
+}
+
+/// Calculates the underlying received for redeeming principal and claiming yield at the
+/// timestamp, mirroring the calculations of the tokenizer program
+pub fn redeemable_amounts(
+	state: &TokenizerState,
+	balances: &TokenizerBalances,
+	principal_balance: u64,
+	yield_balance: u64,
+	timestamp: i64,
+) -> Result<RedeemableAmounts> {
+	let exchange_rate = balances.exchange_rate;
+
+	let (principal, yield_claim) = if let Some(ref escrow) = balances.escrow {
+		// The underlying has been swept to the claims escrow
+		(
+			asset_to_underlying(principal_balance, escrow.expiry_exchange_rate)?,
+			pro_rata_share(
+				yield_balance,
+				escrow.expiry_yield_reserve,
+				balances.yield_supply,
+			)?,
+		)
+	} else if TokenizerStatus::from_state(state, timestamp) == TokenizerStatus::Active {
+		let yield_pool = balances
+			.vault_balance
+			.saturating_sub(asset_to_underlying_ceil(
+				balances.principal_supply,
+				exchange_rate,
+			)?);
+
+		(
+			asset_to_underlying(principal_balance.min(yield_balance), exchange_rate)?,
+			pro_rata_share(yield_balance, yield_pool, balances.yield_supply)?,
+		)
+	} else {
+		// The expiry is settled on the first interaction after expiry, until then the current
+		// rate is the rate it would be settled at
+		let (expiry_exchange_rate, expiry_yield_reserve) = if state.expiry_exchange_rate != 0 {
+			(state.expiry_exchange_rate, state.expiry_yield_reserve)
+		} else {
+			(
+				exchange_rate,
+				balances
+					.vault_balance
+					.saturating_sub(asset_to_underlying_ceil(
+						balances.principal_supply,
+						exchange_rate,
+					)?),
+			)
+		};
+
+		let principal_exchange_rate = match state.post_expiry_yield {
+			PostExpiryYieldRecipient::PrincipalHolders => expiry_exchange_rate,
+			PostExpiryYieldRecipient::Treasury => exchange_rate.max(expiry_exchange_rate),
+		};
+
+		(
+			asset_to_underlying(principal_balance, principal_exchange_rate)?,
+			pro_rata_share(yield_balance, expiry_yield_reserve, balances.yield_supply)?,
+		)
+	};
+
+	Ok(RedeemableAmounts {
+		principal,
+		yield_claim,
+	})
+}
+
+/// Sums the balances of the token accounts returned by `getTokenAccountsByOwner` by mint,
+/// accepting both parsed and binary encoded accounts
+pub fn token_balances_by_mint(token_accounts: &[RpcKeyedAccount]) -> HashMap<Pubkey, u64> {
+	let mut balances: HashMap<Pubkey, u64> = HashMap::new();
+
+	for keyed_account in token_accounts {
+		let balance = match &keyed_account.account.data {
+			UiAccountData::Json(parsed_account) => {
+				match serde_json::from_value(parsed_account.parsed.clone()) {
+					Ok(TokenAccountType::Account(token_account)) => {
+						Pubkey::from_str(&token_account.mint).ok().zip(
+							token_account.token_amount.amount.parse::<u64>().ok(),
+						)
+					}
+					_ => None,
+				}
+			}
+			_ => keyed_account
+				.account
+				.decode::<Account>()
+				.and_then(|account| spl_token::state::Account::unpack(&account.data).ok())
+				.map(|token_account| (token_account.mint, token_account.amount)),
+		};
+
+		if let Some((mint, amount)) = balance {
+			*balances.entry(mint).or_default() += amount;
+		}
+	}
+
+	balances
+}
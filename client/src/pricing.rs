@@ -0,0 +1,110 @@
+use sclr_token::{
+	math::WAD,
+	rate::{BPS, SECONDS_PER_YEAR},
+};
+
+// A principal token redeems for one unit of the accounting asset at expiry and is priced as a
+// zero-coupon bond, a yield token receives the growth of the exchange rate over that unit. The
+// two together redeem for one unit so their prices sum to one. Prices are in units of the
+// accounting asset and APYs are decimal fractions compounded annually, `None` is returned for
+// inputs outside the domain of a function
+
+/// Returns the years remaining from the timestamp until the expiry date, zero once expired
+pub fn years_to_expiry(expiry_date: i64, timestamp: i64) -> f64 {
+	expiry_date.saturating_sub(timestamp).max(0) as f64 / SECONDS_PER_YEAR as f64
+}
+
+/// Converts an APY in basis points to a decimal fraction
+pub fn apy_from_bps(bps: u64) -> f64 {
+	bps as f64 / BPS as f64
+}
+
+/// Converts an APY to basis points, rounded to the nearest basis point
+pub fn apy_to_bps(apy: f64) -> Option<i64> {
+	let bps = (apy * BPS as f64).round();
+
+	(bps.abs() < i64::MAX as f64).then_some(bps as i64)
+}
+
+// The growth of one unit compounded annually at the APY over the years
+fn growth(apy: f64, years: f64) -> Option<f64> {
+	if !apy.is_finite() || apy <= -1.0 || !years.is_finite() || years < 0.0 {
+		return None;
+	}
+
+	Some((1.0 + apy).powf(years))
+}
+
+/// Returns the price of a principal token, the present value of one unit at expiry
+/// discounted at the implied APY
+pub fn principal_price(implied_apy: f64, years: f64) -> Option<f64> {
+	growth(implied_apy, years).map(|growth| 1.0 / growth)
+}
+
+/// Returns the price of a yield token implied by the APY the principal token is discounted
+/// at, the remainder of one unit after the principal token
+pub fn yield_price(implied_apy: f64, years: f64) -> Option<f64> {
+	principal_price(implied_apy, years).map(|price| 1.0 - price)
+}
+
+/// Returns the fair value of a yield token expecting the exchange rate to grow at the
+/// expected APY until expiry, discounted at the implied APY. Equal to `yield_price` when the
+/// expected APY is the implied APY
+pub fn yield_fair_value(expected_apy: f64, implied_apy: f64, years: f64) -> Option<f64> {
+	let expected_growth = growth(expected_apy, years)?;
+
+	Some((expected_growth - 1.0) / growth(implied_apy, years)?)
+}
+
+/// Returns the APY implied by the price of a principal token, the rate at which buying the
+/// token and holding it until expiry compounds
+pub fn implied_apy_from_principal_price(price: f64, years: f64) -> Option<f64> {
+	if !price.is_finite() || price <= 0.0 || !years.is_finite() || years <= 0.0 {
+		return None;
+	}
+
+	Some((1.0 / price).powf(1.0 / years) - 1.0)
+}
+
+/// Returns the APY implied by the price of a yield token
+pub fn implied_apy_from_yield_price(price: f64, years: f64) -> Option<f64> {
+	if !price.is_finite() || price >= 1.0 {
+		return None;
+	}
+
+	implied_apy_from_principal_price(1.0 - price, years)
+}
+
+/// Returns the Macaulay duration of a principal token in years, a single payment at expiry
+/// makes it the time to expiry
+pub fn macaulay_duration(years: f64) -> f64 {
+	years.max(0.0)
+}
+
+/// Returns the modified duration of a principal token, the relative change in its price for
+/// a change in the implied APY
+pub fn modified_duration(implied_apy: f64, years: f64) -> Option<f64> {
+	growth(implied_apy, years)?;
+
+	Some(years / (1.0 + implied_apy))
+}
+
+/// Returns the convexity of a principal token, the second derivative of its price with
+/// respect to the implied APY relative to the price
+pub fn convexity(implied_apy: f64, years: f64) -> Option<f64> {
+	growth(implied_apy, years)?;
+
+	Some(years * (years + 1.0) / (1.0 + implied_apy).powi(2))
+}
+
+/// Returns the exposure to the underlying yield bought by one unit of the accounting asset
+/// spent on yield tokens
+pub fn yield_leverage(yield_price: f64) -> Option<f64> {
+	(yield_price.is_finite() && yield_price > 0.0).then(|| 1.0 / yield_price)
+}
+
+/// Converts a price in units of the accounting asset to units of the underlying token at an
+/// exchange rate scaled by `WAD`
+pub fn price_in_underlying(price: f64, exchange_rate: u128) -> Option<f64> {
+	(exchange_rate != 0).then(|| price * WAD as f64 / exchange_rate as f64)
+}
@@ -0,0 +1,143 @@
+use {
+	sclr_client::{
+		decode::{decode_instruction, decode_message},
+		error::TokenizerClientError,
+	},
+	sclr_token::{
+		instruction::{
+			self, CheckpointAccounts, DepositAndTokenizeAccounts, TokenizerInstruction,
+		},
+		math::WAD,
+		state::{PostExpiryYieldRecipient, TokenizerState},
+	},
+	solana_program::{
+		instruction::{AccountMeta, Instruction},
+		pubkey::Pubkey,
+	},
+	solana_sdk::message::{Message, VersionedMessage},
+};
+
+fn tokenizer_state() -> TokenizerState {
+	TokenizerState {
+		bump: 255,
+		authority: Pubkey::new_unique(),
+		principal_token_mint: Pubkey::new_unique(),
+		yield_token_mint: Pubkey::new_unique(),
+		underlying_mint: Pubkey::new_unique(),
+		underlying_vault: Pubkey::new_unique(),
+		expiry_date: 1_735_689_600,
+		fixed_apy: 0,
+		grace_period: 30 * 86_400,
+		stake_pool: Pubkey::new_unique(),
+		expiry_exchange_rate: 0,
+		post_expiry_yield: PostExpiryYieldRecipient::Treasury,
+		treasury: Pubkey::new_unique(),
+		expiry_yield_reserve: 0,
+		yield_index: WAD,
+		last_update_slot: 0,
+		last_update_timestamp: 0,
+	}
+}
+
+fn account_addresses(instruction: &Instruction) -> Vec<Pubkey> {
+	instruction
+		.accounts
+		.iter()
+		.map(|account| account.pubkey)
+		.collect()
+}
+
+#[test]
+fn instruction_round_trips() {
+	let tokenizer = Pubkey::new_unique();
+	let state = tokenizer_state();
+	let user = Pubkey::new_unique();
+	let instruction = instruction::deposit_and_tokenize(
+		&DepositAndTokenizeAccounts::resolve(&tokenizer, &state, &user),
+		100,
+	)
+	.unwrap();
+
+	let decoded =
+		decode_instruction(2, &account_addresses(&instruction), &instruction.data).unwrap();
+
+	assert_eq!(decoded.index, 2);
+	assert_eq!(decoded.name, "depositAndTokenize");
+	assert_eq!(
+		decoded.instruction,
+		TokenizerInstruction::DepositAndTokenize { amount: 100 }
+	);
+	assert_eq!(decoded.accounts.len(), instruction.accounts.len());
+	assert_eq!(decoded.accounts[0].name, "tokenizer");
+	assert_eq!(decoded.accounts[0].address, tokenizer);
+	assert!(decoded
+		.accounts
+		.iter()
+		.zip(&instruction.accounts)
+		.all(|(decoded, account)| decoded.address == account.pubkey && decoded.name != "remaining"));
+}
+
+#[test]
+fn extra_accounts_are_labelled_as_remaining() {
+	let tokenizer = Pubkey::new_unique();
+	let stake_pool = Pubkey::new_unique();
+	let extra = Pubkey::new_unique();
+	let instruction =
+		instruction::checkpoint(&CheckpointAccounts::resolve(&tokenizer, &stake_pool)).unwrap();
+
+	let decoded = decode_instruction(
+		0,
+		&[tokenizer, stake_pool, extra],
+		&instruction.data,
+	)
+	.unwrap();
+
+	assert_eq!(decoded.name, "checkpoint");
+	assert_eq!(decoded.accounts[1].name, "stakePool");
+	assert_eq!(decoded.accounts[2].name, "remaining");
+	assert_eq!(decoded.accounts[2].label, "Remaining account");
+	assert_eq!(decoded.accounts[2].address, extra);
+}
+
+#[test]
+fn invalid_instruction_data_is_rejected() {
+	for data in [&[][..], &[u8::MAX][..], &[0, 1][..]] {
+		assert!(matches!(
+			decode_instruction(0, &[], data),
+			Err(TokenizerClientError::InvalidInstructionData)
+		));
+	}
+}
+
+#[test]
+fn message_decodes_only_tokenizer_instructions() {
+	let payer = Pubkey::new_unique();
+	let tokenizer = Pubkey::new_unique();
+	let stake_pool = Pubkey::new_unique();
+
+	let message = Message::new(
+		&[
+			Instruction::new_with_bytes(
+				Pubkey::new_unique(),
+				&[0],
+				vec![AccountMeta::new(payer, true)],
+			),
+			instruction::checkpoint(&CheckpointAccounts::resolve(&tokenizer, &stake_pool)).unwrap(),
+		],
+		Some(&payer),
+	);
+
+	let decoded = decode_message(&VersionedMessage::Legacy(message), &[]).unwrap();
+
+	assert_eq!(decoded.len(), 1);
+	assert_eq!(decoded[0].index, 1);
+	assert_eq!(decoded[0].instruction, TokenizerInstruction::Checkpoint);
+	assert_eq!(
+		decoded[0]
+			.accounts
+			.iter()
+			.map(|account| account.address)
+			.collect::<Vec<_>>(),
+		vec![tokenizer, stake_pool]
+	);
+}
@@ -0,0 +1,118 @@
+use {
+	base64::{engine::general_purpose::STANDARD as BASE64, Engine},
+	sclr_client::event::parse_events,
+	sclr_token::event::{TokenizerEvent, EVENT_TAG},
+	solana_program::pubkey::Pubkey,
+};
+
+fn deposit_event(tokenizer: &Pubkey) -> TokenizerEvent {
+	TokenizerEvent::UnderlyingDeposited {
+		tokenizer: *tokenizer,
+		user: Pubkey::new_unique(),
+		underlying_amount: 100,
+		timestamp: 1_700_000_000,
+	}
+}
+
+// The log of `sol_log_data(&[&EVENT_TAG, &data])`
+fn data_log(event: &TokenizerEvent) -> String {
+	format!(
+		"Program data: {} {}",
+		BASE64.encode(EVENT_TAG),
+		BASE64.encode(borsh::to_vec(event).unwrap())
+	)
+}
+
+fn invoke(program: &Pubkey, depth: usize) -> String {
+	format!("Program {} invoke [{}]", program, depth)
+}
+
+fn success(program: &Pubkey) -> String {
+	format!("Program {} success", program)
+}
+
+#[test]
+fn events_of_the_tokenizer_are_parsed() {
+	let tokenizer = Pubkey::new_unique();
+	let event = deposit_event(&tokenizer);
+	let checkpoint = TokenizerEvent::Checkpoint {
+		tokenizer,
+		yield_index: 1,
+		slot: 2,
+		timestamp: 3,
+	};
+
+	let logs = vec![
+		invoke(&sclr_token::id(), 1),
+		"Program log: Instruction: DepositAndTokenize".to_string(),
+		data_log(&event),
+		data_log(&checkpoint),
+		format!("Program {} consumed 5000 of 200000 compute units", sclr_token::id()),
+		success(&sclr_token::id()),
+	];
+
+	let events = parse_events(&logs);
+
+	assert_eq!(events, vec![event, checkpoint]);
+	assert_eq!(events[0].tokenizer(), &tokenizer);
+	assert_eq!(events[0].timestamp(), 1_700_000_000);
+}
+
+#[test]
+fn data_logged_by_other_programs_is_ignored() {
+	let tokenizer = Pubkey::new_unique();
+	let other_program = Pubkey::new_unique();
+	let event = deposit_event(&tokenizer);
+
+	let logs = vec![
+		invoke(&other_program, 1),
+		data_log(&deposit_event(&Pubkey::new_unique())),
+		// The tokenizer invoked by another program
+		invoke(&sclr_token::id(), 2),
+		// A program invoked by the tokenizer
+		invoke(&spl_token::id(), 3),
+		data_log(&deposit_event(&Pubkey::new_unique())),
+		success(&spl_token::id()),
+		data_log(&event),
+		success(&sclr_token::id()),
+		data_log(&deposit_event(&Pubkey::new_unique())),
+		success(&other_program),
+	];
+
+	assert_eq!(parse_events(&logs), vec![event]);
+}
+
+#[test]
+fn failed_invocations_are_unwound() {
+	let tokenizer = Pubkey::new_unique();
+	let event = deposit_event(&tokenizer);
+
+	let logs = vec![
+		invoke(&sclr_token::id(), 1),
+		invoke(&spl_token::id(), 2),
+		format!("Program {} failed: insufficient funds", spl_token::id()),
+		data_log(&event),
+		format!("Program {} failed: custom program error: 0x1", sclr_token::id()),
+		data_log(&deposit_event(&Pubkey::new_unique())),
+	];
+
+	assert_eq!(parse_events(&logs), vec![event]);
+}
+
+#[test]
+fn data_that_is_not_an_event_is_ignored() {
+	let event = deposit_event(&Pubkey::new_unique());
+	let data = BASE64.encode(borsh::to_vec(&event).unwrap());
+
+	let logs = vec![
+		invoke(&sclr_token::id(), 1),
+		// Untagged, wrongly tagged, truncated and malformed data
+		format!("Program data: {}", data),
+		format!("Program data: {} {}", BASE64.encode(b"not_evt!"), data),
+		format!("Program data: {} {}", BASE64.encode(EVENT_TAG), &data[..8]),
+		format!("Program data: {} not-base64!", BASE64.encode(EVENT_TAG)),
+		success(&sclr_token::id()),
+	];
+
+	assert!(parse_events(&logs).is_empty());
+}
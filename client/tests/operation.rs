@@ -0,0 +1,243 @@
+use {
+	borsh::to_vec,
+	futures::executor::block_on,
+	sclr_client::{
+		accounts::{EscrowAccounts, TokenizerAccounts},
+		error::TokenizerClientError,
+		nonblocking::TokenizerClient as NonblockingTokenizerClient,
+		operation::{Operation, OperationAccounts},
+		TokenizerClient,
+	},
+	sclr_token::{
+		get_escrow_address,
+		instruction::{
+			self, ClaimEscrowYieldAccounts, DepositAndTokenizeAccounts,
+			RedeemEscrowPrincipalAccounts, SettleAccounts,
+		},
+		math::WAD,
+		state::{ClaimsEscrowState, PostExpiryYieldRecipient, TokenizerState},
+	},
+	serde_json::{json, Value},
+	solana_account_decoder::{encode_ui_account, UiAccountEncoding},
+	solana_client::{
+		nonblocking::rpc_client::RpcClient as NonblockingRpcClient,
+		rpc_client::RpcClient,
+		rpc_request::RpcRequest,
+	},
+	solana_program::pubkey::Pubkey,
+	solana_sdk::{
+		account::Account,
+		signature::{Keypair, Signer},
+	},
+	spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+	std::{collections::HashMap, sync::Arc},
+};
+
+const EXPIRY_DATE: i64 = 1_735_689_600;
+
+fn tokenizer_state() -> TokenizerState {
+	TokenizerState {
+		bump: 255,
+		authority: Pubkey::new_unique(),
+		principal_token_mint: Pubkey::new_unique(),
+		yield_token_mint: Pubkey::new_unique(),
+		underlying_mint: Pubkey::new_unique(),
+		underlying_vault: Pubkey::new_unique(),
+		expiry_date: EXPIRY_DATE,
+		fixed_apy: 0,
+		grace_period: 30 * 86_400,
+		stake_pool: Pubkey::new_unique(),
+		expiry_exchange_rate: 0,
+		post_expiry_yield: PostExpiryYieldRecipient::Treasury,
+		treasury: Pubkey::new_unique(),
+		expiry_yield_reserve: 0,
+		yield_index: WAD,
+		last_update_slot: 0,
+		last_update_timestamp: 0,
+	}
+}
+
+fn escrow_state(tokenizer: &Pubkey, underlying_mint: &Pubkey) -> ClaimsEscrowState {
+	ClaimsEscrowState {
+		bump: 255,
+		tokenizer: *tokenizer,
+		principal_token_mint: Pubkey::new_unique(),
+		yield_token_mint: Pubkey::new_unique(),
+		underlying_mint: *underlying_mint,
+		escrow_vault: Pubkey::new_unique(),
+		expiry_date: EXPIRY_DATE,
+		expiry_exchange_rate: WAD,
+		expiry_yield_reserve: 0,
+	}
+}
+
+// The response of `getAccountInfo` for a claims escrow owned by the tokenizer program
+fn escrow_account_info(tokenizer: &Pubkey, escrow_state: &ClaimsEscrowState) -> Value {
+	let (escrow, _) = get_escrow_address(tokenizer);
+	let account = Account {
+		lamports: 1_000_000,
+		data: to_vec(escrow_state).unwrap(),
+		owner: sclr_token::id(),
+		executable: false,
+		rent_epoch: 0,
+	};
+
+	json!({
+		"context": { "slot": 1 },
+		"value": encode_ui_account(&escrow, &account, UiAccountEncoding::Base64, None, None),
+	})
+}
+
+fn mocks(account_info: Option<Value>) -> HashMap<RpcRequest, Value> {
+	account_info
+		.map(|account_info| HashMap::from([(RpcRequest::GetAccountInfo, account_info)]))
+		.unwrap_or_default()
+}
+
+#[test]
+fn tokenizer_instructions_use_resolved_accounts() {
+	let tokenizer = Pubkey::new_unique();
+	let state = tokenizer_state();
+	let payer = Pubkey::new_unique();
+	let owner = Pubkey::new_unique();
+	let accounts = OperationAccounts::Tokenizer(Box::new(TokenizerAccounts::new(&tokenizer, &state)));
+
+	let instructions = Operation::DepositAndTokenize { amount: 100 }
+		.instructions(&accounts, &payer, &owner)
+		.unwrap();
+	assert_eq!(
+		instructions,
+		vec![
+			create_associated_token_account_idempotent(
+				&payer,
+				&owner,
+				&state.principal_token_mint,
+				&spl_token::id()
+			),
+			create_associated_token_account_idempotent(
+				&payer,
+				&owner,
+				&state.yield_token_mint,
+				&spl_token::id()
+			),
+			instruction::deposit_and_tokenize(
+				&DepositAndTokenizeAccounts::resolve(&tokenizer, &state, &owner),
+				100
+			)
+			.unwrap(),
+		]
+	);
+
+	let instructions = Operation::Settle.instructions(&accounts, &payer, &owner).unwrap();
+	assert_eq!(
+		instructions,
+		vec![instruction::settle(&SettleAccounts::resolve(
+			&tokenizer,
+			&state.underlying_mint,
+			&state.stake_pool,
+			&state.treasury
+		))
+		.unwrap()]
+	);
+}
+
+#[test]
+fn escrow_instructions_resolve_without_the_tokenizer() {
+	let tokenizer = Pubkey::new_unique();
+	let underlying_mint = Pubkey::new_unique();
+	let payer = Pubkey::new_unique();
+	let owner = Pubkey::new_unique();
+	let accounts = OperationAccounts::Escrow(EscrowAccounts::new(&tokenizer, &underlying_mint));
+	let create_underlying_account =
+		create_associated_token_account_idempotent(&payer, &owner, &underlying_mint, &spl_token::id());
+
+	let instructions = Operation::RedeemEscrowPrincipal { principal_amount: 100 }
+		.instructions(&accounts, &payer, &owner)
+		.unwrap();
+	assert_eq!(
+		instructions,
+		vec![
+			create_underlying_account.clone(),
+			instruction::redeem_escrow_principal(
+				&RedeemEscrowPrincipalAccounts::resolve(&tokenizer, &underlying_mint, &owner),
+				100
+			)
+			.unwrap(),
+		]
+	);
+
+	let instructions = Operation::ClaimEscrowYield { yield_amount: 100 }
+		.instructions(&accounts, &payer, &owner)
+		.unwrap();
+	assert_eq!(
+		instructions,
+		vec![
+			create_underlying_account,
+			instruction::claim_escrow_yield(
+				&ClaimEscrowYieldAccounts::resolve(&tokenizer, &underlying_mint, &owner),
+				100
+			)
+			.unwrap(),
+		]
+	);
+
+	// Tokenizer operations cannot be built from the escrow
+	assert!(matches!(
+		Operation::Settle.instructions(&accounts, &payer, &owner),
+		Err(TokenizerClientError::AccountNotFound(address)) if address == tokenizer
+	));
+}
+
+#[test]
+fn escrow_operations_succeed_once_the_tokenizer_is_terminated() {
+	let tokenizer = Pubkey::new_unique();
+	let underlying_mint = Pubkey::new_unique();
+	let escrow_state = escrow_state(&tokenizer, &underlying_mint);
+	let payer: Arc<dyn Signer + Send + Sync> = Arc::new(Keypair::new());
+
+	// The mock only serves the escrow, the terminated tokenizer account does not exist
+	let rpc_client = RpcClient::new_mock_with_mocks(
+		"succeeds".to_string(),
+		mocks(Some(escrow_account_info(&tokenizer, &escrow_state))),
+	);
+	let client = TokenizerClient::new(rpc_client, payer.clone());
+	assert_eq!(
+		client.fetch_escrow_accounts(&tokenizer).unwrap(),
+		EscrowAccounts::new(&tokenizer, &underlying_mint)
+	);
+
+	let rpc_client = RpcClient::new_mock_with_mocks(
+		"succeeds".to_string(),
+		mocks(Some(escrow_account_info(&tokenizer, &escrow_state))),
+	);
+	let client = TokenizerClient::new(rpc_client, payer.clone());
+	client.redeem_escrow_principal(&tokenizer, 100).unwrap();
+
+	let rpc_client = NonblockingRpcClient::new_mock_with_mocks(
+		"succeeds".to_string(),
+		mocks(Some(escrow_account_info(&tokenizer, &escrow_state))),
+	);
+	let client = NonblockingTokenizerClient::new(rpc_client, payer);
+	block_on(client.claim_escrow_yield(&tokenizer, 100)).unwrap();
+}
+
+#[test]
+fn escrow_operations_fail_before_the_sweep() {
+	let tokenizer = Pubkey::new_unique();
+	let (escrow, _) = get_escrow_address(&tokenizer);
+	let payer: Arc<dyn Signer + Send + Sync> = Arc::new(Keypair::new());
+
+	let rpc_client = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks(None));
+	let client = TokenizerClient::new(rpc_client, payer.clone());
+	assert!(matches!(
+		client.redeem_escrow_principal(&tokenizer, 100),
+		Err(TokenizerClientError::AccountNotFound(address)) if address == escrow
+	));
+
+	let rpc_client = NonblockingRpcClient::new_mock_with_mocks("succeeds".to_string(), mocks(None));
+	let client = NonblockingTokenizerClient::new(rpc_client, payer);
+	assert!(matches!(
+		block_on(client.claim_escrow_yield(&tokenizer, 100)),
+		Err(TokenizerClientError::AccountNotFound(address)) if address == escrow
+	));
+}
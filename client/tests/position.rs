@@ -0,0 +1,253 @@
+use {
+	sclr_client::position::{
+		redeemable_amounts, token_balances_by_mint, RedeemableAmounts, TokenizerBalances,
+	},
+	sclr_token::{
+		math::WAD,
+		state::{ClaimsEscrowState, PostExpiryYieldRecipient, TokenizerState},
+	},
+	serde_json::json,
+	solana_account_decoder::{
+		encode_ui_account, parse_account_data::ParsedAccount, UiAccount, UiAccountData,
+		UiAccountEncoding,
+	},
+	solana_client::rpc_response::RpcKeyedAccount,
+	solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey},
+	solana_sdk::account::Account,
+	spl_token::state::{Account as TokenAccount, AccountState},
+};
+
+const ONE: u64 = 1_000_000;
+const EXPIRY_DATE: i64 = 1_735_689_600;
+
+fn rate_bps(bps: u128) -> u128 {
+	WAD * bps / 10_000
+}
+
+fn tokenizer_state(post_expiry_yield: PostExpiryYieldRecipient) -> TokenizerState {
+	TokenizerState {
+		bump: 255,
+		authority: Pubkey::new_unique(),
+		principal_token_mint: Pubkey::new_unique(),
+		yield_token_mint: Pubkey::new_unique(),
+		underlying_mint: Pubkey::new_unique(),
+		underlying_vault: Pubkey::new_unique(),
+		expiry_date: EXPIRY_DATE,
+		fixed_apy: 0,
+		grace_period: 30 * 86_400,
+		stake_pool: Pubkey::new_unique(),
+		expiry_exchange_rate: 0,
+		post_expiry_yield,
+		treasury: Pubkey::new_unique(),
+		expiry_yield_reserve: 0,
+		yield_index: WAD,
+		last_update_slot: 0,
+		last_update_timestamp: 0,
+	}
+}
+
+// 150 principal and yield tokens backed by 150 underlying
+fn balances(exchange_rate: u128) -> TokenizerBalances {
+	TokenizerBalances {
+		vault_balance: 150 * ONE,
+		principal_supply: 150 * ONE,
+		yield_supply: 150 * ONE,
+		exchange_rate,
+		escrow: None,
+	}
+}
+
+fn redeemable(
+	state: &TokenizerState,
+	balances: &TokenizerBalances,
+	timestamp: i64,
+) -> RedeemableAmounts {
+	redeemable_amounts(state, balances, 100 * ONE, 50 * ONE, timestamp).unwrap()
+}
+
+#[test]
+fn before_expiry_only_paired_principal_is_redeemable() {
+	let state = tokenizer_state(PostExpiryYieldRecipient::PrincipalHolders);
+
+	// At 1.04 the 150 principal tokens are backed by 144.230770 underlying, the 5.769230
+	// surplus is shared by the 150 yield tokens
+	assert_eq!(
+		redeemable(&state, &balances(rate_bps(10_400)), EXPIRY_DATE),
+		RedeemableAmounts {
+			principal: 48_076_923,
+			yield_claim: 1_923_076,
+		}
+	);
+
+	// Without a surplus there is no yield to claim
+	assert_eq!(
+		redeemable(&state, &balances(WAD), EXPIRY_DATE - 1),
+		RedeemableAmounts {
+			principal: 50 * ONE,
+			yield_claim: 0,
+		}
+	);
+}
+
+#[test]
+fn after_expiry_unsettled_tokenizer_uses_current_rate() {
+	let state = tokenizer_state(PostExpiryYieldRecipient::PrincipalHolders);
+
+	// The expiry would be settled at 1.06, reserving 8.490566 for the yield tokens
+	assert_eq!(
+		redeemable(&state, &balances(rate_bps(10_600)), EXPIRY_DATE + 1),
+		RedeemableAmounts {
+			principal: 94_339_622,
+			yield_claim: 2_830_188,
+		}
+	);
+}
+
+#[test]
+fn after_expiry_principal_holders_redeem_at_expiry_rate() {
+	let mut state = tokenizer_state(PostExpiryYieldRecipient::PrincipalHolders);
+	state.expiry_exchange_rate = rate_bps(10_500);
+	state.expiry_yield_reserve = 7_142_857;
+
+	for exchange_rate in [rate_bps(10_400), rate_bps(10_600)] {
+		assert_eq!(
+			redeemable(&state, &balances(exchange_rate), EXPIRY_DATE + 1),
+			RedeemableAmounts {
+				principal: 95_238_095,
+				yield_claim: 2_380_952,
+			}
+		);
+	}
+}
+
+#[test]
+fn after_expiry_treasury_mode_redeems_at_current_rate_floored_at_expiry_rate() {
+	let mut state = tokenizer_state(PostExpiryYieldRecipient::Treasury);
+	state.expiry_exchange_rate = rate_bps(10_500);
+	state.expiry_yield_reserve = 7_142_857;
+
+	assert_eq!(
+		redeemable(&state, &balances(rate_bps(10_600)), EXPIRY_DATE + 1),
+		RedeemableAmounts {
+			principal: 94_339_622,
+			yield_claim: 2_380_952,
+		}
+	);
+	assert_eq!(
+		redeemable(&state, &balances(rate_bps(10_400)), EXPIRY_DATE + 1),
+		RedeemableAmounts {
+			principal: 95_238_095,
+			yield_claim: 2_380_952,
+		}
+	);
+}
+
+#[test]
+fn after_sweep_amounts_are_paid_from_the_escrow() {
+	let state = tokenizer_state(PostExpiryYieldRecipient::Treasury);
+	let mut balances = balances(rate_bps(10_600));
+	balances.vault_balance = 0;
+	balances.escrow = Some(ClaimsEscrowState {
+		bump: 255,
+		tokenizer: Pubkey::new_unique(),
+		principal_token_mint: state.principal_token_mint,
+		yield_token_mint: state.yield_token_mint,
+		underlying_mint: state.underlying_mint,
+		escrow_vault: Pubkey::new_unique(),
+		expiry_date: EXPIRY_DATE,
+		expiry_exchange_rate: rate_bps(10_500),
+		expiry_yield_reserve: 3 * ONE,
+	});
+
+	assert_eq!(
+		redeemable(&state, &balances, EXPIRY_DATE + 60 * 86_400),
+		RedeemableAmounts {
+			principal: 95_238_095,
+			yield_claim: ONE,
+		}
+	);
+}
+
+fn token_account(mint: &Pubkey, amount: u64) -> Account {
+	let mut data = vec![0; TokenAccount::LEN];
+	TokenAccount {
+		mint: *mint,
+		owner: Pubkey::new_unique(),
+		amount,
+		delegate: COption::None,
+		state: AccountState::Initialized,
+		is_native: COption::None,
+		delegated_amount: 0,
+		close_authority: COption::None,
+	}
+	.pack_into_slice(&mut data);
+
+	Account {
+		lamports: 2_039_280,
+		data,
+		owner: spl_token::id(),
+		executable: false,
+		rent_epoch: 0,
+	}
+}
+
+fn parsed_token_account(mint: &Pubkey, amount: u64) -> UiAccount {
+	let mut account = encode_ui_account(
+		&Pubkey::new_unique(),
+		&token_account(mint, amount),
+		UiAccountEncoding::Base64,
+		None,
+		None,
+	);
+	account.data = UiAccountData::Json(ParsedAccount {
+		program: "spl-token".to_string(),
+		parsed: json!({
+			"type": "account",
+			"info": {
+				"mint": mint.to_string(),
+				"owner": Pubkey::new_unique().to_string(),
+				"tokenAmount": {
+					"uiAmount": amount as f64 / ONE as f64,
+					"decimals": 6,
+					"amount": amount.to_string(),
+					"uiAmountString": (amount as f64 / ONE as f64).to_string(),
+				},
+				"state": "initialized",
+				"isNative": false,
+			},
+		}),
+		space: TokenAccount::LEN as u64,
+	});
+
+	account
+}
+
+#[test]
+fn token_balances_are_summed_by_mint() {
+	let principal_mint = Pubkey::new_unique();
+	let yield_mint = Pubkey::new_unique();
+
+	let keyed_accounts = [
+		parsed_token_account(&principal_mint, 2 * ONE),
+		parsed_token_account(&principal_mint, 3 * ONE),
+		encode_ui_account(
+			&Pubkey::new_unique(),
+			&token_account(&yield_mint, ONE),
+			UiAccountEncoding::Base64,
+			None,
+			None,
+		),
+	]
+	.into_iter()
+	.map(|account| RpcKeyedAccount {
+		pubkey: Pubkey::new_unique().to_string(),
+		account,
+	})
+	.collect::<Vec<_>>();
+
+	let balances = token_balances_by_mint(&keyed_accounts);
+
+	assert_eq!(balances.len(), 2);
+	assert_eq!(balances[&principal_mint], 5 * ONE);
+	assert_eq!(balances[&yield_mint], ONE);
+}
@@ -0,0 +1,352 @@
+use {
+	sclr_client::pricing::{
+		apy_from_bps, apy_to_bps, convexity, implied_apy_from_principal_price,
+		implied_apy_from_yield_price, macaulay_duration, modified_duration, price_in_underlying,
+		principal_price, years_to_expiry, yield_fair_value, yield_leverage, yield_price,
+	},
+	sclr_token::{math::WAD, rate::SECONDS_PER_YEAR},
+};
+
+const EPSILON: f64 = 1e-9;
+
+const APYS: [f64; 7] = [-0.5, -0.01, 0.0, 0.01, 0.05, 0.2, 1.5];
+const YEARS: [f64; 7] = [0.0, 1.0 / 365.0, 0.25, 0.5, 1.0, 2.0, 10.0];
+
+fn assert_close(actual: f64, expected: f64) {
+	assert!(
+		(actual - expected).abs() <= EPSILON * expected.abs().max(1.0),
+		"expected {}, got {}",
+		expected,
+		actual
+	);
+}
+
+#[test]
+fn years_to_expiry_counts_down_to_zero() {
+	let year = SECONDS_PER_YEAR as i64;
+
+	assert_close(years_to_expiry(year, 0), 1.0);
+	assert_close(years_to_expiry(1_000 + year / 2, 1_000), 0.5);
+	assert_close(years_to_expiry(3 * year, year), 2.0);
+	assert_close(years_to_expiry(1_000, 1_000), 0.0);
+	assert_close(years_to_expiry(1_000, 2_000), 0.0);
+	assert_close(
+		years_to_expiry(i64::MAX, i64::MIN),
+		i64::MAX as f64 / year as f64,
+	);
+}
+
+#[test]
+fn bps_convert_to_and_from_fractions() {
+	assert_close(apy_from_bps(0), 0.0);
+	assert_close(apy_from_bps(1), 0.0001);
+	assert_close(apy_from_bps(500), 0.05);
+	assert_close(apy_from_bps(10_000), 1.0);
+
+	assert_eq!(apy_to_bps(0.05), Some(500));
+	assert_eq!(apy_to_bps(-0.0125), Some(-125));
+	assert_eq!(apy_to_bps(0.000_049), Some(0));
+	assert_eq!(apy_to_bps(0.000_051), Some(1));
+	assert_eq!(apy_to_bps(f64::NAN), None);
+	assert_eq!(apy_to_bps(f64::INFINITY), None);
+	assert_eq!(apy_to_bps(1e20), None);
+
+	for bps in [0, 1, 99, 500, 1_234, 10_000, 250_000] {
+		assert_eq!(apy_to_bps(apy_from_bps(bps)), Some(bps as i64));
+	}
+}
+
+#[test]
+fn principal_price_matches_known_values() {
+	// 1 / (1 + r)^t
+	assert_close(principal_price(0.05, 1.0).unwrap(), 0.952_380_952_380_952_4);
+	assert_close(principal_price(0.1, 2.0).unwrap(), 0.826_446_280_991_735_5);
+	assert_close(principal_price(0.04, 0.5).unwrap(), 0.980_580_675_690_920_2);
+	assert_close(
+		principal_price(0.08, 10.0).unwrap(),
+		0.463_193_488_629_728_6,
+	);
+	assert_close(
+		principal_price(-0.02, 1.0).unwrap(),
+		1.020_408_163_265_306_1,
+	);
+	assert_close(principal_price(1.0, 3.0).unwrap(), 0.125);
+}
+
+#[test]
+fn principal_price_is_par_without_time_or_yield() {
+	for apy in APYS {
+		assert_close(principal_price(apy, 0.0).unwrap(), 1.0);
+	}
+
+	for years in YEARS {
+		assert_close(principal_price(0.0, years).unwrap(), 1.0);
+	}
+}
+
+#[test]
+fn principal_price_falls_with_apy_and_time() {
+	for years in YEARS.iter().filter(|years| **years > 0.0) {
+		for pair in APYS.windows(2) {
+			assert!(
+				principal_price(pair[0], *years).unwrap()
+					> principal_price(pair[1], *years).unwrap()
+			);
+		}
+	}
+
+	for apy in APYS.iter().filter(|apy| **apy > 0.0) {
+		for pair in YEARS.windows(2) {
+			assert!(
+				principal_price(*apy, pair[0]).unwrap() > principal_price(*apy, pair[1]).unwrap()
+			);
+		}
+	}
+}
+
+#[test]
+fn principal_price_compounds_across_periods() {
+	// Discounting over t1 + t2 is discounting over t1 then over t2
+	for apy in APYS {
+		for (first, second) in [(0.25, 0.75), (1.0, 1.0), (0.5, 9.5)] {
+			assert_close(
+				principal_price(apy, first + second).unwrap(),
+				principal_price(apy, first).unwrap() * principal_price(apy, second).unwrap(),
+			);
+		}
+	}
+}
+
+#[test]
+fn principal_price_rejects_invalid_inputs() {
+	assert_eq!(principal_price(-1.0, 1.0), None);
+	assert_eq!(principal_price(-1.5, 1.0), None);
+	assert_eq!(principal_price(0.05, -1.0), None);
+	assert_eq!(principal_price(f64::NAN, 1.0), None);
+	assert_eq!(principal_price(0.05, f64::NAN), None);
+	assert_eq!(principal_price(f64::INFINITY, 1.0), None);
+	assert_eq!(principal_price(0.05, f64::INFINITY), None);
+}
+
+#[test]
+fn principal_and_yield_prices_sum_to_one() {
+	for apy in APYS {
+		for years in YEARS {
+			let principal = principal_price(apy, years).unwrap();
+			let yield_token = yield_price(apy, years).unwrap();
+
+			assert_close(principal + yield_token, 1.0);
+		}
+	}
+
+	assert_close(yield_price(0.05, 1.0).unwrap(), 0.047_619_047_619_047_6);
+	assert_close(yield_price(0.1, 2.0).unwrap(), 0.173_553_719_008_264_5);
+	assert_eq!(yield_price(-1.0, 1.0), None);
+}
+
+#[test]
+fn yield_fair_value_matches_known_values() {
+	// ((1 + g)^t - 1) / (1 + r)^t
+	assert_close(yield_fair_value(0.07, 0.05, 1.0).unwrap(), 0.07 / 1.05);
+	assert_close(yield_fair_value(0.1, 0.05, 2.0).unwrap(), 0.21 / 1.1025);
+	assert_close(
+		yield_fair_value(0.03, 0.06, 0.5).unwrap(),
+		(1.03_f64.sqrt() - 1.0) / 1.06_f64.sqrt(),
+	);
+}
+
+#[test]
+fn yield_fair_value_is_yield_price_at_the_implied_apy() {
+	for apy in APYS {
+		for years in YEARS {
+			assert_close(
+				yield_fair_value(apy, apy, years).unwrap(),
+				yield_price(apy, years).unwrap(),
+			);
+		}
+	}
+}
+
+#[test]
+fn yield_fair_value_bounds() {
+	for implied_apy in APYS {
+		for years in YEARS {
+			// No growth is worth nothing and no time leaves nothing to accrue
+			assert_close(yield_fair_value(0.0, implied_apy, years).unwrap(), 0.0);
+			assert_close(yield_fair_value(implied_apy, 0.05, 0.0).unwrap(), 0.0);
+		}
+	}
+
+	// Higher expected yield is worth more, a falling exchange rate is worth less than nothing
+	for years in YEARS.iter().filter(|years| **years > 0.0) {
+		for pair in APYS.windows(2) {
+			assert!(
+				yield_fair_value(pair[0], 0.05, *years).unwrap()
+					< yield_fair_value(pair[1], 0.05, *years).unwrap()
+			);
+		}
+
+		assert!(yield_fair_value(-0.01, 0.05, *years).unwrap() < 0.0);
+	}
+
+	assert_eq!(yield_fair_value(-1.0, 0.05, 1.0), None);
+	assert_eq!(yield_fair_value(0.05, -1.0, 1.0), None);
+	assert_eq!(yield_fair_value(0.05, 0.05, -1.0), None);
+}
+
+#[test]
+fn implied_apy_matches_known_values() {
+	// (1 / p)^(1 / t) - 1
+	assert_close(
+		implied_apy_from_principal_price(0.95, 1.0).unwrap(),
+		0.052_631_578_947_368_4,
+	);
+	assert_close(
+		implied_apy_from_principal_price(0.81, 2.0).unwrap(),
+		1.0 / 0.9 - 1.0,
+	);
+	assert_close(
+		implied_apy_from_principal_price(0.98, 0.5).unwrap(),
+		0.041_232_819_658_475_6,
+	);
+	assert_close(implied_apy_from_principal_price(1.0, 1.0).unwrap(), 0.0);
+
+	// A principal token above par implies a negative yield
+	assert!(implied_apy_from_principal_price(1.01, 1.0).unwrap() < 0.0);
+
+	assert_close(
+		implied_apy_from_yield_price(0.05, 1.0).unwrap(),
+		0.052_631_578_947_368_4,
+	);
+	assert_close(implied_apy_from_yield_price(0.0, 1.0).unwrap(), 0.0);
+}
+
+#[test]
+fn implied_apy_inverts_the_prices() {
+	for apy in APYS {
+		for years in YEARS.iter().filter(|years| **years > 0.0) {
+			let principal = principal_price(apy, *years).unwrap();
+			let yield_token = yield_price(apy, *years).unwrap();
+
+			assert_close(
+				implied_apy_from_principal_price(principal, *years).unwrap(),
+				apy,
+			);
+			assert_close(
+				implied_apy_from_yield_price(yield_token, *years).unwrap(),
+				apy,
+			);
+		}
+	}
+}
+
+#[test]
+fn implied_apy_rejects_invalid_inputs() {
+	// No time to expiry leaves the APY undefined
+	assert_eq!(implied_apy_from_principal_price(0.95, 0.0), None);
+	assert_eq!(implied_apy_from_principal_price(0.95, -1.0), None);
+	assert_eq!(implied_apy_from_principal_price(0.0, 1.0), None);
+	assert_eq!(implied_apy_from_principal_price(-0.5, 1.0), None);
+	assert_eq!(implied_apy_from_principal_price(f64::NAN, 1.0), None);
+	assert_eq!(implied_apy_from_principal_price(0.95, f64::INFINITY), None);
+
+	assert_eq!(implied_apy_from_yield_price(1.0, 1.0), None);
+	assert_eq!(implied_apy_from_yield_price(1.5, 1.0), None);
+	assert_eq!(implied_apy_from_yield_price(f64::NAN, 1.0), None);
+	assert_eq!(implied_apy_from_yield_price(0.05, 0.0), None);
+}
+
+#[test]
+fn duration_of_a_zero_coupon_is_its_maturity() {
+	for years in YEARS {
+		assert_close(macaulay_duration(years), years);
+	}
+	assert_close(macaulay_duration(-1.0), 0.0);
+
+	// D / (1 + r)
+	assert_close(modified_duration(0.05, 1.0).unwrap(), 1.0 / 1.05);
+	assert_close(
+		modified_duration(0.1, 2.0).unwrap(),
+		1.818_181_818_181_818_2,
+	);
+	assert_close(modified_duration(0.0, 5.0).unwrap(), 5.0);
+	assert_eq!(modified_duration(-1.0, 1.0), None);
+	assert_eq!(modified_duration(0.05, -1.0), None);
+
+	// t (t + 1) / (1 + r)^2
+	assert_close(convexity(0.1, 2.0).unwrap(), 4.958_677_685_950_413);
+	assert_close(convexity(0.05, 1.0).unwrap(), 2.0 / 1.1025);
+	assert_close(convexity(0.05, 0.0).unwrap(), 0.0);
+	assert_eq!(convexity(-1.0, 1.0), None);
+}
+
+#[test]
+fn duration_and_convexity_match_the_price_derivatives() {
+	let step = 1e-5;
+
+	for apy in APYS {
+		for years in YEARS {
+			let price = |apy: f64| principal_price(apy, years).unwrap();
+			let first_derivative = (price(apy + step) - price(apy - step)) / (2.0 * step);
+			let second_derivative =
+				(price(apy + step) - 2.0 * price(apy) + price(apy - step)) / (step * step);
+
+			let duration = modified_duration(apy, years).unwrap();
+			let expected_duration = -first_derivative / price(apy);
+			assert!(
+				(duration - expected_duration).abs() <= 1e-6 * duration.max(1.0),
+				"duration {} differs from {} at {} over {} years",
+				duration,
+				expected_duration,
+				apy,
+				years
+			);
+
+			let convexity = convexity(apy, years).unwrap();
+			let expected_convexity = second_derivative / price(apy);
+			assert!(
+				(convexity - expected_convexity).abs() <= 1e-3 * convexity.max(1.0),
+				"convexity {} differs from {} at {} over {} years",
+				convexity,
+				expected_convexity,
+				apy,
+				years
+			);
+		}
+	}
+}
+
+#[test]
+fn duration_and_convexity_approximate_price_changes() {
+	// dP / P = -D dr + C dr^2 / 2, with an error of the order of dr^3
+	let (apy, years, change) = (0.05, 2.0, 0.001);
+	let price = principal_price(apy, years).unwrap();
+	let shifted = principal_price(apy + change, years).unwrap();
+
+	let estimate = -modified_duration(apy, years).unwrap() * change
+		+ convexity(apy, years).unwrap() * change * change / 2.0;
+
+	assert!(((shifted - price) / price - estimate).abs() < 1e-8);
+}
+
+#[test]
+fn yield_leverage_is_the_inverse_of_the_price() {
+	assert_close(yield_leverage(0.05).unwrap(), 20.0);
+	assert_close(yield_leverage(1.0).unwrap(), 1.0);
+	assert_eq!(yield_leverage(0.0), None);
+	assert_eq!(yield_leverage(-0.1), None);
+	assert_eq!(yield_leverage(f64::NAN), None);
+
+	// Leverage grows as expiry approaches and the yield token price falls
+	let near = yield_leverage(yield_price(0.05, 0.1).unwrap()).unwrap();
+	let far = yield_leverage(yield_price(0.05, 1.0).unwrap()).unwrap();
+	assert!(near > far);
+}
+
+#[test]
+fn prices_convert_to_the_underlying_at_the_exchange_rate() {
+	assert_close(price_in_underlying(1.0, WAD).unwrap(), 1.0);
+	assert_close(price_in_underlying(0.95, WAD * 2).unwrap(), 0.475);
+	assert_close(price_in_underlying(1.0, WAD + WAD / 10).unwrap(), 1.0 / 1.1);
+	assert_eq!(price_in_underlying(1.0, 0), None);
+}
@@ -0,0 +1,480 @@
+use {
+	anyhow::{anyhow, Result},
+	borsh::{
+		schema::{BorshSchemaContainer, Declaration, Definition, Fields},
+		BorshSchema,
+	},
+	clap::Parser,
+	num_traits::FromPrimitive,
+	sclr_token::{
+		error::TokenizerError,
+		instruction::TokenizerInstruction,
+		state::{ClaimsEscrowState, TokenizerState},
+	},
+	serde_json::{json, Map, Value},
+	std::{collections::BTreeMap, fs, path::PathBuf},
+};
+
+// The account lists of the instructions are only documented on `TokenizerInstruction`, the
+// documentation is parsed so that it remains the single source of the account layouts
+const INSTRUCTION_SOURCE: &str = include_str!("../../program/src/instruction.rs");
+
+const PRIMITIVES: [&str; 13] = [
+	"u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "f32", "f64", "bool",
+];
+
+/// Generates the IDL of the tokenizer program in the Shank format, which can be read by
+/// Codama and Anchor based tooling to decode instructions, accounts and errors
+#[derive(Parser, Debug)]
+struct Cli {
+	/// Path of the IDL file, the IDL is printed if omitted. The client decodes instructions
+	/// with the IDL at `client/idl/scalar_tokenizer.json`
+	#[arg(short, long)]
+	out: Option<PathBuf>,
+}
+
+struct InstructionDocs {
+	docs: Vec<String>,
+	accounts: Vec<Value>,
+}
+
+// Converts a snake case identifier or a sentence to camel case
+fn camel_case(name: &str) -> String {
+	name.split(|c: char| c == '_' || c.is_whitespace())
+		.filter(|word| !word.is_empty())
+		.enumerate()
+		.map(|(index, word)| {
+			let word = word.to_lowercase();
+			if index == 0 {
+				return word;
+			}
+
+			let mut chars = word.chars();
+			chars
+				.next()
+				.map(|first| first.to_uppercase().chain(chars).collect())
+				.unwrap_or_default()
+		})
+		.collect()
+}
+
+// Names an account from its description, e.g. `Underlying vault account` is named
+// `underlyingVault` whereas token accounts keep their suffix
+fn account_name(description: &str) -> String {
+	let description = description.to_lowercase();
+	let name = match description.strip_suffix(" account") {
+		Some(name) if !name.ends_with(" token") => name,
+		_ => &description,
+	};
+
+	camel_case(name)
+}
+
+// Parses an account of an `Accounts expected` list, e.g. "0. `[writable]` Tokenizer account"
+fn parse_account(line: &str) -> Option<Value> {
+	let (index, rest) = line.split_once(". ")?;
+	index.parse::<usize>().ok()?;
+
+	let rest = rest.strip_prefix("`[")?;
+	let (flags, description) = rest.split_once("]`")?;
+	let flags = flags.split(',').map(str::trim).collect::<Vec<_>>();
+	let description = description.trim();
+
+	Some(json!({
+		"name": account_name(description),
+		"isMut": flags.contains(&"writable"),
+		"isSigner": flags.contains(&"signer"),
+		"docs": [description],
+	}))
+}
+
+// Parses the documentation of `TokenizerInstruction` itself, describing the program
+fn parse_program_docs(source: &str) -> Result<Vec<String>> {
+	let head = source
+		.split_once("pub enum TokenizerInstruction {")
+		.ok_or(anyhow!("Unable to find `TokenizerInstruction`"))?
+		.0;
+
+	let mut docs = head
+		.lines()
+		.rev()
+		.skip_while(|line| line.trim().is_empty())
+		.take_while(|line| line.starts_with("///") || line.starts_with("#["))
+		.filter_map(|line| line.strip_prefix("///"))
+		.map(|line| line.trim().to_string())
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>();
+	docs.reverse();
+
+	Ok(docs)
+}
+
+// Parses the documentation of each variant of `TokenizerInstruction`
+fn parse_instruction_docs(source: &str) -> Result<BTreeMap<String, InstructionDocs>> {
+	let body = source
+		.split_once("pub enum TokenizerInstruction {")
+		.ok_or(anyhow!("Unable to find `TokenizerInstruction`"))?
+		.1;
+
+	let mut instructions = BTreeMap::new();
+	let mut doc_lines = vec![];
+
+	for line in body.lines() {
+		if line.starts_with('}') {
+			break;
+		}
+
+		// Only the documentation of the variants is indented once, fields are indented twice
+		let Some(line) = line.strip_prefix('\t') else {
+			continue;
+		};
+
+		if let Some(doc_line) = line.strip_prefix("///") {
+			doc_lines.push(doc_line.trim().to_string());
+		} else if line.starts_with(|c: char| c.is_ascii_uppercase()) {
+			let name = line
+				.split(|c: char| !c.is_alphanumeric())
+				.next()
+				.unwrap_or_default()
+				.to_string();
+
+			let mut docs = vec![];
+			let mut accounts = vec![];
+			let mut in_accounts = false;
+
+			for doc_line in doc_lines.drain(..) {
+				if doc_line == "Accounts expected:" {
+					in_accounts = true;
+				} else if in_accounts {
+					accounts.extend(parse_account(&doc_line));
+				} else if !doc_line.is_empty() {
+					docs.push(doc_line);
+				}
+			}
+
+			instructions.insert(name, InstructionDocs { docs, accounts });
+		} else if !line.trim_start().starts_with("///") {
+			doc_lines.clear();
+		}
+	}
+
+	Ok(instructions)
+}
+
+struct IdlBuilder {
+	container: BorshSchemaContainer,
+	types: BTreeMap<String, Value>,
+}
+
+impl IdlBuilder {
+	fn new<T: BorshSchema>() -> Self {
+		IdlBuilder {
+			container: BorshSchemaContainer::for_type::<T>(),
+			types: BTreeMap::new(),
+		}
+	}
+
+	fn definition(&self, declaration: &Declaration) -> Result<&Definition> {
+		self.container
+			.get_definition(declaration)
+			.ok_or(anyhow!("Missing schema definition of `{}`", declaration))
+	}
+
+	// Returns the IDL type of a declaration, registering the definitions of structs and enums
+	fn idl_type(&mut self, declaration: &Declaration) -> Result<Value> {
+		if PRIMITIVES.contains(&declaration.as_str()) {
+			return Ok(json!(declaration));
+		}
+
+		match declaration.as_str() {
+			"String" => return Ok(json!("string")),
+			"Pubkey" => return Ok(json!("publicKey")),
+			_ => {}
+		}
+
+		match self.definition(declaration)?.clone() {
+			Definition::Sequence {
+				length_width,
+				length_range,
+				elements,
+			} => {
+				let elements = self.idl_type(&elements)?;
+				if length_width == Definition::ARRAY_LENGTH_WIDTH
+					&& length_range.start() == length_range.end()
+				{
+					Ok(json!({ "array": [elements, length_range.start()] }))
+				} else {
+					Ok(json!({ "vec": elements }))
+				}
+			}
+			Definition::Enum { variants, .. } if declaration.starts_with("Option<") => {
+				let (_, _, some) = variants
+					.iter()
+					.find(|(_, name, _)| name == "Some")
+					.ok_or(anyhow!("Invalid option `{}`", declaration))?;
+				let Definition::Struct {
+					fields: Fields::UnnamedFields(fields),
+				} = self.definition(some)?.clone()
+				else {
+					return Err(anyhow!("Invalid option `{}`", declaration));
+				};
+
+				Ok(json!({ "option": self.idl_type(&fields[0])? }))
+			}
+			_ => {
+				if !self.types.contains_key(declaration) {
+					let type_definition = self.type_definition(declaration)?;
+					self.types.insert(declaration.clone(), type_definition);
+				}
+
+				Ok(json!({ "defined": declaration }))
+			}
+		}
+	}
+
+	fn fields(&mut self, fields: &Fields) -> Result<Vec<Value>> {
+		match fields {
+			Fields::NamedFields(fields) => fields
+				.iter()
+				.map(|(name, declaration)| {
+					Ok(json!({
+						"name": camel_case(name),
+						"type": self.idl_type(declaration)?,
+					}))
+				})
+				.collect(),
+			Fields::UnnamedFields(fields) => fields
+				.iter()
+				.map(|declaration| self.idl_type(declaration))
+				.collect(),
+			Fields::Empty => Ok(vec![]),
+		}
+	}
+
+	fn type_definition(&mut self, declaration: &Declaration) -> Result<Value> {
+		match self.definition(declaration)?.clone() {
+			Definition::Struct { fields } => Ok(json!({
+				"kind": "struct",
+				"fields": self.fields(&fields)?,
+			})),
+			Definition::Enum { variants, .. } => {
+				let variants = variants
+					.iter()
+					.map(|(_, name, variant)| {
+						let mut idl_variant = Map::new();
+						idl_variant.insert("name".to_string(), json!(name));
+
+						if let Definition::Struct { fields } = self.definition(variant)?.clone() {
+							if fields != Fields::Empty {
+								idl_variant
+									.insert("fields".to_string(), json!(self.fields(&fields)?));
+							}
+						}
+
+						Ok(Value::Object(idl_variant))
+					})
+					.collect::<Result<Vec<_>>>()?;
+
+				Ok(json!({ "kind": "enum", "variants": variants }))
+			}
+			_ => Err(anyhow!("Unsupported type `{}`", declaration)),
+		}
+	}
+
+	fn instructions(&mut self) -> Result<Vec<Value>> {
+		let docs = parse_instruction_docs(INSTRUCTION_SOURCE)?;
+
+		let Definition::Enum { variants, .. } =
+			self.definition(self.container.declaration())?.clone()
+		else {
+			return Err(anyhow!("`TokenizerInstruction` is not an enum"));
+		};
+
+		variants
+			.iter()
+			.map(|(discriminant, name, variant)| {
+				let instruction_docs = docs
+					.get(name)
+					.ok_or(anyhow!("Missing documentation of instruction `{}`", name))?;
+
+				if instruction_docs.accounts.is_empty() {
+					return Err(anyhow!("Missing accounts of instruction `{}`", name));
+				}
+
+				let Definition::Struct { fields } = self.definition(variant)?.clone() else {
+					return Err(anyhow!("Invalid instruction `{}`", name));
+				};
+
+				Ok(json!({
+					"name": camel_case(&split_words(name)),
+					"docs": instruction_docs.docs,
+					"accounts": instruction_docs.accounts,
+					"args": self.fields(&fields)?,
+					"discriminant": { "type": "u8", "value": discriminant },
+				}))
+			})
+			.collect()
+	}
+
+	fn account<T: BorshSchema>(&mut self) -> Result<Value> {
+		let container = BorshSchemaContainer::for_type::<T>();
+		let declaration = container.declaration().clone();
+
+		let mut builder = IdlBuilder {
+			container,
+			types: std::mem::take(&mut self.types),
+		};
+		let type_definition = builder.type_definition(&declaration);
+		self.types = builder.types;
+
+		Ok(json!({ "name": declaration, "type": type_definition? }))
+	}
+}
+
+// Splits a Pascal case identifier into words, e.g. `ClaimYield` into `Claim Yield`
+fn split_words(name: &str) -> String {
+	let mut words = String::new();
+	for c in name.chars() {
+		if c.is_ascii_uppercase() && !words.is_empty() {
+			words.push(' ');
+		}
+		words.push(c);
+	}
+
+	words
+}
+
+fn errors() -> Vec<Value> {
+	(0..)
+		.map_while(|code| TokenizerError::from_u32(code).map(|error| (code, error)))
+		.map(|(code, error)| {
+			json!({
+				"code": code,
+				"name": format!("{:?}", error),
+				"msg": error.to_string(),
+			})
+		})
+		.collect()
+}
+
+// Generates the IDL of the tokenizer program
+fn idl() -> Result<Value> {
+	let mut builder = IdlBuilder::new::<TokenizerInstruction>();
+	let instructions = builder.instructions()?;
+	let accounts = vec![
+		builder.account::<TokenizerState>()?,
+		builder.account::<ClaimsEscrowState>()?,
+	];
+	let types = builder
+		.types
+		.into_iter()
+		.map(|(name, type_definition)| json!({ "name": name, "type": type_definition }))
+		.collect::<Vec<_>>();
+
+	Ok(json!({
+		"version": env!("CARGO_PKG_VERSION"),
+		"name": "scalar_tokenizer",
+		"docs": parse_program_docs(INSTRUCTION_SOURCE)?,
+		"instructions": instructions,
+		"accounts": accounts,
+		"types": types,
+		"errors": errors(),
+		"metadata": {
+			"origin": "shank",
+			"address": sclr_token::id().to_string(),
+		},
+	}))
+}
+
+fn main() -> Result<()> {
+	let args = Cli::parse();
+	let idl = serde_json::to_string_pretty(&idl()?)?;
+
+	match args.out {
+		Some(path) => fs::write(&path, idl + "\n")
+			.map_err(|err| anyhow!("Unable to write IDL to {}: {}", path.display(), err))?,
+		None => println!("{}", idl),
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, std::path::Path};
+
+	const IDL_PATH: &str = "../client/idl/scalar_tokenizer.json";
+
+	#[test]
+	fn names_are_camel_cased() {
+		assert_eq!(camel_case("principal_token_mint"), "principalTokenMint");
+		assert_eq!(camel_case("Claim Yield"), "claimYield");
+		assert_eq!(split_words("RedeemMaturePrincipal"), "Redeem Mature Principal");
+
+		assert_eq!(account_name("Underlying vault account"), "underlyingVault");
+		assert_eq!(account_name("User principal token account"), "userPrincipalTokenAccount");
+	}
+
+	#[test]
+	fn accounts_are_parsed_from_docs() {
+		assert_eq!(
+			parse_account("3. `[writable, signer]` User account"),
+			Some(json!({
+				"name": "user",
+				"isMut": true,
+				"isSigner": true,
+				"docs": ["User account"],
+			}))
+		);
+		assert_eq!(
+			parse_account("10. `[]` Stake pool account"),
+			Some(json!({
+				"name": "stakePool",
+				"isMut": false,
+				"isSigner": false,
+				"docs": ["Stake pool account"],
+			}))
+		);
+		assert_eq!(parse_account("Accounts expected:"), None);
+		assert_eq!(parse_account("1. Tokenizer account"), None);
+	}
+
+	#[test]
+	fn every_instruction_is_documented() {
+		let idl = idl().unwrap();
+		let instructions = idl["instructions"].as_array().unwrap();
+		let docs = parse_instruction_docs(INSTRUCTION_SOURCE).unwrap();
+
+		assert_eq!(instructions.len(), docs.len());
+		for (discriminant, instruction) in instructions.iter().enumerate() {
+			assert_eq!(instruction["discriminant"]["value"], json!(discriminant));
+			assert!(!instruction["accounts"].as_array().unwrap().is_empty());
+		}
+	}
+
+	#[test]
+	fn errors_match_error_codes() {
+		let errors = errors();
+
+		assert!(!errors.is_empty());
+		for (code, error) in errors.iter().enumerate() {
+			let tokenizer_error = TokenizerError::from_usize(code).unwrap();
+			assert_eq!(error["code"], json!(code));
+			assert_eq!(error["msg"], json!(tokenizer_error.to_string()));
+		}
+	}
+
+	#[test]
+	fn committed_idl_is_up_to_date() {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(IDL_PATH);
+		let committed: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+		assert_eq!(
+			committed,
+			idl().unwrap(),
+			"{} is out of date, regenerate it with `cargo run -- --out {}`",
+			path.display(),
+			IDL_PATH
+		);
+	}
+}
@@ -0,0 +1,566 @@
+use {
+	crate::source::IndexedTransaction,
+	anyhow::{anyhow, Result},
+	rusqlite::{params, Connection, OptionalExtension, Transaction},
+	sclr_token::event::TokenizerEvent,
+	serde_json::{Map, Value},
+	solana_program::pubkey::Pubkey,
+	solana_sdk::signature::Signature,
+	std::{path::Path, str::FromStr},
+};
+
+const SCHEMA: &str = include_str!("schema.sql");
+
+/// Supplies and vault balance of a tokenizer at a slot
+#[derive(Debug, PartialEq)]
+pub struct Snapshot {
+	pub tokenizer: Pubkey,
+	pub slot: u64,
+	pub timestamp: i64,
+	pub principal_supply: Option<u64>,
+	pub yield_supply: Option<u64>,
+	pub vault_balance: Option<u64>,
+	pub yield_index: u128,
+	pub expiry_exchange_rate: u128,
+}
+
+pub struct Database {
+	connection: Connection,
+}
+
+impl Database {
+	/// Opens the database, creating the tables of the schema if they do not exist
+	pub fn open(path: &Path) -> Result<Self> {
+		let connection = Connection::open(path)
+			.map_err(|err| anyhow!("Unable to open database {}: {}", path.display(), err))?;
+		connection
+			.execute_batch(SCHEMA)
+			.map_err(|err| anyhow!("Unable to create database schema: {}", err))?;
+
+		Ok(Database { connection })
+	}
+
+	/// Returns the newest signature indexed
+	pub fn cursor(&self) -> Result<Option<Signature>> {
+		let signature: Option<String> = self
+			.connection
+			.query_row("SELECT signature FROM cursor WHERE id = 0", [], |row| {
+				row.get(0)
+			})
+			.optional()?;
+
+		signature
+			.map(|signature| {
+				Signature::from_str(&signature)
+					.map_err(|err| anyhow!("Invalid cursor signature {}: {}", signature, err))
+			})
+			.transpose()
+	}
+
+	pub fn contains(&self, signature: &Signature) -> Result<bool> {
+		Ok(self
+			.connection
+			.query_row(
+				"SELECT 1 FROM transactions WHERE signature = ?1",
+				[signature.to_string()],
+				|_| Ok(()),
+			)
+			.optional()?
+			.is_some())
+	}
+
+	/// Writes a transaction along with its instructions and events and moves the cursor to
+	/// it, all or nothing is written
+	pub fn index_transaction(&mut self, transaction: &IndexedTransaction) -> Result<()> {
+		let db_transaction = self.connection.transaction()?;
+		let signature = transaction.signature.to_string();
+
+		db_transaction.execute(
+			"INSERT INTO transactions (signature, slot, block_time, error) VALUES (?1, ?2, ?3, ?4)",
+			params![
+				signature,
+				transaction.slot,
+				transaction.block_time,
+				transaction.error
+			],
+		)?;
+
+		for instruction in &transaction.instructions {
+			let accounts = instruction
+				.accounts
+				.iter()
+				.map(|account| {
+					(
+						account.name.clone(),
+						Value::from(account.address.to_string()),
+					)
+				})
+				.collect::<Map<_, _>>();
+
+			db_transaction.execute(
+				"INSERT INTO instructions (signature, instruction_index, name, args, accounts)
+				VALUES (?1, ?2, ?3, ?4, ?5)",
+				params![
+					signature,
+					instruction.index,
+					instruction.name,
+					format!("{:?}", instruction.instruction),
+					Value::Object(accounts).to_string()
+				],
+			)?;
+		}
+
+		for (event_index, event) in transaction.events.iter().enumerate() {
+			insert_event(&db_transaction, &signature, event_index, event)?;
+		}
+
+		db_transaction.execute(
+			"INSERT INTO cursor (id, signature) VALUES (0, ?1)
+			ON CONFLICT (id) DO UPDATE SET signature = excluded.signature",
+			[&signature],
+		)?;
+
+		db_transaction.commit()?;
+
+		Ok(())
+	}
+
+	/// Returns every tokenizer that has emitted an event
+	pub fn tokenizers(&self) -> Result<Vec<Pubkey>> {
+		let mut statement = self
+			.connection
+			.prepare("SELECT DISTINCT tokenizer FROM events ORDER BY tokenizer")?;
+		let tokenizers = statement
+			.query_map([], |row| row.get::<_, String>(0))?
+			.collect::<rusqlite::Result<Vec<_>>>()?;
+
+		tokenizers
+			.iter()
+			.map(|tokenizer| {
+				Pubkey::from_str(tokenizer)
+					.map_err(|err| anyhow!("Invalid tokenizer address {}: {}", tokenizer, err))
+			})
+			.collect()
+	}
+
+	/// Writes a snapshot unless it is identical to the latest snapshot of the tokenizer,
+	/// returns whether the snapshot was written
+	pub fn insert_snapshot(&self, snapshot: &Snapshot) -> Result<bool> {
+		let tokenizer = snapshot.tokenizer.to_string();
+		let yield_index = snapshot.yield_index.to_string();
+		let expiry_exchange_rate = snapshot.expiry_exchange_rate.to_string();
+
+		let unchanged = self
+			.connection
+			.query_row(
+				"SELECT 1 FROM snapshots WHERE tokenizer = ?1
+				AND slot = (SELECT MAX(slot) FROM snapshots WHERE tokenizer = ?1)
+				AND principal_supply IS ?2 AND yield_supply IS ?3 AND vault_balance IS ?4
+				AND yield_index = ?5 AND expiry_exchange_rate = ?6",
+				params![
+					tokenizer,
+					snapshot.principal_supply,
+					snapshot.yield_supply,
+					snapshot.vault_balance,
+					yield_index,
+					expiry_exchange_rate
+				],
+				|_| Ok(()),
+			)
+			.optional()?
+			.is_some();
+
+		if unchanged {
+			return Ok(false);
+		}
+
+		self.connection.execute(
+			"INSERT OR REPLACE INTO snapshots (tokenizer, slot, timestamp, principal_supply,
+			yield_supply, vault_balance, yield_index, expiry_exchange_rate)
+			VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+			params![
+				tokenizer,
+				snapshot.slot,
+				snapshot.timestamp,
+				snapshot.principal_supply,
+				snapshot.yield_supply,
+				snapshot.vault_balance,
+				yield_index,
+				expiry_exchange_rate
+			],
+		)?;
+
+		Ok(true)
+	}
+}
+
+// Writes an event to the events table and to the table of its kind
+fn insert_event(
+	db_transaction: &Transaction,
+	signature: &str,
+	event_index: usize,
+	event: &TokenizerEvent,
+) -> Result<()> {
+	let kind = match event {
+		TokenizerEvent::TokenizerInitialized {
+			tokenizer,
+			authority,
+			underlying_mint,
+			stake_pool,
+			expiry_date,
+			fixed_apy,
+			yield_index,
+			timestamp,
+		} => {
+			db_transaction.execute(
+				"INSERT OR REPLACE INTO tokenizers (tokenizer, authority, underlying_mint,
+				stake_pool, expiry_date, fixed_apy, initial_yield_index, created_at)
+				VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+				params![
+					tokenizer.to_string(),
+					authority.to_string(),
+					underlying_mint.to_string(),
+					stake_pool.to_string(),
+					expiry_date,
+					fixed_apy,
+					yield_index.to_string(),
+					timestamp
+				],
+			)?;
+
+			"TokenizerInitialized"
+		}
+		TokenizerEvent::MintsInitialized { .. } => "MintsInitialized",
+		TokenizerEvent::UnderlyingDeposited {
+			tokenizer,
+			user,
+			underlying_amount,
+			timestamp,
+		} => {
+			db_transaction.execute(
+				"INSERT INTO deposits (signature, event_index, tokenizer, user,
+				underlying_amount, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+				params![
+					signature,
+					event_index,
+					tokenizer.to_string(),
+					user.to_string(),
+					underlying_amount,
+					timestamp
+				],
+			)?;
+
+			"UnderlyingDeposited"
+		}
+		TokenizerEvent::PrincipalTokenized {
+			tokenizer,
+			user,
+			underlying_amount,
+			principal_amount: token_amount,
+			exchange_rate,
+			timestamp,
+		}
+		| TokenizerEvent::YieldTokenized {
+			tokenizer,
+			user,
+			underlying_amount,
+			yield_amount: token_amount,
+			exchange_rate,
+			timestamp,
+		} => {
+			let (kind, token) = match event {
+				TokenizerEvent::PrincipalTokenized { .. } => ("PrincipalTokenized", "principal"),
+				_ => ("YieldTokenized", "yield"),
+			};
+
+			db_transaction.execute(
+				"INSERT INTO mints (signature, event_index, tokenizer, user, token,
+				underlying_amount, token_amount, exchange_rate, timestamp)
+				VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+				params![
+					signature,
+					event_index,
+					tokenizer.to_string(),
+					user.to_string(),
+					token,
+					underlying_amount,
+					token_amount,
+					exchange_rate.to_string(),
+					timestamp
+				],
+			)?;
+
+			kind
+		}
+		TokenizerEvent::PrincipalRedeemed {
+			tokenizer,
+			user,
+			principal_amount,
+			underlying_amount,
+			timestamp,
+			..
+		}
+		| TokenizerEvent::EscrowPrincipalRedeemed {
+			tokenizer,
+			user,
+			principal_amount,
+			underlying_amount,
+			timestamp,
+		} => {
+			// Principal redeemed from the escrow settles at the rate recorded in the escrow
+			let (kind, source, exchange_rate) = match event {
+				TokenizerEvent::PrincipalRedeemed { exchange_rate, .. } => (
+					"PrincipalRedeemed",
+					"tokenizer",
+					Some(exchange_rate.to_string()),
+				),
+				_ => ("EscrowPrincipalRedeemed", "escrow", None),
+			};
+
+			db_transaction.execute(
+				"INSERT INTO redemptions (signature, event_index, tokenizer, user, source,
+				principal_amount, underlying_amount, exchange_rate, timestamp)
+				VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+				params![
+					signature,
+					event_index,
+					tokenizer.to_string(),
+					user.to_string(),
+					source,
+					principal_amount,
+					underlying_amount,
+					exchange_rate,
+					timestamp
+				],
+			)?;
+
+			kind
+		}
+		TokenizerEvent::YieldClaimed {
+			tokenizer,
+			user,
+			yield_amount,
+			underlying_amount,
+			timestamp,
+		}
+		| TokenizerEvent::EscrowYieldClaimed {
+			tokenizer,
+			user,
+			yield_amount,
+			underlying_amount,
+			timestamp,
+		} => {
+			let (kind, source) = match event {
+				TokenizerEvent::YieldClaimed { .. } => ("YieldClaimed", "tokenizer"),
+				_ => ("EscrowYieldClaimed", "escrow"),
+			};
+
+			db_transaction.execute(
+				"INSERT INTO claims (signature, event_index, tokenizer, user, source,
+				yield_amount, underlying_amount, timestamp)
+				VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+				params![
+					signature,
+					event_index,
+					tokenizer.to_string(),
+					user.to_string(),
+					source,
+					yield_amount,
+					underlying_amount,
+					timestamp
+				],
+			)?;
+
+			kind
+		}
+		TokenizerEvent::ExpirySettled { .. } => "ExpirySettled",
+		TokenizerEvent::PostExpiryYieldSwept { .. } => "PostExpiryYieldSwept",
+		TokenizerEvent::SweptToEscrow { .. } => "SweptToEscrow",
+		TokenizerEvent::TokenizerTerminated { .. } => "TokenizerTerminated",
+		TokenizerEvent::MintsTerminated { .. } => "MintsTerminated",
+		TokenizerEvent::Checkpoint { .. } => "Checkpoint",
+		TokenizerEvent::YieldIndexReset { .. } => "YieldIndexReset",
+	};
+
+	db_transaction.execute(
+		"INSERT INTO events (signature, event_index, kind, tokenizer, data, timestamp)
+		VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+		params![
+			signature,
+			event_index,
+			kind,
+			event.tokenizer().to_string(),
+			format!("{:?}", event),
+			event.timestamp()
+		],
+	)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		sclr_client::decode::{DecodedAccount, DecodedInstruction},
+		sclr_token::instruction::TokenizerInstruction,
+	};
+
+	fn database() -> Database {
+		Database::open(Path::new(":memory:")).unwrap()
+	}
+
+	fn count(database: &Database, table: &str) -> u64 {
+		database
+			.connection
+			.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+			.unwrap()
+	}
+
+	fn checkpoint_instruction(index: usize, tokenizer: &Pubkey) -> DecodedInstruction {
+		DecodedInstruction {
+			index,
+			name: "checkpoint".to_string(),
+			instruction: TokenizerInstruction::Checkpoint,
+			accounts: vec![DecodedAccount {
+				name: "tokenizer".to_string(),
+				label: "Tokenizer account".to_string(),
+				address: *tokenizer,
+			}],
+		}
+	}
+
+	fn transaction(
+		instructions: Vec<DecodedInstruction>,
+		events: Vec<TokenizerEvent>,
+	) -> IndexedTransaction {
+		IndexedTransaction {
+			signature: Signature::new_unique(),
+			slot: 10,
+			block_time: Some(1_700_000_000),
+			error: None,
+			instructions,
+			events,
+		}
+	}
+
+	fn snapshot(tokenizer: &Pubkey, slot: u64, vault_balance: Option<u64>) -> Snapshot {
+		Snapshot {
+			tokenizer: *tokenizer,
+			slot,
+			timestamp: 1_700_000_000,
+			principal_supply: Some(100),
+			yield_supply: Some(100),
+			vault_balance,
+			yield_index: u128::MAX,
+			expiry_exchange_rate: 0,
+		}
+	}
+
+	#[test]
+	fn transactions_are_indexed_with_their_events() {
+		let mut database = database();
+		let tokenizer = Pubkey::new_unique();
+		let user = Pubkey::new_unique();
+		let transaction = transaction(
+			vec![checkpoint_instruction(0, &tokenizer)],
+			vec![
+				TokenizerEvent::UnderlyingDeposited {
+					tokenizer,
+					user,
+					underlying_amount: 100,
+					timestamp: 1,
+				},
+				TokenizerEvent::PrincipalTokenized {
+					tokenizer,
+					user,
+					underlying_amount: 100,
+					principal_amount: 95,
+					exchange_rate: u128::MAX,
+					timestamp: 1,
+				},
+				TokenizerEvent::EscrowYieldClaimed {
+					tokenizer,
+					user,
+					yield_amount: 5,
+					underlying_amount: 4,
+					timestamp: 2,
+				},
+			],
+		);
+
+		assert_eq!(database.cursor().unwrap(), None);
+		database.index_transaction(&transaction).unwrap();
+
+		assert!(database.contains(&transaction.signature).unwrap());
+		assert_eq!(database.cursor().unwrap(), Some(transaction.signature));
+		assert_eq!(database.tokenizers().unwrap(), vec![tokenizer]);
+		assert_eq!(count(&database, "instructions"), 1);
+		assert_eq!(count(&database, "events"), 3);
+		assert_eq!(count(&database, "deposits"), 1);
+		assert_eq!(count(&database, "mints"), 1);
+		assert_eq!(count(&database, "claims"), 1);
+
+		// Exchange rates beyond an SQLite integer are kept as text
+		let (token, exchange_rate): (String, String) = database
+			.connection
+			.query_row("SELECT token, exchange_rate FROM mints", [], |row| {
+				Ok((row.get(0)?, row.get(1)?))
+			})
+			.unwrap();
+		assert_eq!(token, "principal");
+		assert_eq!(exchange_rate, u128::MAX.to_string());
+
+		let source: String = database
+			.connection
+			.query_row("SELECT source FROM claims", [], |row| row.get(0))
+			.unwrap();
+		assert_eq!(source, "escrow");
+	}
+
+	#[test]
+	fn failed_writes_are_rolled_back() {
+		let mut database = database();
+		let tokenizer = Pubkey::new_unique();
+
+		let indexed = transaction(vec![checkpoint_instruction(0, &tokenizer)], vec![]);
+		database.index_transaction(&indexed).unwrap();
+
+		// Two instructions at the same index violate the primary key of the instructions
+		let invalid = transaction(
+			vec![
+				checkpoint_instruction(0, &tokenizer),
+				checkpoint_instruction(0, &tokenizer),
+			],
+			vec![],
+		);
+		assert!(database.index_transaction(&invalid).is_err());
+
+		assert!(!database.contains(&invalid.signature).unwrap());
+		assert_eq!(database.cursor().unwrap(), Some(indexed.signature));
+		assert_eq!(count(&database, "transactions"), 1);
+		assert_eq!(count(&database, "instructions"), 1);
+
+		// A transaction is only indexed once
+		assert!(database.index_transaction(&indexed).is_err());
+	}
+
+	#[test]
+	fn unchanged_snapshots_are_skipped() {
+		let database = database();
+		let tokenizer = Pubkey::new_unique();
+
+		assert!(database.insert_snapshot(&snapshot(&tokenizer, 1, Some(100))).unwrap());
+		assert!(!database.insert_snapshot(&snapshot(&tokenizer, 2, Some(100))).unwrap());
+
+		// A closed vault is a change
+		assert!(database.insert_snapshot(&snapshot(&tokenizer, 3, None)).unwrap());
+		assert!(!database.insert_snapshot(&snapshot(&tokenizer, 4, None)).unwrap());
+
+		// Snapshots of other tokenizers are independent
+		let other = Pubkey::new_unique();
+		assert!(database.insert_snapshot(&snapshot(&other, 4, None)).unwrap());
+
+		assert_eq!(count(&database, "snapshots"), 3);
+	}
+}
@@ -0,0 +1,225 @@
+mod db;
+mod source;
+
+use {
+	anyhow::{anyhow, Result},
+	clap::Parser,
+	db::{Database, Snapshot},
+	sclr_client::deserialize_account,
+	sclr_token::state::TokenizerState,
+	solana_cli_config::ConfigInput,
+	solana_client::{rpc_client::RpcClient, rpc_request::MAX_MULTIPLE_ACCOUNTS},
+	solana_program::{program_pack::Pack, pubkey::Pubkey},
+	solana_sdk::{account::Account, commitment_config::CommitmentConfig},
+	source::{
+		fetch_signatures, fetch_transaction, read_fixtures, record_fixture, IndexedTransaction,
+	},
+	std::{
+		fs,
+		path::{Path, PathBuf},
+		thread,
+		time::Duration,
+	},
+};
+
+/// Indexes the transactions and events of the tokenizer program into a SQLite database, see
+/// `schema.sql` for the tables written
+#[derive(Parser, Debug)]
+struct Cli {
+	/// Path to the Solana CLI configuration file
+	#[arg(short, long)]
+	config: Option<String>,
+	/// RPC endpoint URL or moniker: localhost, devnet, testnet or mainnet-beta
+	#[arg(short, long)]
+	rpc: Option<String>,
+	/// Path of the SQLite database, created if it does not exist
+	#[arg(short, long, default_value = "sclr-index.db")]
+	database: PathBuf,
+	/// Number of seconds between polls of the cluster
+	#[arg(long, default_value_t = 10)]
+	poll_interval: u64,
+	/// Index the transactions not yet indexed and exit instead of polling
+	#[arg(long)]
+	once: bool,
+	/// Index the transactions recorded in a directory instead of fetching them from the cluster
+	#[arg(long, conflicts_with_all = ["rpc", "record"])]
+	fixtures: Option<PathBuf>,
+	/// Record the fetched transactions to a directory to be replayed with `--fixtures`
+	#[arg(long)]
+	record: Option<PathBuf>,
+}
+
+fn index_fixtures(database: &mut Database, directory: &Path) -> Result<()> {
+	let mut indexed = 0;
+	for confirmed in read_fixtures(directory)? {
+		let transaction = IndexedTransaction::decode(&confirmed)?;
+		if database.contains(&transaction.signature)? {
+			continue;
+		}
+
+		database.index_transaction(&transaction)?;
+		indexed += 1;
+	}
+
+	println!(
+		"Indexed {} transactions from {}",
+		indexed,
+		directory.display()
+	);
+
+	Ok(())
+}
+
+// Indexes the transactions after the cursor, returns the number of transactions indexed
+fn index_new_transactions(
+	client: &RpcClient,
+	database: &mut Database,
+	record: Option<&Path>,
+) -> Result<usize> {
+	let signatures = fetch_signatures(client, database.cursor()?)?;
+
+	for (sequence, signature) in signatures.iter().enumerate() {
+		let confirmed = fetch_transaction(client, signature)?;
+		if let Some(directory) = record {
+			record_fixture(directory, sequence, signature, &confirmed)?;
+		}
+
+		database.index_transaction(&IndexedTransaction::decode(&confirmed)?)?;
+	}
+
+	Ok(signatures.len())
+}
+
+fn get_multiple_accounts(
+	client: &RpcClient,
+	addresses: &[Pubkey],
+) -> Result<(u64, Vec<Option<Account>>)> {
+	let mut slot = 0;
+	let mut accounts = vec![];
+
+	for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+		let response = client
+			.get_multiple_accounts_with_commitment(chunk, client.commitment())
+			.map_err(|err| anyhow!("Unable to fetch accounts: {}", err))?;
+		slot = slot.max(response.context.slot);
+		accounts.extend(response.value);
+	}
+
+	Ok((slot, accounts))
+}
+
+// Records the supplies and vault balance of every indexed tokenizer that has not been
+// terminated, returns the number of snapshots that changed
+fn take_snapshots(client: &RpcClient, database: &Database) -> Result<usize> {
+	let tokenizers = database.tokenizers()?;
+	let (_, tokenizer_accounts) = get_multiple_accounts(client, &tokenizers)?;
+
+	let states = tokenizers
+		.iter()
+		.zip(&tokenizer_accounts)
+		.filter_map(|(tokenizer, account)| {
+			let account = account.as_ref()?;
+			let state = deserialize_account::<TokenizerState>(tokenizer, account).ok()?;
+
+			Some((*tokenizer, state))
+		})
+		.collect::<Vec<_>>();
+
+	if states.is_empty() {
+		return Ok(0);
+	}
+
+	let addresses = states
+		.iter()
+		.flat_map(|(_, state)| {
+			[
+				state.underlying_vault,
+				state.principal_token_mint,
+				state.yield_token_mint,
+			]
+		})
+		.collect::<Vec<_>>();
+	let (slot, accounts) = get_multiple_accounts(client, &addresses)?;
+	let timestamp = client.get_block_time(slot)?;
+
+	let get_supply = |account: &Option<Account>| {
+		account
+			.as_ref()
+			.and_then(|account| spl_token::state::Mint::unpack(&account.data).ok())
+			.map(|mint| mint.supply)
+	};
+
+	let mut changed = 0;
+	for ((tokenizer, state), accounts) in states.iter().zip(accounts.chunks(3)) {
+		let [vault, principal_mint, yield_mint] = accounts else {
+			return Err(anyhow!("Unexpected number of accounts returned"));
+		};
+
+		let snapshot = Snapshot {
+			tokenizer: *tokenizer,
+			slot,
+			timestamp,
+			principal_supply: get_supply(principal_mint),
+			yield_supply: get_supply(yield_mint),
+			vault_balance: vault
+				.as_ref()
+				.and_then(|account| spl_token::state::Account::unpack(&account.data).ok())
+				.map(|account| account.amount),
+			yield_index: state.yield_index,
+			expiry_exchange_rate: state.expiry_exchange_rate,
+		};
+
+		if database.insert_snapshot(&snapshot)? {
+			changed += 1;
+		}
+	}
+
+	Ok(changed)
+}
+
+fn main() -> Result<()> {
+	let args = Cli::parse();
+
+	let mut database = Database::open(&args.database)?;
+
+	if let Some(ref directory) = args.fixtures {
+		return index_fixtures(&mut database, directory);
+	}
+
+	if let Some(ref directory) = args.record {
+		fs::create_dir_all(directory)
+			.map_err(|err| anyhow!("Unable to create {}: {}", directory.display(), err))?;
+	}
+
+	let solana_config_file = if let Some(ref config) = args.config {
+		solana_cli_config::Config::load(config)
+			.map_err(|err| anyhow!("Unable to load config file {}: {}", config, err))?
+	} else if let Some(ref config) = *solana_cli_config::CONFIG_FILE {
+		solana_cli_config::Config::load(config).unwrap_or_default()
+	} else {
+		solana_cli_config::Config::default()
+	};
+
+	// Command line flags take precedence over the config file, monikers are resolved to URLs
+	let (_, json_rpc_url) = ConfigInput::compute_json_rpc_url_setting(
+		args.rpc.as_deref().unwrap_or_default(),
+		&solana_config_file.json_rpc_url,
+	);
+
+	let client = RpcClient::new_with_commitment(json_rpc_url, CommitmentConfig::confirmed());
+
+	loop {
+		let indexed = index_new_transactions(&client, &mut database, args.record.as_deref())?;
+		let snapshots = take_snapshots(&client, &database)?;
+		println!(
+			"Indexed {} transactions, {} tokenizer snapshots changed",
+			indexed, snapshots
+		);
+
+		if args.once {
+			return Ok(());
+		}
+
+		thread::sleep(Duration::from_secs(args.poll_interval));
+	}
+}
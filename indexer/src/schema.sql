@@ -0,0 +1,133 @@
+-- Schema of the database written by `sclr-indexer`
+--
+-- Amounts are in base units of their token. Exchange rates and yield indexes are scaled by
+-- `WAD` (10^18) and stored as decimal text as they do not fit in an SQLite integer. Addresses
+-- and signatures are base58 text and timestamps are unix timestamps of the cluster.
+
+-- Every transaction of the tokenizer program that has been indexed, failed transactions are
+-- recorded along with their instructions but never with events
+CREATE TABLE IF NOT EXISTS transactions (
+	signature TEXT PRIMARY KEY,
+	slot INTEGER NOT NULL,
+	block_time INTEGER,
+	-- The error of a failed transaction, NULL if it succeeded
+	error TEXT
+);
+
+-- Tokenizer instructions invoked directly by a transaction
+CREATE TABLE IF NOT EXISTS instructions (
+	signature TEXT NOT NULL REFERENCES transactions (signature),
+	-- Index of the instruction within the transaction
+	instruction_index INTEGER NOT NULL,
+	-- Name of the instruction in the IDL, e.g. `depositAndTokenize`
+	name TEXT NOT NULL,
+	-- The decoded instruction data
+	args TEXT NOT NULL,
+	-- JSON object of the labelled accounts, keyed by their name in the IDL
+	accounts TEXT NOT NULL,
+	PRIMARY KEY (signature, instruction_index)
+);
+
+-- Every event emitted by the tokenizer program, events are also written to the tables below
+-- according to their kind
+CREATE TABLE IF NOT EXISTS events (
+	signature TEXT NOT NULL REFERENCES transactions (signature),
+	-- Index of the event within the transaction
+	event_index INTEGER NOT NULL,
+	-- Name of the `TokenizerEvent` variant, e.g. `PrincipalTokenized`
+	kind TEXT NOT NULL,
+	tokenizer TEXT NOT NULL,
+	-- The decoded event
+	data TEXT NOT NULL,
+	timestamp INTEGER NOT NULL,
+	PRIMARY KEY (signature, event_index)
+);
+
+-- Tokenizers, from their `TokenizerInitialized` event
+CREATE TABLE IF NOT EXISTS tokenizers (
+	tokenizer TEXT PRIMARY KEY,
+	authority TEXT NOT NULL,
+	underlying_mint TEXT NOT NULL,
+	stake_pool TEXT NOT NULL,
+	expiry_date INTEGER NOT NULL,
+	fixed_apy INTEGER NOT NULL,
+	initial_yield_index TEXT NOT NULL,
+	created_at INTEGER NOT NULL
+);
+
+-- Underlying deposited into a tokenizer vault
+CREATE TABLE IF NOT EXISTS deposits (
+	signature TEXT NOT NULL REFERENCES transactions (signature),
+	event_index INTEGER NOT NULL,
+	tokenizer TEXT NOT NULL,
+	user TEXT NOT NULL,
+	underlying_amount INTEGER NOT NULL,
+	timestamp INTEGER NOT NULL,
+	PRIMARY KEY (signature, event_index)
+);
+
+-- Principal and yield tokens minted against deposited underlying
+CREATE TABLE IF NOT EXISTS mints (
+	signature TEXT NOT NULL REFERENCES transactions (signature),
+	event_index INTEGER NOT NULL,
+	tokenizer TEXT NOT NULL,
+	user TEXT NOT NULL,
+	-- `principal` or `yield`
+	token TEXT NOT NULL,
+	underlying_amount INTEGER NOT NULL,
+	token_amount INTEGER NOT NULL,
+	exchange_rate TEXT NOT NULL,
+	timestamp INTEGER NOT NULL,
+	PRIMARY KEY (signature, event_index)
+);
+
+-- Principal tokens redeemed for the underlying
+CREATE TABLE IF NOT EXISTS redemptions (
+	signature TEXT NOT NULL REFERENCES transactions (signature),
+	event_index INTEGER NOT NULL,
+	tokenizer TEXT NOT NULL,
+	user TEXT NOT NULL,
+	-- `tokenizer`, or `escrow` once the underlying has been swept to the claims escrow
+	source TEXT NOT NULL,
+	principal_amount INTEGER NOT NULL,
+	underlying_amount INTEGER NOT NULL,
+	-- The exchange rate of the redemption, NULL for redemptions from the claims escrow
+	exchange_rate TEXT,
+	timestamp INTEGER NOT NULL,
+	PRIMARY KEY (signature, event_index)
+);
+
+-- Yield tokens claimed for the underlying
+CREATE TABLE IF NOT EXISTS claims (
+	signature TEXT NOT NULL REFERENCES transactions (signature),
+	event_index INTEGER NOT NULL,
+	tokenizer TEXT NOT NULL,
+	user TEXT NOT NULL,
+	-- `tokenizer`, or `escrow` once the underlying has been swept to the claims escrow
+	source TEXT NOT NULL,
+	yield_amount INTEGER NOT NULL,
+	underlying_amount INTEGER NOT NULL,
+	timestamp INTEGER NOT NULL,
+	PRIMARY KEY (signature, event_index)
+);
+
+-- Supplies and vault balance of each indexed tokenizer, taken from the cluster after every
+-- poll. Snapshots are not taken when indexing recorded fixtures
+CREATE TABLE IF NOT EXISTS snapshots (
+	tokenizer TEXT NOT NULL,
+	slot INTEGER NOT NULL,
+	timestamp INTEGER NOT NULL,
+	-- NULL once the mints or the vault have been closed
+	principal_supply INTEGER,
+	yield_supply INTEGER,
+	vault_balance INTEGER,
+	yield_index TEXT NOT NULL,
+	expiry_exchange_rate TEXT NOT NULL,
+	PRIMARY KEY (tokenizer, slot)
+);
+
+-- The newest signature indexed, the indexer resumes after it
+CREATE TABLE IF NOT EXISTS cursor (
+	id INTEGER PRIMARY KEY CHECK (id = 0),
+	signature TEXT NOT NULL
+);
@@ -0,0 +1,195 @@
+use {
+	anyhow::{anyhow, Result},
+	sclr_client::{
+		decode::{decode_message, DecodedInstruction},
+		event::parse_events,
+	},
+	sclr_token::event::TokenizerEvent,
+	solana_client::{
+		rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+		rpc_config::RpcTransactionConfig,
+	},
+	solana_program::pubkey::Pubkey,
+	solana_sdk::signature::Signature,
+	solana_transaction_status::{
+		EncodedConfirmedTransactionWithStatusMeta, UiLoadedAddresses, UiTransactionEncoding,
+	},
+	std::{fs, path::Path, str::FromStr},
+};
+
+/// A transaction of the tokenizer program decoded for indexing
+#[derive(Debug)]
+pub struct IndexedTransaction {
+	pub signature: Signature,
+	pub slot: u64,
+	pub block_time: Option<i64>,
+	pub error: Option<String>,
+	pub instructions: Vec<DecodedInstruction>,
+	/// Events of the transaction, empty if it failed as its state changes were rolled back
+	pub events: Vec<TokenizerEvent>,
+}
+
+fn parse_pubkeys(addresses: &[String]) -> Result<Vec<Pubkey>> {
+	addresses
+		.iter()
+		.map(|address| {
+			Pubkey::from_str(address).map_err(|err| anyhow!("Invalid address {}: {}", address, err))
+		})
+		.collect()
+}
+
+impl IndexedTransaction {
+	/// Decodes the tokenizer instructions and events of a transaction fetched with the base64
+	/// encoding
+	pub fn decode(confirmed: &EncodedConfirmedTransactionWithStatusMeta) -> Result<Self> {
+		let transaction = confirmed.transaction.transaction.decode().ok_or(anyhow!(
+			"Unable to decode transaction at slot {}",
+			confirmed.slot
+		))?;
+		let signature = *transaction.signatures.first().ok_or(anyhow!(
+			"Transaction at slot {} is unsigned",
+			confirmed.slot
+		))?;
+		let meta = confirmed
+			.transaction
+			.meta
+			.as_ref()
+			.ok_or(anyhow!("Transaction {} has no status metadata", signature))?;
+
+		// Addresses loaded from lookup tables follow the static keys, writable addresses first
+		let loaded_addresses =
+			match Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()) {
+				Some(loaded) => {
+					let mut addresses = parse_pubkeys(&loaded.writable)?;
+					addresses.extend(parse_pubkeys(&loaded.readonly)?);
+					addresses
+				}
+				None => vec![],
+			};
+
+		let instructions = decode_message(&transaction.message, &loaded_addresses)
+			.map_err(|err| anyhow!("Unable to decode transaction {}: {}", signature, err))?;
+
+		let events = match (
+			&meta.err,
+			Option::<&Vec<String>>::from(meta.log_messages.as_ref()),
+		) {
+			(None, Some(log_messages)) => parse_events(log_messages),
+			_ => vec![],
+		};
+
+		Ok(IndexedTransaction {
+			signature,
+			slot: confirmed.slot,
+			block_time: confirmed.block_time,
+			error: meta.err.as_ref().map(|err| err.to_string()),
+			instructions,
+			events,
+		})
+	}
+}
+
+/// Returns the signatures of the transactions of the tokenizer program after the cursor,
+/// oldest first
+pub fn fetch_signatures(client: &RpcClient, cursor: Option<Signature>) -> Result<Vec<Signature>> {
+	let mut signatures = vec![];
+	let mut before = None;
+
+	// Signatures are returned newest first in pages
+	loop {
+		let page = client
+			.get_signatures_for_address_with_config(
+				&sclr_token::id(),
+				GetConfirmedSignaturesForAddress2Config {
+					before,
+					until: cursor,
+					limit: None,
+					commitment: Some(client.commitment()),
+				},
+			)
+			.map_err(|err| anyhow!("Unable to fetch signatures: {}", err))?;
+
+		let Some(last) = page.last() else {
+			break;
+		};
+		before = Some(
+			Signature::from_str(&last.signature)
+				.map_err(|err| anyhow!("Invalid signature {}: {}", last.signature, err))?,
+		);
+
+		for status in &page {
+			signatures.push(
+				Signature::from_str(&status.signature)
+					.map_err(|err| anyhow!("Invalid signature {}: {}", status.signature, err))?,
+			);
+		}
+	}
+
+	signatures.reverse();
+
+	Ok(signatures)
+}
+
+pub fn fetch_transaction(
+	client: &RpcClient,
+	signature: &Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+	client
+		.get_transaction_with_config(
+			signature,
+			RpcTransactionConfig {
+				encoding: Some(UiTransactionEncoding::Base64),
+				commitment: Some(client.commitment()),
+				max_supported_transaction_version: Some(0),
+			},
+		)
+		.map_err(|err| anyhow!("Unable to fetch transaction {}: {}", signature, err))
+}
+
+/// Writes a transaction to the fixtures directory, named by its slot and its position in the
+/// sequence of indexed transactions so that the file names sort in the order of execution
+pub fn record_fixture(
+	directory: &Path,
+	sequence: usize,
+	signature: &Signature,
+	confirmed: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<()> {
+	let path = directory.join(format!(
+		"{:012}-{:06}-{}.json",
+		confirmed.slot, sequence, signature
+	));
+	let json = serde_json::to_string_pretty(confirmed)?;
+
+	fs::write(&path, json + "\n")
+		.map_err(|err| anyhow!("Unable to write fixture {}: {}", path.display(), err))
+}
+
+/// Reads the transactions recorded in a fixtures directory, oldest first. Transactions of
+/// the same slot are ordered by file name
+pub fn read_fixtures(directory: &Path) -> Result<Vec<EncodedConfirmedTransactionWithStatusMeta>> {
+	let mut paths = fs::read_dir(directory)
+		.map_err(|err| anyhow!("Unable to read fixtures {}: {}", directory.display(), err))?
+		.map(|entry| entry.map(|entry| entry.path()))
+		.collect::<Result<Vec<_>, _>>()?;
+	paths.retain(|path| {
+		path.extension()
+			.is_some_and(|extension| extension == "json")
+	});
+	paths.sort();
+
+	let mut transactions = paths
+		.iter()
+		.map(|path| {
+			let json = fs::read_to_string(path)
+				.map_err(|err| anyhow!("Unable to read fixture {}: {}", path.display(), err))?;
+
+			serde_json::from_str::<EncodedConfirmedTransactionWithStatusMeta>(&json)
+				.map_err(|err| anyhow!("Invalid fixture {}: {}", path.display(), err))
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	// The sort is stable so the file name order is kept within a slot
+	transactions.sort_by_key(|transaction| transaction.slot);
+
+	Ok(transactions)
+}
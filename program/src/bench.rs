@@ -0,0 +1,52 @@
+use {
+	crate::math::{exp_wad, ln_wad, mul_div, pow_wad, powi_wad},
+	borsh::{BorshDeserialize, BorshSerialize},
+	solana_program::{
+		entrypoint::ProgramResult, program::set_return_data, program_error::ProgramError,
+	},
+};
+
+/// The first byte of a benchmark instruction, outside the range of `TokenizerInstruction`
+pub const BENCH_INSTRUCTION_TAG: u8 = u8::MAX;
+
+/// Runs a function of the math module on-chain so that its compute units can be measured,
+/// only built with the `bench` feature
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MathBenchInstruction {
+	MulDiv { a: u128, b: u128, denominator: u128 },
+	ExpWad { x: i128 },
+	LnWad { x: u128 },
+	PowWad { base: u128, exponent: i128 },
+	PowiWad { base: u128, exponent: u64 },
+}
+
+impl MathBenchInstruction {
+	/// Returns the instruction data, prefixed with `BENCH_INSTRUCTION_TAG`
+	pub fn data(&self) -> Vec<u8> {
+		let mut data = vec![BENCH_INSTRUCTION_TAG];
+		data.extend_from_slice(&borsh::to_vec(self).unwrap());
+		data
+	}
+}
+
+/// Processes a benchmark instruction without its tag, the result is set as the return data
+/// so that the calculation cannot be optimised away
+pub fn process_math_bench(data: &[u8]) -> ProgramResult {
+	let instruction = MathBenchInstruction::try_from_slice(data)
+		.map_err(|_| ProgramError::InvalidInstructionData)?;
+
+	let result = match instruction {
+		MathBenchInstruction::MulDiv { a, b, denominator } => {
+			mul_div(a, b, denominator)?.to_le_bytes()
+		}
+		MathBenchInstruction::ExpWad { x } => exp_wad(x)?.to_le_bytes(),
+		MathBenchInstruction::LnWad { x } => ln_wad(x)?.to_le_bytes(),
+		MathBenchInstruction::PowWad { base, exponent } => pow_wad(base, exponent)?.to_le_bytes(),
+		MathBenchInstruction::PowiWad { base, exponent } => {
+			powi_wad(base, exponent)?.to_le_bytes()
+		}
+	};
+	set_return_data(&result);
+
+	Ok(())
+}
@@ -12,6 +12,11 @@ pub fn process_instruction(
 	accounts: &[AccountInfo],
 	instruction_data: &[u8],
 ) -> ProgramResult {
+	#[cfg(feature = "bench")]
+	if let Some((&crate::bench::BENCH_INSTRUCTION_TAG, data)) = instruction_data.split_first() {
+		return crate::bench::process_math_bench(data);
+	}
+
 	if let Err(e) = TokenizerProcessor::process(program_id, accounts, instruction_data) {
 		e.print::<TokenizerError>();
 		return Err(e);
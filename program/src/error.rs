@@ -41,6 +41,24 @@ pub enum TokenizerError {
 	InsufficientFunds,
 	#[error("Vault Is Not Empty")]
 	VaultNotEmpty,
+	#[error("Invalid Grace Period")]
+	InvalidGracePeriod,
+	#[error("Settlement Window Has Not Elapsed")]
+	SettlementWindowNotElapsed,
+	#[error("Incorrect Escrow Address")]
+	IncorrectEscrowAddress,
+	#[error("Escrow Not Initialized")]
+	EscrowNotInitialized,
+	#[error("Incorrect Stake Pool Address")]
+	IncorrectStakePoolAddress,
+	#[error("Invalid Exchange Rate")]
+	InvalidExchangeRate,
+	#[error("Calculation Failure")]
+	CalculationFailure,
+	#[error("Incorrect Treasury Address")]
+	IncorrectTreasuryAddress,
+	#[error("Exchange Rate Out Of Bounds")]
+	ExchangeRateOutOfBounds,
 }
 
 impl From<TokenizerError> for ProgramError {
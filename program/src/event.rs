@@ -0,0 +1,190 @@
+use {
+	borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+	solana_program::{entrypoint::ProgramResult, log::sol_log_data, pubkey::Pubkey},
+};
+
+/// Prefix of the log data of every event, distinguishes events from other data logged by
+/// the program
+pub const EVENT_TAG: [u8; 8] = *b"sclr_evt";
+
+/// Structured events logged by the processor with `sol_log_data`, each event is logged as
+/// the `EVENT_TAG` followed by the Borsh encoded event. Amounts are in base units of their
+/// token and exchange rates are scaled by `WAD`
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, BorshSchema, PartialEq)]
+pub enum TokenizerEvent {
+	TokenizerInitialized {
+		tokenizer: Pubkey,
+		authority: Pubkey,
+		underlying_mint: Pubkey,
+		stake_pool: Pubkey,
+		expiry_date: i64,
+		fixed_apy: u64,
+		yield_index: u128,
+		timestamp: i64,
+	},
+	MintsInitialized {
+		tokenizer: Pubkey,
+		principal_token_mint: Pubkey,
+		yield_token_mint: Pubkey,
+		timestamp: i64,
+	},
+	UnderlyingDeposited {
+		tokenizer: Pubkey,
+		user: Pubkey,
+		underlying_amount: u64,
+		timestamp: i64,
+	},
+	PrincipalTokenized {
+		tokenizer: Pubkey,
+		user: Pubkey,
+		underlying_amount: u64,
+		principal_amount: u64,
+		exchange_rate: u128,
+		timestamp: i64,
+	},
+	YieldTokenized {
+		tokenizer: Pubkey,
+		user: Pubkey,
+		underlying_amount: u64,
+		yield_amount: u64,
+		exchange_rate: u128,
+		timestamp: i64,
+	},
+	/// Principal redeemed at maturity or alongside yield tokens before expiry
+	PrincipalRedeemed {
+		tokenizer: Pubkey,
+		user: Pubkey,
+		principal_amount: u64,
+		underlying_amount: u64,
+		exchange_rate: u128,
+		timestamp: i64,
+	},
+	YieldClaimed {
+		tokenizer: Pubkey,
+		user: Pubkey,
+		yield_amount: u64,
+		underlying_amount: u64,
+		timestamp: i64,
+	},
+	/// The exchange rate and yield reserve were frozen on the first interaction after expiry
+	ExpirySettled {
+		tokenizer: Pubkey,
+		expiry_exchange_rate: u128,
+		expiry_yield_reserve: u64,
+		timestamp: i64,
+	},
+	PostExpiryYieldSwept {
+		tokenizer: Pubkey,
+		treasury_token_account: Pubkey,
+		underlying_amount: u64,
+		timestamp: i64,
+	},
+	SweptToEscrow {
+		tokenizer: Pubkey,
+		escrow: Pubkey,
+		underlying_amount: u64,
+		timestamp: i64,
+	},
+	EscrowPrincipalRedeemed {
+		tokenizer: Pubkey,
+		user: Pubkey,
+		principal_amount: u64,
+		underlying_amount: u64,
+		timestamp: i64,
+	},
+	EscrowYieldClaimed {
+		tokenizer: Pubkey,
+		user: Pubkey,
+		yield_amount: u64,
+		underlying_amount: u64,
+		timestamp: i64,
+	},
+	TokenizerTerminated {
+		tokenizer: Pubkey,
+		authority: Pubkey,
+		timestamp: i64,
+	},
+	MintsTerminated {
+		tokenizer: Pubkey,
+		authority: Pubkey,
+		timestamp: i64,
+	},
+	Checkpoint {
+		tokenizer: Pubkey,
+		yield_index: u128,
+		slot: u64,
+		timestamp: i64,
+	},
+	/// The authority set the yield index to the exchange rate of the stake pool, bypassing the
+	/// rate of change bounds
+	YieldIndexReset {
+		tokenizer: Pubkey,
+		authority: Pubkey,
+		previous_yield_index: u128,
+		yield_index: u128,
+		slot: u64,
+		timestamp: i64,
+	},
+}
+
+impl TokenizerEvent {
+	/// Logs the event as program data
+	pub fn emit(&self) -> ProgramResult {
+		let data = borsh::to_vec(self)?;
+		sol_log_data(&[&EVENT_TAG, &data]);
+
+		Ok(())
+	}
+
+	/// Decodes the fields of a `Program data:` log, returns `None` if they are not an event
+	pub fn from_log_data(fields: &[&[u8]]) -> Option<Self> {
+		match fields {
+			[tag, data] if *tag == EVENT_TAG => Self::try_from_slice(data).ok(),
+			_ => None,
+		}
+	}
+
+	/// Returns the tokenizer the event was emitted for
+	pub fn tokenizer(&self) -> &Pubkey {
+		match self {
+			TokenizerEvent::TokenizerInitialized { tokenizer, .. }
+			| TokenizerEvent::MintsInitialized { tokenizer, .. }
+			| TokenizerEvent::UnderlyingDeposited { tokenizer, .. }
+			| TokenizerEvent::PrincipalTokenized { tokenizer, .. }
+			| TokenizerEvent::YieldTokenized { tokenizer, .. }
+			| TokenizerEvent::PrincipalRedeemed { tokenizer, .. }
+			| TokenizerEvent::YieldClaimed { tokenizer, .. }
+			| TokenizerEvent::ExpirySettled { tokenizer, .. }
+			| TokenizerEvent::PostExpiryYieldSwept { tokenizer, .. }
+			| TokenizerEvent::SweptToEscrow { tokenizer, .. }
+			| TokenizerEvent::EscrowPrincipalRedeemed { tokenizer, .. }
+			| TokenizerEvent::EscrowYieldClaimed { tokenizer, .. }
+			| TokenizerEvent::TokenizerTerminated { tokenizer, .. }
+			| TokenizerEvent::MintsTerminated { tokenizer, .. }
+			| TokenizerEvent::Checkpoint { tokenizer, .. }
+			| TokenizerEvent::YieldIndexReset { tokenizer, .. } => tokenizer,
+		}
+	}
+
+	/// Returns the timestamp of the cluster when the event was emitted
+	pub fn timestamp(&self) -> i64 {
+		match self {
+			TokenizerEvent::TokenizerInitialized { timestamp, .. }
+			| TokenizerEvent::MintsInitialized { timestamp, .. }
+			| TokenizerEvent::UnderlyingDeposited { timestamp, .. }
+			| TokenizerEvent::PrincipalTokenized { timestamp, .. }
+			| TokenizerEvent::YieldTokenized { timestamp, .. }
+			| TokenizerEvent::PrincipalRedeemed { timestamp, .. }
+			| TokenizerEvent::YieldClaimed { timestamp, .. }
+			| TokenizerEvent::ExpirySettled { timestamp, .. }
+			| TokenizerEvent::PostExpiryYieldSwept { timestamp, .. }
+			| TokenizerEvent::SweptToEscrow { timestamp, .. }
+			| TokenizerEvent::EscrowPrincipalRedeemed { timestamp, .. }
+			| TokenizerEvent::EscrowYieldClaimed { timestamp, .. }
+			| TokenizerEvent::TokenizerTerminated { timestamp, .. }
+			| TokenizerEvent::MintsTerminated { timestamp, .. }
+			| TokenizerEvent::Checkpoint { timestamp, .. }
+			| TokenizerEvent::YieldIndexReset { timestamp, .. } => *timestamp,
+		}
+	}
+}
@@ -1,5 +1,10 @@
 use {
-	crate::Expiry,
+	crate::{
+		get_escrow_address, get_principal_mint_address, get_tokenizer_address,
+		get_yield_mint_address,
+		state::{PostExpiryYieldRecipient, TokenizerState},
+		Expiry,
+	},
 	borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
 	solana_program::{
 		instruction::{AccountMeta, Instruction},
@@ -7,9 +12,14 @@ use {
 		pubkey::Pubkey,
 		system_program,
 	},
+	spl_associated_token_account::get_associated_token_address,
 	spl_token,
 };
 
+/// Instructions of the tokenizer program
+///
+/// Every instruction that reads or settles the exchange rate takes the stake pool account
+/// recorded on the tokenizer, instructions built for layouts without it are rejected
 #[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
 pub enum TokenizerInstruction {
 	/// Initializes the Tokenizer
@@ -22,6 +32,8 @@ pub enum TokenizerInstruction {
 	/// 3. `[]` Underlying mint account
 	/// 4. `[]` Token program
 	/// 5. `[]` System program
+	/// 6. `[]` Associated Token program
+	/// 7. `[]` Stake pool account
 	InitializeTokenizer {
 		/// The public key of the underlying mint
 		underlying_mint: Pubkey,
@@ -33,6 +45,13 @@ pub enum TokenizerInstruction {
 		expiry: Expiry,
 		/// The fixed APY of the Principal token
 		fixed_apy: u64,
+		/// The number of seconds after the expiry date before the remaining underlying
+		/// can be swept into the claims escrow
+		grace_period: i64,
+		/// The recipient of the yield earned after the expiry date
+		post_expiry_yield: PostExpiryYieldRecipient,
+		/// The wallet that receives the post expiry yield when routed to the treasury
+		treasury: Pubkey,
 	},
 
 	/// Initializes the principal and yield token mints
@@ -41,9 +60,11 @@ pub enum TokenizerInstruction {
 	///
 	/// 0. `[writable]` Tokenizer account
 	/// 1. `[writable, signer]` Authority
-	/// 2. `[writable]` Principal token mint account
-	/// 3. `[writable]` Yield token mint account
-	/// 4. `[]` Token program
+	/// 2. `[writable]` Underlying mint account
+	/// 3. `[writable]` Principal token mint account
+	/// 4. `[writable]` Yield token mint account
+	/// 5. `[]` Token program
+	/// 6. `[]` System program
 	InitializeMints {
 		/// The public key of the underlying mint
 		underlying_mint: Pubkey,
@@ -58,11 +79,13 @@ pub enum TokenizerInstruction {
 	/// 0. `[writable]` Tokenizer account
 	/// 1. `[writable,signer]` Authority
 	/// 2. `[writable]` Underlying vault account
-	/// 3. `[writable]` Principal token mint account
-	/// 4. `[writable]` Yield token mint account
-	/// 5. `[]` Token program
-	/// 6. `[]` Associated Token Program
+	/// 3. `[]` Underlying mint account
+	/// 4. `[writable]` Principal token mint account
+	/// 5. `[writable]` Yield token mint account
+	/// 6. `[]` Token program
 	/// 7. `[]` System program
+	/// 8. `[]` Associated Token Program
+	/// 9. `[]` Stake pool account
 	InitializeTokenizerAndMints {
 		/// The public key of the underlying mint
 		underlying_mint: Pubkey,
@@ -74,6 +97,13 @@ pub enum TokenizerInstruction {
 		expiry: Expiry,
 		/// The fixed APY of the Principal token
 		fixed_apy: u64,
+		/// The number of seconds after the expiry date before the remaining underlying
+		/// can be swept into the claims escrow
+		grace_period: i64,
+		/// The recipient of the yield earned after the expiry date
+		post_expiry_yield: PostExpiryYieldRecipient,
+		/// The wallet that receives the post expiry yield when routed to the treasury
+		treasury: Pubkey,
 	},
 
 	/// Deposits the underlying token into the LysergicTokenizer
@@ -99,9 +129,12 @@ pub enum TokenizerInstruction {
 	/// 2. `[writable, signer]` User account
 	/// 3. `[writable]` User principal token account
 	/// 4. `[]` Token program
-	/// 5. `[]` System program
+	/// 5. `[]` Stake pool account
+	/// 6. `[]` System program
+	/// 7. `[]` Associated Token program
 	TokenizePrincipal {
-		/// The amount of the underlying token to tokenize
+		/// The amount of the underlying token to tokenize, principal tokens are minted
+		/// in units of the accounting asset at the current exchange rate
 		amount: u64,
 	},
 	/// Tokenizes the underlying token into yield tokens
@@ -113,9 +146,12 @@ pub enum TokenizerInstruction {
 	/// 2. `[writable, signer]` User account
 	/// 3. `[writable]` User yield token account
 	/// 4. `[]` Token program
-	/// 5. `[]` System program
+	/// 5. `[]` Stake pool account
+	/// 6. `[]` System program
+	/// 7. `[]` Associated Token program
 	TokenizeYield {
-		/// The amount of the underlying token to tokenize
+		/// The amount of the underlying token to tokenize, yield tokens are minted
+		/// in units of the accounting asset at the current exchange rate
 		amount: u64,
 	},
 
@@ -132,7 +168,9 @@ pub enum TokenizerInstruction {
 	/// 6. `[writable]` User principal token account
 	/// 7. `[writable]` User yield token account
 	/// 8. `[]` Token program
-	/// 9. `[]` System program
+	/// 9. `[]` Stake pool account
+	/// 10. `[]` System program
+	/// 11. `[]` Associated Token program
 	DepositAndTokenize {
 		/// The amount of the underlying token to deposit
 		amount: u64,
@@ -152,10 +190,14 @@ pub enum TokenizerInstruction {
 	/// 7. `[writable]` User principal token account
 	/// 8. `[writable]` User yield token account
 	/// 9. `[]` Token program
-	/// 10. `[]` System program
+	/// 10. `[]` Stake pool account
+	/// 11. `[]` System program
+	/// 12. `[]` Associated Token program
 	RedeemPrincipalAndYield { amount: u64 },
 
 	/// Redeems the principal token for the underlying token
+	/// Each principal token is worth one unit of the accounting asset at maturity and is
+	/// settled at the exchange rate recorded on the first interaction after the expiry date
 	///
 	/// Accounts expected:
 	///
@@ -165,32 +207,41 @@ pub enum TokenizerInstruction {
 	/// 3. `[writable]` Principal token mint account
 	/// 4. `[writable, signer]` User account
 	/// 5. `[writable]` User underlying token account
-	/// 7. `[writable]` User principal token account
-	/// 8. `[]` Token program
+	/// 6. `[writable]` User principal token account
+	/// 7. `[]` Token program
+	/// 8. `[]` Stake pool account
 	/// 9. `[]` System program
+	/// 10. `[]` Associated Token program
 	RedeemMaturePrincipal {
 		/// The amount of the principal token to redeem
 		principal_amount: u64,
 	},
 
 	/// Claims the yield
+	/// Before the expiry date yield tokens claim a pro rata share of the underlying in excess
+	/// of that backing the principal tokens, after the expiry date this share is frozen
 	///
 	/// Accounts expected:
 	///
 	/// 0. `[writable]` Tokenizer account
-	/// 1. `[writable]` Yield token mint account
-	/// 2. `[writable]` Underlying vault account
-	/// 3. `[writable, signer]` User account
-	/// 4. `[writable]` User underlying token account
-	/// 5. `[writable]` User yield token account
-	/// 6. `[]` Token program
-	/// 7. `[]` System program
+	/// 1. `[writable]` Underlying vault account
+	/// 2. `[]` Underlying mint account
+	/// 3. `[writable]` Yield token mint account
+	/// 4. `[writable, signer]` User account
+	/// 5. `[writable]` User underlying token account
+	/// 6. `[writable]` User yield token account
+	/// 7. `[]` Token program
+	/// 8. `[]` Principal token mint account
+	/// 9. `[]` Stake pool account
+	/// 10. `[]` System program
+	/// 11. `[]` Associated Token program
 	ClaimYield {
-		/// The amount of the underlying token to claim
+		/// The amount of the yield token to redeem
 		yield_amount: u64,
 	},
 
-	/// Terminates the LysergicTokenizer and Mints
+	/// Terminates the LysergicTokenizer, revoking its authority over the mints, closing the
+	/// underlying vault and returning the rent to the authority
 	///
 	/// Accounts expected:
 	///
@@ -203,7 +254,7 @@ pub enum TokenizerInstruction {
 	/// 6. `[]` System program
 	Terminate,
 
-	/// Terminates the Tokenizer 
+	/// Terminates the Tokenizer and closes the underlying vault account
 	/// Should only be called AFTER the mints have been terminated
 	/// as the tokenizer is the owner of the mints
 	///
@@ -216,9 +267,10 @@ pub enum TokenizerInstruction {
 	/// 4. `[]` System Program
 	TerminateTokenizer,
 
-	/// Terminates the Mints associated with the LysergicTokenizer
+	/// Revokes the authority of the LysergicTokenizer to mint principal and yield tokens, the
+	/// mints stay open as escrow redemptions burn against them
 	/// This function cannot be called unless the LysergicTokenizer HAS NOT
-	/// been terminated as the tokenizer itself is the owner of the mints
+	/// been terminated as the tokenizer itself is the authority of the mints
 	///
 	/// Accounts expected:
 	///
@@ -229,61 +281,206 @@ pub enum TokenizerInstruction {
 	/// 4. `[]` Token program
 	/// 5. `[]` System program
 	TerminateMints,
+
+	/// Sweeps the remaining underlying from the vault into the claims escrow
+	/// Can only be called once the grace period following the expiry date has elapsed,
+	/// after which the tokenizer can be terminated. When the post expiry yield is routed to
+	/// the treasury it is swept to the treasury first, as `Settle` would
+	///
+	/// Accounts expected:
+	///
+	/// 0. `[writable]` Tokenizer account
+	/// 1. `[writable, signer]` Authority
+	/// 2. `[writable]` Underlying vault account
+	/// 3. `[writable]` Claims escrow account
+	/// 4. `[writable]` Claims escrow vault account
+	/// 5. `[]` Underlying mint account
+	/// 6. `[]` Principal token mint account
+	/// 7. `[]` Token program
+	/// 8. `[]` System program
+	/// 9. `[]` Associated Token program
+	/// 10. `[]` Stake pool account
+	/// 11. `[writable]` Treasury underlying token account
+	SweepToEscrow,
+
+	/// Redeems the principal token for the underlying token held in the claims escrow
+	///
+	/// Accounts expected:
+	///
+	/// 0. `[]` Claims escrow account
+	/// 1. `[writable]` Claims escrow vault account
+	/// 2. `[writable]` Principal token mint account
+	/// 3. `[writable, signer]` User account
+	/// 4. `[writable]` User underlying token account
+	/// 5. `[writable]` User principal token account
+	/// 6. `[]` Token program
+	RedeemEscrowPrincipal {
+		/// The amount of the principal token to redeem
+		principal_amount: u64,
+	},
+
+	/// Claims the yield from the underlying token held in the claims escrow
+	///
+	/// Accounts expected:
+	///
+	/// 0. `[writable]` Claims escrow account
+	/// 1. `[writable]` Claims escrow vault account
+	/// 2. `[writable]` Yield token mint account
+	/// 3. `[writable, signer]` User account
+	/// 4. `[writable]` User underlying token account
+	/// 5. `[writable]` User yield token account
+	/// 6. `[]` Token program
+	ClaimEscrowYield {
+		/// The amount of the yield token to redeem
+		yield_amount: u64,
+	},
+
+	/// Records the expiry exchange rate and yield reserve if they have not been recorded
+	/// and sweeps any yield earned after the expiry date to the treasury
+	/// Can be called by anyone once the expiry date has elapsed
+	///
+	/// Accounts expected:
+	///
+	/// 0. `[writable]` Tokenizer account
+	/// 1. `[writable]` Underlying vault account
+	/// 2. `[]` Principal token mint account
+	/// 3. `[]` Stake pool account
+	/// 4. `[writable]` Treasury underlying token account
+	/// 5. `[]` Token program
+	Settle,
+
+	/// Refreshes the yield index from the exchange rate reported by the stake pool,
+	/// rejecting rates that moved outside the allowed bounds since the last update
+	/// Can be called by anyone
+	///
+	/// Accounts expected:
+	///
+	/// 0. `[writable]` Tokenizer account
+	/// 1. `[]` Stake pool account
+	Checkpoint,
+
+	/// Sets the yield index to the exchange rate reported by the stake pool without checking
+	/// the rate of change bounds, recovering from a change the bounds reject
+	/// Can only be called by the authority
+	///
+	/// Accounts expected:
+	///
+	/// 0. `[writable]` Tokenizer account
+	/// 1. `[signer]` Authority
+	/// 2. `[]` Stake pool account
+	ResetYieldIndex,
+}
+
+/// Accounts of an `InitializeTokenizer` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct InitializeTokenizerAccounts {
+	pub tokenizer: Pubkey,
+	pub authority: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub underlying_mint: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl InitializeTokenizerAccounts {
+	/// Resolves the accounts of the tokenizer of an underlying mint and expiry date
+	pub fn resolve(
+		underlying_mint: &Pubkey,
+		expiry_date: i64,
+		authority: &Pubkey,
+		stake_pool: &Pubkey,
+	) -> Self {
+		let (tokenizer, _) = get_tokenizer_address(underlying_mint, expiry_date);
+
+		InitializeTokenizerAccounts {
+			tokenizer,
+			authority: *authority,
+			underlying_vault: get_associated_token_address(&tokenizer, underlying_mint),
+			underlying_mint: *underlying_mint,
+			principal_token_mint: get_principal_mint_address(&tokenizer).0,
+			yield_token_mint: get_yield_mint_address(&tokenizer).0,
+			stake_pool: *stake_pool,
+		}
+	}
 }
 
 /// Creates an `InitializeTokenizer` instruction
 pub fn init_tokenizer(
-	tokenizer: &Pubkey,
-	authority: &Pubkey,
-	underlying_vault: &Pubkey,
-	underlying_mint: &Pubkey,
-	principal_token_mint: &Pubkey,
-	yield_token_mint: &Pubkey,
+	accounts: &InitializeTokenizerAccounts,
+	treasury: &Pubkey,
 	expiry: Expiry,
 	fixed_apy: u64,
+	grace_period: i64,
+	post_expiry_yield: PostExpiryYieldRecipient,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::InitializeTokenizer {
-			underlying_mint: *underlying_mint,
-			principal_token_mint: *principal_token_mint,
-			yield_token_mint: *yield_token_mint,
+			underlying_mint: accounts.underlying_mint,
+			principal_token_mint: accounts.principal_token_mint,
+			yield_token_mint: accounts.yield_token_mint,
 			expiry,
 			fixed_apy,
+			grace_period,
+			post_expiry_yield,
+			treasury: *treasury,
 		},
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*authority, true),
-			AccountMeta::new(*underlying_vault, false),
-			AccountMeta::new_readonly(*underlying_mint, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.authority, true),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new_readonly(accounts.underlying_mint, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
 			AccountMeta::new_readonly(system_program::id(), false),
 			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
 		],
 	))
 }
 
+/// Accounts of an `InitializeMints` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct InitializeMintsAccounts {
+	pub tokenizer: Pubkey,
+	pub authority: Pubkey,
+	pub underlying_mint: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+}
+
+impl InitializeMintsAccounts {
+	/// Resolves the accounts of the tokenizer of an underlying mint and expiry date
+	pub fn resolve(underlying_mint: &Pubkey, expiry_date: i64, authority: &Pubkey) -> Self {
+		let (tokenizer, _) = get_tokenizer_address(underlying_mint, expiry_date);
+
+		InitializeMintsAccounts {
+			tokenizer,
+			authority: *authority,
+			underlying_mint: *underlying_mint,
+			principal_token_mint: get_principal_mint_address(&tokenizer).0,
+			yield_token_mint: get_yield_mint_address(&tokenizer).0,
+		}
+	}
+}
+
 /// Creates an `InitializeMints` instruction
 pub fn init_mints(
-	lysergic_tokenizer: &Pubkey,
-	authority: &Pubkey,
-	underlying_mint: &Pubkey,
-	principal_token_mint: &Pubkey,
-	yield_token_mint: &Pubkey,
+	accounts: &InitializeMintsAccounts,
 	expiry: Expiry,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::InitializeMints {
-			underlying_mint: *underlying_mint,
+			underlying_mint: accounts.underlying_mint,
 			expiry,
 		},
 		vec![
-			AccountMeta::new(*lysergic_tokenizer, false),
-			AccountMeta::new(*authority, true),
-			AccountMeta::new(*underlying_mint, false),
-			AccountMeta::new(*principal_token_mint, false),
-			AccountMeta::new(*yield_token_mint, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.authority, true),
+			AccountMeta::new(accounts.underlying_mint, false),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
 			AccountMeta::new_readonly(system_program::id(), false),
 		],
@@ -292,277 +489,795 @@ pub fn init_mints(
 
 /// Creates an `InitializeTokenizerAndMints` instruction
 pub fn init_tokenizer_and_mints(
-	tokenizer: &Pubkey,
-	authority: &Pubkey,
-	underlying_vault: &Pubkey,
-	underlying_mint: &Pubkey,
-	principal_token_mint: &Pubkey,
-	yield_token_mint: &Pubkey,
+	accounts: &InitializeTokenizerAccounts,
+	treasury: &Pubkey,
 	expiry: Expiry,
 	fixed_apy: u64,
+	grace_period: i64,
+	post_expiry_yield: PostExpiryYieldRecipient,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::InitializeTokenizerAndMints {
-			underlying_mint: *underlying_mint,
-			principal_token_mint: *principal_token_mint,
-			yield_token_mint: *yield_token_mint,
+			underlying_mint: accounts.underlying_mint,
+			principal_token_mint: accounts.principal_token_mint,
+			yield_token_mint: accounts.yield_token_mint,
 			expiry,
 			fixed_apy,
+			grace_period,
+			post_expiry_yield,
+			treasury: *treasury,
 		},
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*authority, true),
-			AccountMeta::new(*underlying_vault, false),
-			AccountMeta::new_readonly(*underlying_mint, false),
-			AccountMeta::new(*principal_token_mint, false),
-			AccountMeta::new(*yield_token_mint, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.authority, true),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new_readonly(accounts.underlying_mint, false),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
 			AccountMeta::new_readonly(system_program::id(), false),
 			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
 		],
 	))
 }
 
+/// Accounts of a `DepositUnderlying` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepositUnderlyingAccounts {
+	pub tokenizer: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub user: Pubkey,
+	pub user_underlying_token_account: Pubkey,
+}
+
+impl DepositUnderlyingAccounts {
+	/// Resolves the accounts of a tokenizer and the associated token accounts of a user
+	pub fn resolve(tokenizer: &Pubkey, state: &TokenizerState, user: &Pubkey) -> Self {
+		DepositUnderlyingAccounts {
+			tokenizer: *tokenizer,
+			underlying_vault: state.underlying_vault,
+			user: *user,
+			user_underlying_token_account: get_associated_token_address(
+				user,
+				&state.underlying_mint,
+			),
+		}
+	}
+}
+
 /// Creates a `DepositUnderlying` instruction
 pub fn deposit_underlying(
-	tokenizer: &Pubkey,
-	underlying_vault: &Pubkey,
-	user: &Pubkey,
-	user_underlying_token_account: &Pubkey,
+	accounts: &DepositUnderlyingAccounts,
 	amount: u64,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::DepositUnderlying { amount },
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*underlying_vault, false),
-			AccountMeta::new(*user, true),
-			AccountMeta::new(*user_underlying_token_account, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_underlying_token_account, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
 		],
 	))
 }
 
+/// Accounts of a `TokenizePrincipal` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenizePrincipalAccounts {
+	pub tokenizer: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub user: Pubkey,
+	pub user_principal_token_account: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl TokenizePrincipalAccounts {
+	/// Resolves the accounts of a tokenizer and the associated token accounts of a user
+	pub fn resolve(tokenizer: &Pubkey, state: &TokenizerState, user: &Pubkey) -> Self {
+		TokenizePrincipalAccounts {
+			tokenizer: *tokenizer,
+			principal_token_mint: state.principal_token_mint,
+			user: *user,
+			user_principal_token_account: get_associated_token_address(
+				user,
+				&state.principal_token_mint,
+			),
+			stake_pool: state.stake_pool,
+		}
+	}
+}
+
 /// Creates a `TokenizePrincipal` instruction
 pub fn tokenize_principal(
-	lysergic_tokenizer: &Pubkey,
-	principal_token_mint: &Pubkey,
-	user: &Pubkey,
-	user_principal_token_account: &Pubkey,
+	accounts: &TokenizePrincipalAccounts,
 	amount: u64,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::TokenizePrincipal { amount },
 		vec![
-			AccountMeta::new(*lysergic_tokenizer, false),
-			AccountMeta::new(*principal_token_mint, false),
-			AccountMeta::new(*user, true),
-			AccountMeta::new(*user_principal_token_account, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_principal_token_account, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
 			AccountMeta::new_readonly(system_program::id(), false),
 			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
 		],
 	))
 }
 
+/// Accounts of a `TokenizeYield` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenizeYieldAccounts {
+	pub tokenizer: Pubkey,
+	pub yield_token_mint: Pubkey,
+	pub user: Pubkey,
+	pub user_yield_token_account: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl TokenizeYieldAccounts {
+	/// Resolves the accounts of a tokenizer and the associated token accounts of a user
+	pub fn resolve(tokenizer: &Pubkey, state: &TokenizerState, user: &Pubkey) -> Self {
+		TokenizeYieldAccounts {
+			tokenizer: *tokenizer,
+			yield_token_mint: state.yield_token_mint,
+			user: *user,
+			user_yield_token_account: get_associated_token_address(user, &state.yield_token_mint),
+			stake_pool: state.stake_pool,
+		}
+	}
+}
+
 /// Creates a `TokenizeYield` instruction
 pub fn tokenize_yield(
-	lysergic_tokenizer: &Pubkey,
-	yield_token_mint: &Pubkey,
-	user: &Pubkey,
-	user_yield_token_account: &Pubkey,
+	accounts: &TokenizeYieldAccounts,
 	amount: u64,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::TokenizeYield { amount },
 		vec![
-			AccountMeta::new(*lysergic_tokenizer, false),
-			AccountMeta::new(*yield_token_mint, false),
-			AccountMeta::new(*user, true),
-			AccountMeta::new(*user_yield_token_account, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_yield_token_account, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
 			AccountMeta::new_readonly(system_program::id(), false),
 			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
 		],
 	))
 }
 
+/// Accounts of a `DepositAndTokenize` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepositAndTokenizeAccounts {
+	pub tokenizer: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+	pub user: Pubkey,
+	pub user_underlying_token_account: Pubkey,
+	pub user_principal_token_account: Pubkey,
+	pub user_yield_token_account: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl DepositAndTokenizeAccounts {
+	/// Resolves the accounts of a tokenizer and the associated token accounts of a user
+	pub fn resolve(tokenizer: &Pubkey, state: &TokenizerState, user: &Pubkey) -> Self {
+		DepositAndTokenizeAccounts {
+			tokenizer: *tokenizer,
+			underlying_vault: state.underlying_vault,
+			principal_token_mint: state.principal_token_mint,
+			yield_token_mint: state.yield_token_mint,
+			user: *user,
+			user_underlying_token_account: get_associated_token_address(
+				user,
+				&state.underlying_mint,
+			),
+			user_principal_token_account: get_associated_token_address(
+				user,
+				&state.principal_token_mint,
+			),
+			user_yield_token_account: get_associated_token_address(user, &state.yield_token_mint),
+			stake_pool: state.stake_pool,
+		}
+	}
+}
+
 /// Creates a `DepositAndTokenize` instruction
 pub fn deposit_and_tokenize(
-	tokenizer: &Pubkey,
-	underlying_vault: &Pubkey,
-	principal_token_mint: &Pubkey,
-	yield_token_mint: &Pubkey,
-	user: &Pubkey,
-	user_underlying_token_account: &Pubkey,
-	user_principal_token_account: &Pubkey,
-	user_yield_token_account: &Pubkey,
+	accounts: &DepositAndTokenizeAccounts,
 	amount: u64,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::DepositAndTokenize { amount },
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*underlying_vault, false),
-			AccountMeta::new(*principal_token_mint, false),
-			AccountMeta::new(*yield_token_mint, false),
-			AccountMeta::new(*user, true),
-			AccountMeta::new(*user_underlying_token_account, false),
-			AccountMeta::new(*user_principal_token_account, false),
-			AccountMeta::new(*user_yield_token_account, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_underlying_token_account, false),
+			AccountMeta::new(accounts.user_principal_token_account, false),
+			AccountMeta::new(accounts.user_yield_token_account, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
 			AccountMeta::new_readonly(system_program::id(), false),
 			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
 		],
 	))
 }
 
-/// Creates a `RedeemPrincipal` instruction
+/// Accounts of a `RedeemMaturePrincipal` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedeemMaturePrincipalAccounts {
+	pub tokenizer: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub underlying_mint: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub user: Pubkey,
+	pub user_underlying_token_account: Pubkey,
+	pub user_principal_token_account: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl RedeemMaturePrincipalAccounts {
+	/// Resolves the accounts of a tokenizer and the associated token accounts of a user
+	pub fn resolve(tokenizer: &Pubkey, state: &TokenizerState, user: &Pubkey) -> Self {
+		RedeemMaturePrincipalAccounts {
+			tokenizer: *tokenizer,
+			underlying_vault: state.underlying_vault,
+			underlying_mint: state.underlying_mint,
+			principal_token_mint: state.principal_token_mint,
+			user: *user,
+			user_underlying_token_account: get_associated_token_address(
+				user,
+				&state.underlying_mint,
+			),
+			user_principal_token_account: get_associated_token_address(
+				user,
+				&state.principal_token_mint,
+			),
+			stake_pool: state.stake_pool,
+		}
+	}
+}
+
+/// Creates a `RedeemMaturePrincipal` instruction
 pub fn redeem_mature_principal(
-	tokenizer: &Pubkey,
-	underlying_vault: &Pubkey,
-	underlying_mint: &Pubkey,
-	principal_token_mint: &Pubkey,
-	user: &Pubkey,
-	user_underlying_token_account: &Pubkey,
-	user_principal_token_account: &Pubkey,
+	accounts: &RedeemMaturePrincipalAccounts,
 	principal_amount: u64,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::RedeemMaturePrincipal { principal_amount },
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*underlying_vault, false),
-			AccountMeta::new_readonly(*underlying_mint, false),
-			AccountMeta::new(*principal_token_mint, false),
-			AccountMeta::new(*user, true),
-			AccountMeta::new(*user_underlying_token_account, false),
-			AccountMeta::new(*user_principal_token_account, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new_readonly(accounts.underlying_mint, false),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_underlying_token_account, false),
+			AccountMeta::new(accounts.user_principal_token_account, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
 			AccountMeta::new_readonly(system_program::id(), false),
+			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
 		],
 	))
 }
 
+/// Accounts of a `RedeemPrincipalAndYield` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedeemPrincipalAndYieldAccounts {
+	pub tokenizer: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub underlying_mint: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+	pub user: Pubkey,
+	pub user_underlying_token_account: Pubkey,
+	pub user_principal_token_account: Pubkey,
+	pub user_yield_token_account: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl RedeemPrincipalAndYieldAccounts {
+	/// Resolves the accounts of a tokenizer and the associated token accounts of a user
+	pub fn resolve(tokenizer: &Pubkey, state: &TokenizerState, user: &Pubkey) -> Self {
+		RedeemPrincipalAndYieldAccounts {
+			tokenizer: *tokenizer,
+			underlying_vault: state.underlying_vault,
+			underlying_mint: state.underlying_mint,
+			principal_token_mint: state.principal_token_mint,
+			yield_token_mint: state.yield_token_mint,
+			user: *user,
+			user_underlying_token_account: get_associated_token_address(
+				user,
+				&state.underlying_mint,
+			),
+			user_principal_token_account: get_associated_token_address(
+				user,
+				&state.principal_token_mint,
+			),
+			user_yield_token_account: get_associated_token_address(user, &state.yield_token_mint),
+			stake_pool: state.stake_pool,
+		}
+	}
+}
+
 /// Creates a `RedeemPrincipalAndYield` instruction
 pub fn redeem_principal_and_yield(
-	tokenizer: &Pubkey,
-	underlying_vault: &Pubkey,
-	underlying_mint: &Pubkey,
-	principal_token_mint: &Pubkey,
-	yield_token_mint: &Pubkey,
-	user: &Pubkey,
-	user_underlying_token_account: &Pubkey,
-	user_principal_token_account: &Pubkey,
-	user_yield_token_account: &Pubkey,
+	accounts: &RedeemPrincipalAndYieldAccounts,
 	amount: u64,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::RedeemPrincipalAndYield { amount },
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*underlying_vault, false),
-			AccountMeta::new_readonly(*underlying_mint, false),
-			AccountMeta::new(*principal_token_mint, false),
-			AccountMeta::new(*yield_token_mint, false),
-			AccountMeta::new(*user, true),
-			AccountMeta::new(*user_underlying_token_account, false),
-			AccountMeta::new(*user_principal_token_account, false),
-			AccountMeta::new(*user_yield_token_account, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new_readonly(accounts.underlying_mint, false),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_underlying_token_account, false),
+			AccountMeta::new(accounts.user_principal_token_account, false),
+			AccountMeta::new(accounts.user_yield_token_account, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
 			AccountMeta::new_readonly(system_program::id(), false),
+			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
 		],
 	))
 }
 
+/// Accounts of a `ClaimYield` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimYieldAccounts {
+	pub tokenizer: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub underlying_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+	pub user: Pubkey,
+	pub user_underlying_token_account: Pubkey,
+	pub user_yield_token_account: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl ClaimYieldAccounts {
+	/// Resolves the accounts of a tokenizer and the associated token accounts of a user
+	pub fn resolve(tokenizer: &Pubkey, state: &TokenizerState, user: &Pubkey) -> Self {
+		ClaimYieldAccounts {
+			tokenizer: *tokenizer,
+			underlying_vault: state.underlying_vault,
+			underlying_mint: state.underlying_mint,
+			yield_token_mint: state.yield_token_mint,
+			user: *user,
+			user_underlying_token_account: get_associated_token_address(
+				user,
+				&state.underlying_mint,
+			),
+			user_yield_token_account: get_associated_token_address(user, &state.yield_token_mint),
+			principal_token_mint: state.principal_token_mint,
+			stake_pool: state.stake_pool,
+		}
+	}
+}
+
 /// Creates a `ClaimYield` instruction
 pub fn claim_yield(
-	tokenizer: &Pubkey,
-	underlying_vault: &Pubkey,
-	yield_token_mint: &Pubkey,
-	user: &Pubkey,
-	user_underlying_token_account: &Pubkey,
-	user_yield_token_account: &Pubkey,
+	accounts: &ClaimYieldAccounts,
 	yield_amount: u64,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::ClaimYield { yield_amount },
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*underlying_vault, false),
-			AccountMeta::new(*yield_token_mint, false),
-			AccountMeta::new(*user, true),
-			AccountMeta::new(*user_underlying_token_account, false),
-			AccountMeta::new(*user_yield_token_account, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new_readonly(accounts.underlying_mint, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_underlying_token_account, false),
+			AccountMeta::new(accounts.user_yield_token_account, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
+			AccountMeta::new_readonly(accounts.principal_token_mint, false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
+			AccountMeta::new_readonly(system_program::id(), false),
+			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
 		],
 	))
 }
 
-pub fn terminate(
-	tokenizer: &Pubkey,
-	authority: &Pubkey,
-	underlying_vault: &Pubkey,
-	principal_token_mint: &Pubkey,
-	yield_token_mint: &Pubkey,
-) -> Result<Instruction, ProgramError> {
+/// Accounts of a `Terminate` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct TerminateAccounts {
+	pub tokenizer: Pubkey,
+	pub authority: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+}
+
+impl TerminateAccounts {
+	/// Resolves the accounts of a tokenizer from its address and underlying mint, without
+	/// reading its state
+	pub fn resolve(tokenizer: &Pubkey, underlying_mint: &Pubkey, authority: &Pubkey) -> Self {
+		TerminateAccounts {
+			tokenizer: *tokenizer,
+			authority: *authority,
+			underlying_vault: get_associated_token_address(tokenizer, underlying_mint),
+			principal_token_mint: get_principal_mint_address(tokenizer).0,
+			yield_token_mint: get_yield_mint_address(tokenizer).0,
+		}
+	}
+}
+
+pub fn terminate(accounts: &TerminateAccounts) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::Terminate,
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*authority, true),
-			AccountMeta::new(*underlying_vault, false),
-			AccountMeta::new(*principal_token_mint, false),
-			AccountMeta::new(*yield_token_mint, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.authority, true),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
 			AccountMeta::new_readonly(system_program::id(), false),
 		],
 	))
 }
 
+/// Accounts of a `TerminateTokenizer` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct TerminateTokenizerAccounts {
+	pub tokenizer: Pubkey,
+	pub authority: Pubkey,
+	pub underlying_vault: Pubkey,
+}
+
+impl TerminateTokenizerAccounts {
+	/// Resolves the accounts of a tokenizer from its address and underlying mint, without
+	/// reading its state
+	pub fn resolve(tokenizer: &Pubkey, underlying_mint: &Pubkey, authority: &Pubkey) -> Self {
+		TerminateTokenizerAccounts {
+			tokenizer: *tokenizer,
+			authority: *authority,
+			underlying_vault: get_associated_token_address(tokenizer, underlying_mint),
+		}
+	}
+}
+
 pub fn terminate_tokenizer(
-	tokenizer: &Pubkey,
-	authority: &Pubkey,
-	underlying_vault: &Pubkey,
+	accounts: &TerminateTokenizerAccounts,
 ) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::TerminateTokenizer,
 		vec![
-			AccountMeta::new(*tokenizer, false),
-			AccountMeta::new(*authority, true),
-			AccountMeta::new(*underlying_vault, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.authority, true),
+			AccountMeta::new(accounts.underlying_vault, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
 			AccountMeta::new_readonly(system_program::id(), false),
 		],
 	))
 }
 
-pub fn terminate_mints(
-	lysergic_tokenizer: &Pubkey,
-	authority: &Pubkey,
-	principal_token_mint: &Pubkey,
-	yield_token_mint: &Pubkey,
-) -> Result<Instruction, ProgramError> {
+/// Accounts of a `TerminateMints` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct TerminateMintsAccounts {
+	pub tokenizer: Pubkey,
+	pub authority: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+}
+
+impl TerminateMintsAccounts {
+	/// Resolves the accounts of a tokenizer from its address, without reading its state
+	pub fn resolve(tokenizer: &Pubkey, authority: &Pubkey) -> Self {
+		TerminateMintsAccounts {
+			tokenizer: *tokenizer,
+			authority: *authority,
+			principal_token_mint: get_principal_mint_address(tokenizer).0,
+			yield_token_mint: get_yield_mint_address(tokenizer).0,
+		}
+	}
+}
+
+pub fn terminate_mints(accounts: &TerminateMintsAccounts) -> Result<Instruction, ProgramError> {
 	Ok(Instruction::new_with_borsh(
 		crate::id(),
 		&TokenizerInstruction::TerminateMints,
 		vec![
-			AccountMeta::new(*lysergic_tokenizer, false),
-			AccountMeta::new(*authority, true),
-			AccountMeta::new(*principal_token_mint, false),
-			AccountMeta::new(*yield_token_mint, false),
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.authority, true),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
+			AccountMeta::new_readonly(spl_token::id(), false),
+			AccountMeta::new_readonly(system_program::id(), false),
+		],
+	))
+}
+
+/// Accounts of a `SweepToEscrow` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepToEscrowAccounts {
+	pub tokenizer: Pubkey,
+	pub authority: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub escrow: Pubkey,
+	pub escrow_vault: Pubkey,
+	pub underlying_mint: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub stake_pool: Pubkey,
+	pub treasury_token_account: Pubkey,
+}
+
+impl SweepToEscrowAccounts {
+	/// Resolves the accounts of a tokenizer, its claims escrow and the underlying token
+	/// account of its treasury, without reading the tokenizer state
+	pub fn resolve(
+		tokenizer: &Pubkey,
+		underlying_mint: &Pubkey,
+		authority: &Pubkey,
+		stake_pool: &Pubkey,
+		treasury: &Pubkey,
+	) -> Self {
+		let (escrow, _) = get_escrow_address(tokenizer);
+
+		SweepToEscrowAccounts {
+			tokenizer: *tokenizer,
+			authority: *authority,
+			underlying_vault: get_associated_token_address(tokenizer, underlying_mint),
+			escrow,
+			escrow_vault: get_associated_token_address(&escrow, underlying_mint),
+			underlying_mint: *underlying_mint,
+			principal_token_mint: get_principal_mint_address(tokenizer).0,
+			stake_pool: *stake_pool,
+			treasury_token_account: get_associated_token_address(treasury, underlying_mint),
+		}
+	}
+}
+
+/// Creates a `SweepToEscrow` instruction
+pub fn sweep_to_escrow(accounts: &SweepToEscrowAccounts) -> Result<Instruction, ProgramError> {
+	Ok(Instruction::new_with_borsh(
+		crate::id(),
+		&TokenizerInstruction::SweepToEscrow,
+		vec![
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.authority, true),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new(accounts.escrow, false),
+			AccountMeta::new(accounts.escrow_vault, false),
+			AccountMeta::new_readonly(accounts.underlying_mint, false),
+			AccountMeta::new_readonly(accounts.principal_token_mint, false),
 			AccountMeta::new_readonly(spl_token::id(), false),
 			AccountMeta::new_readonly(system_program::id(), false),
+			AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
+			AccountMeta::new(accounts.treasury_token_account, false),
+		],
+	))
+}
+
+/// Accounts of a `RedeemEscrowPrincipal` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedeemEscrowPrincipalAccounts {
+	pub escrow: Pubkey,
+	pub escrow_vault: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub user: Pubkey,
+	pub user_underlying_token_account: Pubkey,
+	pub user_principal_token_account: Pubkey,
+}
+
+impl RedeemEscrowPrincipalAccounts {
+	/// Resolves the claims escrow of a tokenizer and the associated token accounts of a
+	/// user, the tokenizer account may already be terminated so its accounts are derived
+	pub fn resolve(tokenizer: &Pubkey, underlying_mint: &Pubkey, user: &Pubkey) -> Self {
+		let (escrow, _) = get_escrow_address(tokenizer);
+		let (principal_token_mint, _) = get_principal_mint_address(tokenizer);
+
+		RedeemEscrowPrincipalAccounts {
+			escrow,
+			escrow_vault: get_associated_token_address(&escrow, underlying_mint),
+			principal_token_mint,
+			user: *user,
+			user_underlying_token_account: get_associated_token_address(user, underlying_mint),
+			user_principal_token_account: get_associated_token_address(
+				user,
+				&principal_token_mint,
+			),
+		}
+	}
+}
+
+/// Creates a `RedeemEscrowPrincipal` instruction
+pub fn redeem_escrow_principal(
+	accounts: &RedeemEscrowPrincipalAccounts,
+	principal_amount: u64,
+) -> Result<Instruction, ProgramError> {
+	Ok(Instruction::new_with_borsh(
+		crate::id(),
+		&TokenizerInstruction::RedeemEscrowPrincipal { principal_amount },
+		vec![
+			AccountMeta::new_readonly(accounts.escrow, false),
+			AccountMeta::new(accounts.escrow_vault, false),
+			AccountMeta::new(accounts.principal_token_mint, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_underlying_token_account, false),
+			AccountMeta::new(accounts.user_principal_token_account, false),
+			AccountMeta::new_readonly(spl_token::id(), false),
+		],
+	))
+}
+
+/// Accounts of a `ClaimEscrowYield` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimEscrowYieldAccounts {
+	pub escrow: Pubkey,
+	pub escrow_vault: Pubkey,
+	pub yield_token_mint: Pubkey,
+	pub user: Pubkey,
+	pub user_underlying_token_account: Pubkey,
+	pub user_yield_token_account: Pubkey,
+}
+
+impl ClaimEscrowYieldAccounts {
+	/// Resolves the claims escrow of a tokenizer and the associated token accounts of a
+	/// user, the tokenizer account may already be terminated so its accounts are derived
+	pub fn resolve(tokenizer: &Pubkey, underlying_mint: &Pubkey, user: &Pubkey) -> Self {
+		let (escrow, _) = get_escrow_address(tokenizer);
+		let (yield_token_mint, _) = get_yield_mint_address(tokenizer);
+
+		ClaimEscrowYieldAccounts {
+			escrow,
+			escrow_vault: get_associated_token_address(&escrow, underlying_mint),
+			yield_token_mint,
+			user: *user,
+			user_underlying_token_account: get_associated_token_address(user, underlying_mint),
+			user_yield_token_account: get_associated_token_address(user, &yield_token_mint),
+		}
+	}
+}
+
+/// Creates a `ClaimEscrowYield` instruction
+pub fn claim_escrow_yield(
+	accounts: &ClaimEscrowYieldAccounts,
+	yield_amount: u64,
+) -> Result<Instruction, ProgramError> {
+	Ok(Instruction::new_with_borsh(
+		crate::id(),
+		&TokenizerInstruction::ClaimEscrowYield { yield_amount },
+		vec![
+			AccountMeta::new(accounts.escrow, false),
+			AccountMeta::new(accounts.escrow_vault, false),
+			AccountMeta::new(accounts.yield_token_mint, false),
+			AccountMeta::new(accounts.user, true),
+			AccountMeta::new(accounts.user_underlying_token_account, false),
+			AccountMeta::new(accounts.user_yield_token_account, false),
+			AccountMeta::new_readonly(spl_token::id(), false),
+		],
+	))
+}
+
+/// Accounts of a `Settle` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettleAccounts {
+	pub tokenizer: Pubkey,
+	pub underlying_vault: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub stake_pool: Pubkey,
+	pub treasury_token_account: Pubkey,
+}
+
+impl SettleAccounts {
+	/// Resolves the accounts of a tokenizer and the underlying token account of its treasury,
+	/// without reading the tokenizer state
+	pub fn resolve(
+		tokenizer: &Pubkey,
+		underlying_mint: &Pubkey,
+		stake_pool: &Pubkey,
+		treasury: &Pubkey,
+	) -> Self {
+		SettleAccounts {
+			tokenizer: *tokenizer,
+			underlying_vault: get_associated_token_address(tokenizer, underlying_mint),
+			principal_token_mint: get_principal_mint_address(tokenizer).0,
+			stake_pool: *stake_pool,
+			treasury_token_account: get_associated_token_address(treasury, underlying_mint),
+		}
+	}
+}
+
+/// Creates a `Settle` instruction
+pub fn settle(accounts: &SettleAccounts) -> Result<Instruction, ProgramError> {
+	Ok(Instruction::new_with_borsh(
+		crate::id(),
+		&TokenizerInstruction::Settle,
+		vec![
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new(accounts.underlying_vault, false),
+			AccountMeta::new_readonly(accounts.principal_token_mint, false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
+			AccountMeta::new(accounts.treasury_token_account, false),
+			AccountMeta::new_readonly(spl_token::id(), false),
+		],
+	))
+}
+
+/// Accounts of a `Checkpoint` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckpointAccounts {
+	pub tokenizer: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl CheckpointAccounts {
+	/// Resolves the accounts of a tokenizer, without reading its state
+	pub fn resolve(tokenizer: &Pubkey, stake_pool: &Pubkey) -> Self {
+		CheckpointAccounts {
+			tokenizer: *tokenizer,
+			stake_pool: *stake_pool,
+		}
+	}
+}
+
+/// Creates a `Checkpoint` instruction
+pub fn checkpoint(accounts: &CheckpointAccounts) -> Result<Instruction, ProgramError> {
+	Ok(Instruction::new_with_borsh(
+		crate::id(),
+		&TokenizerInstruction::Checkpoint,
+		vec![
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
+		],
+	))
+}
+
+/// Accounts of a `ResetYieldIndex` instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResetYieldIndexAccounts {
+	pub tokenizer: Pubkey,
+	pub authority: Pubkey,
+	pub stake_pool: Pubkey,
+}
+
+impl ResetYieldIndexAccounts {
+	/// Resolves the accounts of a tokenizer, without reading its state
+	pub fn resolve(tokenizer: &Pubkey, authority: &Pubkey, stake_pool: &Pubkey) -> Self {
+		ResetYieldIndexAccounts {
+			tokenizer: *tokenizer,
+			authority: *authority,
+			stake_pool: *stake_pool,
+		}
+	}
+}
+
+/// Creates a `ResetYieldIndex` instruction
+pub fn reset_yield_index(accounts: &ResetYieldIndexAccounts) -> Result<Instruction, ProgramError> {
+	Ok(Instruction::new_with_borsh(
+		crate::id(),
+		&TokenizerInstruction::ResetYieldIndex,
+		vec![
+			AccountMeta::new(accounts.tokenizer, false),
+			AccountMeta::new_readonly(accounts.authority, true),
+			AccountMeta::new_readonly(accounts.stake_pool, false),
 		],
 	))
 }
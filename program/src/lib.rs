@@ -1,7 +1,12 @@
+#[cfg(feature = "bench")]
+pub mod bench;
 pub mod entrypoint;
 pub mod error;
+pub mod event;
 pub mod instruction;
+pub mod math;
 pub mod processor;
+pub mod rate;
 pub mod state;
 
 use {
@@ -33,6 +38,12 @@ pub fn get_yield_mint_address(tokenizer_address: &Pubkey) -> (Pubkey, u8) {
 	Pubkey::find_program_address(seeds, &crate::id())
 }
 
+// Generate the claims escrow address
+pub fn get_escrow_address(tokenizer_address: &Pubkey) -> (Pubkey, u8) {
+	let seeds = &[b"escrow", &tokenizer_address.to_bytes()[..]];
+	Pubkey::find_program_address(seeds, &crate::id())
+}
+
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, BorshSchema, PartialEq)]
 pub enum Expiry {
 	TwelveMonths,
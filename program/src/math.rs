@@ -0,0 +1,216 @@
+use {
+	crate::error::TokenizerError, solana_program::program_error::ProgramError, uint::construct_uint,
+};
+
+// The expansion of `construct_uint!` trips lints the crate cannot fix
+#[allow(clippy::assign_op_pattern, clippy::manual_div_ceil)]
+mod uint256 {
+	use super::construct_uint;
+
+	construct_uint! {
+		/// A 256 bit unsigned integer holding products that overflow a `u128`
+		pub struct U256(4);
+	}
+}
+
+pub use uint256::U256;
+
+/// Fixed point precision, a value of `WAD` is one. An exchange rate of `WAD` is one lamport per
+/// underlying token
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// The natural logarithm of two, scaled by `WAD`
+pub const LN_2: u128 = 693_147_180_559_945_309;
+
+// Magnitude of the exponents `exp_wad` reduces, in whole units
+const EXP_ARGUMENT_BOUND: i128 = 64;
+
+fn calculation_failure() -> ProgramError {
+	TokenizerError::CalculationFailure.into()
+}
+
+/// Returns `a * b / denominator` rounding down, the product may exceed a `u128`
+pub fn mul_div(a: u128, b: u128, denominator: u128) -> Result<u128, ProgramError> {
+	if denominator == 0 {
+		return Err(calculation_failure());
+	}
+
+	if let Some(product) = a.checked_mul(b) {
+		return Ok(product / denominator);
+	}
+
+	u128::try_from(U256::from(a) * U256::from(b) / U256::from(denominator))
+		.map_err(|_| calculation_failure())
+}
+
+/// Returns `a * b / denominator` rounding up, the product may exceed a `u128`
+pub fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Result<u128, ProgramError> {
+	if denominator == 0 {
+		return Err(calculation_failure());
+	}
+
+	if let Some(product) = a.checked_mul(b) {
+		return Ok(product.div_ceil(denominator));
+	}
+
+	let (quotient, remainder) = (U256::from(a) * U256::from(b)).div_mod(U256::from(denominator));
+	let quotient = u128::try_from(quotient).map_err(|_| calculation_failure())?;
+
+	if remainder.is_zero() {
+		Ok(quotient)
+	} else {
+		quotient.checked_add(1).ok_or(calculation_failure())
+	}
+}
+
+/// Multiplies two `WAD` scaled values, rounding down
+pub fn mul_wad(a: u128, b: u128) -> Result<u128, ProgramError> {
+	mul_div(a, b, WAD)
+}
+
+/// Multiplies two `WAD` scaled values, rounding up
+pub fn mul_wad_ceil(a: u128, b: u128) -> Result<u128, ProgramError> {
+	mul_div_ceil(a, b, WAD)
+}
+
+/// Divides two `WAD` scaled values, rounding down
+pub fn div_wad(a: u128, b: u128) -> Result<u128, ProgramError> {
+	mul_div(a, WAD, b)
+}
+
+/// Divides two `WAD` scaled values, rounding up
+pub fn div_wad_ceil(a: u128, b: u128) -> Result<u128, ProgramError> {
+	mul_div_ceil(a, WAD, b)
+}
+
+/// Returns `e^x` for a `WAD` scaled exponent, rounding down to zero for exponents below
+/// about -41.4 and failing once the result no longer fits in a `u128`, above about 47.3
+pub fn exp_wad(x: i128) -> Result<u128, ProgramError> {
+	let wad = WAD as i128;
+	let ln_2 = LN_2 as i128;
+
+	// Bounds well outside the representable results keep the reduction below from overflowing
+	if x < -EXP_ARGUMENT_BOUND * wad {
+		return Ok(0);
+	}
+	if x > EXP_ARGUMENT_BOUND * wad {
+		return Err(calculation_failure());
+	}
+
+	// e^x = 2^k * e^r with |r| <= ln(2) / 2, so the series converges within 20 terms
+	let k = if x >= 0 {
+		(x + ln_2 / 2) / ln_2
+	} else {
+		(x - ln_2 / 2) / ln_2
+	};
+	let r = x - k * ln_2;
+
+	let mut term = wad;
+	let mut sum = wad;
+	let mut n = 1;
+	while term != 0 {
+		term = term * r / (wad * n);
+		sum += term;
+		n += 1;
+	}
+
+	// The sum is at least e^(-ln(2) / 2), so it is positive
+	let sum = sum as u128;
+
+	if k >= 0 {
+		let shift = u32::try_from(k).map_err(|_| calculation_failure())?;
+		if shift > sum.leading_zeros() {
+			return Err(calculation_failure());
+		}
+
+		Ok(sum << shift)
+	} else {
+		Ok(u32::try_from(-k)
+			.ok()
+			.and_then(|shift| sum.checked_shr(shift))
+			.unwrap_or(0))
+	}
+}
+
+/// Returns the natural logarithm of a `WAD` scaled value, negative for values below one
+pub fn ln_wad(x: u128) -> Result<i128, ProgramError> {
+	if x == 0 {
+		return Err(calculation_failure());
+	}
+
+	let wad = WAD as i128;
+
+	// ln(x) = k * ln(2) + ln(m), shifting x to the same bit length as `WAD` leaves m between
+	// 0.57 and 1.16
+	let k = WAD.leading_zeros() as i128 - x.leading_zeros() as i128;
+	let m = (if k >= 0 { x >> k } else { x << -k }) as i128;
+
+	// ln(m) = 2 * atanh(z) with z = (m - 1) / (m + 1), |z| < 0.27 converges within 20 terms
+	let z = (m - wad) * wad / (m + wad);
+	let z_squared = z * z / wad;
+
+	let mut term = z;
+	let mut sum = z;
+	let mut n = 3;
+	loop {
+		term = term * z_squared / wad;
+		if term == 0 {
+			break;
+		}
+
+		sum += term / n;
+		n += 2;
+	}
+
+	Ok(k * LN_2 as i128 + 2 * sum)
+}
+
+/// Raises a `WAD` scaled base to a `WAD` scaled exponent, `e^(y * ln(x))`
+pub fn pow_wad(base: u128, exponent: i128) -> Result<u128, ProgramError> {
+	if exponent == 0 {
+		return Ok(WAD);
+	}
+
+	if base == 0 {
+		return if exponent > 0 {
+			Ok(0)
+		} else {
+			Err(calculation_failure())
+		};
+	}
+
+	let ln = ln_wad(base)?;
+	let product = ln.checked_mul(exponent).ok_or(calculation_failure())?;
+
+	exp_wad(product / WAD as i128)
+}
+
+/// Raises a `WAD` scaled base to an integer exponent by repeated squaring, rounding down
+/// after each multiplication. Compounding over whole periods this way avoids the error of the
+/// logarithm in `pow_wad`
+pub fn powi_wad(base: u128, exponent: u64) -> Result<u128, ProgramError> {
+	let mut result = WAD;
+	let mut base = base;
+	let mut exponent = exponent;
+
+	while exponent > 0 {
+		if exponent & 1 == 1 {
+			result = mul_wad(result, base)?;
+		}
+
+		exponent >>= 1;
+		if exponent > 0 {
+			base = mul_wad(base, base)?;
+		}
+	}
+
+	Ok(result)
+}
+
+/// Converts an amount of whole tokens into base units of a mint with the given decimals
+pub fn ui_amount_to_amount(amount: u64, decimals: u8) -> Result<u64, ProgramError> {
+	10u64
+		.checked_pow(decimals as u32)
+		.and_then(|scale| amount.checked_mul(scale))
+		.ok_or(calculation_failure())
+}
@@ -1,9 +1,19 @@
 use {
 	crate::{
 		error::TokenizerError,
-		get_principal_mint_address, get_tokenizer_address, get_yield_mint_address,
+		event::TokenizerEvent,
+		get_escrow_address, get_principal_mint_address, get_tokenizer_address,
+		get_yield_mint_address,
 		instruction::TokenizerInstruction,
-		state::{TokenizerState, STATE_SIZE},
+		math::ui_amount_to_amount,
+		rate::{
+			asset_to_underlying, asset_to_underlying_ceil, check_rate_change, get_exchange_rate,
+			pro_rata_share, underlying_to_asset,
+		},
+		state::{
+			ClaimsEscrowState, PostExpiryYieldRecipient, TokenizerState, ESCROW_STATE_SIZE,
+			STATE_SIZE,
+		},
 		Expiry,
 	},
 	borsh::{BorshDeserialize, BorshSerialize},
@@ -28,6 +38,23 @@ pub enum RedemptionMode {
 	PrincipalYield,
 }
 
+pub enum EscrowRedemption {
+	Principal,
+	Yield,
+}
+
+/// Parameters of an `InitializeTokenizer` or `InitializeTokenizerAndMints` instruction
+pub struct InitializeTokenizerParams {
+	pub underlying_mint: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+	pub expiry: Expiry,
+	pub fixed_apy: u64,
+	pub grace_period: i64,
+	pub post_expiry_yield: PostExpiryYieldRecipient,
+	pub treasury: Pubkey,
+}
+
 pub struct TokenizerProcessor;
 
 impl TokenizerProcessor {
@@ -47,13 +74,21 @@ impl TokenizerProcessor {
 				yield_token_mint,
 				expiry,
 				fixed_apy,
+				grace_period,
+				post_expiry_yield,
+				treasury,
 			} => Self::process_initialize_lysergic_tokenizer(
 				accounts,
-				underlying_mint,
-				principal_token_mint,
-				yield_token_mint,
-				&expiry,
-				fixed_apy,
+				&InitializeTokenizerParams {
+					underlying_mint,
+					principal_token_mint,
+					yield_token_mint,
+					expiry,
+					fixed_apy,
+					grace_period,
+					post_expiry_yield,
+					treasury,
+				},
 			),
 			TokenizerInstruction::InitializeMints {
 				underlying_mint,
@@ -65,13 +100,21 @@ impl TokenizerProcessor {
 				yield_token_mint,
 				expiry,
 				fixed_apy,
+				grace_period,
+				post_expiry_yield,
+				treasury,
 			} => Self::process_initialize_tokenizer_and_mints(
 				accounts,
-				underlying_mint,
-				principal_token_mint,
-				yield_token_mint,
-				expiry,
-				fixed_apy,
+				&InitializeTokenizerParams {
+					underlying_mint,
+					principal_token_mint,
+					yield_token_mint,
+					expiry,
+					fixed_apy,
+					grace_period,
+					post_expiry_yield,
+					treasury,
+				},
 			),
 			TokenizerInstruction::DepositUnderlying { amount } => {
 				Self::process_deposit_underlying(accounts, amount)
@@ -99,17 +142,38 @@ impl TokenizerProcessor {
 				Self::process_terminate_lysergic_tokenizer(accounts)
 			}
 			TokenizerInstruction::TerminateMints => Self::process_terminate_mints(accounts),
+			TokenizerInstruction::SweepToEscrow => Self::process_sweep_to_escrow(accounts),
+			TokenizerInstruction::RedeemEscrowPrincipal { principal_amount } => {
+				Self::process_redeem_from_escrow(
+					accounts,
+					EscrowRedemption::Principal,
+					principal_amount,
+				)
+			}
+			TokenizerInstruction::ClaimEscrowYield { yield_amount } => {
+				Self::process_redeem_from_escrow(accounts, EscrowRedemption::Yield, yield_amount)
+			}
+			TokenizerInstruction::Settle => Self::process_settle(accounts),
+			TokenizerInstruction::Checkpoint => Self::process_checkpoint(accounts),
+			TokenizerInstruction::ResetYieldIndex => Self::process_reset_yield_index(accounts),
 		}
 	}
 
 	fn process_initialize_lysergic_tokenizer(
 		accounts: &[AccountInfo],
-		underlying_mint: Pubkey,
-		principal_token_mint: Pubkey,
-		yield_token_mint: Pubkey,
-		expiry: &Expiry,
-		fixed_apy: u64,
+		params: &InitializeTokenizerParams,
 	) -> ProgramResult {
+		let InitializeTokenizerParams {
+			underlying_mint,
+			principal_token_mint,
+			yield_token_mint,
+			ref expiry,
+			fixed_apy,
+			grace_period,
+			ref post_expiry_yield,
+			treasury,
+		} = *params;
+
 		let account_info_iter = &mut accounts.iter();
 		let lysergic_tokenizer_account = next_account_info(account_info_iter)?;
 		let authority = next_account_info(account_info_iter)?;
@@ -118,9 +182,11 @@ impl TokenizerProcessor {
 		let token_program = next_account_info(account_info_iter)?;
 		let system_program = next_account_info(account_info_iter)?;
 		let atoken_program = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
 
 		let rent = rent::Rent::get()?;
-		let timestamp = Clock::get()?.unix_timestamp;
+		let clock = Clock::get()?;
+		let timestamp = clock.unix_timestamp;
 
 		let expiry_date = expiry
 			.to_expiry_date(timestamp)
@@ -165,6 +231,14 @@ impl TokenizerProcessor {
 			return Err(TokenizerError::IncorrectYieldMintAddress.into());
 		}
 
+		// Check the grace period, the settlement window must end after the expiry date
+		if grace_period <= 0 {
+			return Err(TokenizerError::InvalidGracePeriod.into());
+		}
+
+		// Check the stake pool issues the underlying and reports a valid exchange rate
+		let exchange_rate = get_exchange_rate(stake_pool_account, &underlying_mint)?;
+
 		// Check token program
 		if token_program.key != &spl_token::id() {
 			return Err(ProgramError::IncorrectProgramId);
@@ -213,7 +287,7 @@ impl TokenizerProcessor {
 			msg!("Creating underlying vault account");
 			// Create underlying vault account
 			invoke_signed(
-				&spl_associated_token_account::instruction::create_associated_token_account(
+				&spl_associated_token_account::instruction::create_associated_token_account_idempotent(
 					authority.key,
 					lysergic_tokenizer_account.key,
 					&underlying_mint,
@@ -245,12 +319,33 @@ impl TokenizerProcessor {
 				underlying_vault: *underlying_vault_account.key,
 				expiry_date,
 				fixed_apy,
+				grace_period,
+				stake_pool: *stake_pool_account.key,
+				expiry_exchange_rate: 0,
+				post_expiry_yield: post_expiry_yield.clone(),
+				treasury,
+				expiry_yield_reserve: 0,
+				yield_index: exchange_rate,
+				last_update_slot: clock.slot,
+				last_update_timestamp: clock.unix_timestamp,
 			};
 
 			lysergic_tokenizer_state
 				.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
 			msg!("Lysergic tokenizer account created");
 
+			TokenizerEvent::TokenizerInitialized {
+				tokenizer: tokenizer_key,
+				authority: *authority.key,
+				underlying_mint,
+				stake_pool: *stake_pool_account.key,
+				expiry_date,
+				fixed_apy,
+				yield_index: exchange_rate,
+				timestamp,
+			}
+			.emit()?;
+
 			Ok(())
 		} else {
 			return Err(TokenizerError::TokenizerAlreadyInitialized.into());
@@ -426,16 +521,20 @@ impl TokenizerProcessor {
 			]],
 		)?;
 
+		TokenizerEvent::MintsInitialized {
+			tokenizer: tokenizer_key,
+			principal_token_mint: principal_mint,
+			yield_token_mint: yield_mint,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
 		Ok(())
 	}
 
 	fn process_initialize_tokenizer_and_mints(
 		accounts: &[AccountInfo],
-		underlying_mint: Pubkey,
-		principal_token_mint: Pubkey,
-		yield_token_mint: Pubkey,
-		expiry: Expiry,
-		fixed_apy: u64,
+		params: &InitializeTokenizerParams,
 	) -> ProgramResult {
 		let account_info_iter = &mut accounts.iter();
 		let lysergic_tokenizer_account = next_account_info(account_info_iter)?;
@@ -448,6 +547,7 @@ impl TokenizerProcessor {
 		let token_program = next_account_info(account_info_iter)?;
 		let system_program = next_account_info(account_info_iter)?;
 		let atoken_program = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
 
 		let initialize_tokenizer_accounts = [
 			lysergic_tokenizer_account.clone(),
@@ -457,6 +557,7 @@ impl TokenizerProcessor {
 			token_program.clone(),
 			system_program.clone(),
 			atoken_program.clone(),
+			stake_pool_account.clone(),
 		];
 
 		let initialize_mint_accounts = [
@@ -471,14 +572,14 @@ impl TokenizerProcessor {
 
 		Self::process_initialize_lysergic_tokenizer(
 			&initialize_tokenizer_accounts,
-			underlying_mint,
-			principal_token_mint,
-			yield_token_mint,
-			&expiry,
-			fixed_apy,
+			params,
 		)?;
 
-		Self::process_initialize_mints(&initialize_mint_accounts, underlying_mint, &expiry)?;
+		Self::process_initialize_mints(
+			&initialize_mint_accounts,
+			params.underlying_mint,
+			&params.expiry,
+		)?;
 
 		Ok(())
 	}
@@ -491,7 +592,7 @@ impl TokenizerProcessor {
 		let user_underlying_token_account = next_account_info(account_info_iter)?;
 		let token_program = next_account_info(account_info_iter)?;
 
-		let amount = spl_token::ui_amount_to_amount(amount as f64, 6);
+		let amount = ui_amount_to_amount(amount, 6)?;
 
 		let lysergic_tokenizer_state = TokenizerState::try_from_slice(
 			&lysergic_tokenizer_account.data.borrow()[..STATE_SIZE],
@@ -545,6 +646,14 @@ impl TokenizerProcessor {
 			],
 		)?;
 
+		TokenizerEvent::UnderlyingDeposited {
+			tokenizer: *lysergic_tokenizer_account.key,
+			user: *user_account.key,
+			underlying_amount: amount,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
 		Ok(())
 	}
 
@@ -555,14 +664,15 @@ impl TokenizerProcessor {
 		let user_account = next_account_info(account_info_iter)?;
 		let user_principal_token_account = next_account_info(account_info_iter)?;
 		let token_program = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
 
-		let amount = spl_token::ui_amount_to_amount(amount as f64, 6);
+		let amount = ui_amount_to_amount(amount, 6)?;
 
 		if lysergic_tokenizer_account.owner != &crate::id() {
 			return Err(TokenizerError::TokenizerNotInitialized.into());
 		}
 
-		let lysergic_tokenizer_state =
+		let mut lysergic_tokenizer_state =
 			TokenizerState::try_from_slice(&lysergic_tokenizer_account.data.borrow()[..])?;
 
 		// Check to see if the expiry date has elapsed
@@ -586,6 +696,18 @@ impl TokenizerProcessor {
 			return Err(ProgramError::IncorrectProgramId);
 		}
 
+		if stake_pool_account.key != &lysergic_tokenizer_state.stake_pool {
+			return Err(TokenizerError::IncorrectStakePoolAddress.into());
+		}
+
+		// Principal tokens are denominated in the accounting asset
+		let exchange_rate =
+			Self::update_yield_index(&mut lysergic_tokenizer_state, stake_pool_account)?;
+		let principal_amount = underlying_to_asset(amount, exchange_rate)?;
+
+		lysergic_tokenizer_state
+			.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+
 		// We may want to create a principal token account for the user if it doesn't exist
 		if user_principal_token_account.owner != token_program.key {
 			msg!("No user principal account found, creating...");
@@ -601,7 +723,7 @@ impl TokenizerProcessor {
 			}
 
 			invoke(
-				&spl_associated_token_account::instruction::create_associated_token_account(
+				&spl_associated_token_account::instruction::create_associated_token_account_idempotent(
 					user_account.key,
 					user_account.key,
 					&lysergic_tokenizer_state.principal_token_mint,
@@ -628,7 +750,7 @@ impl TokenizerProcessor {
 				user_principal_token_account.key,
 				lysergic_tokenizer_account.key,
 				&[],
-				amount,
+				principal_amount,
 			)?,
 			&[
 				principal_token_mint_account.clone(),
@@ -644,6 +766,16 @@ impl TokenizerProcessor {
 			]],
 		)?;
 
+		TokenizerEvent::PrincipalTokenized {
+			tokenizer: *lysergic_tokenizer_account.key,
+			user: *user_account.key,
+			underlying_amount: amount,
+			principal_amount,
+			exchange_rate,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
 		Ok(())
 	}
 
@@ -654,14 +786,15 @@ impl TokenizerProcessor {
 		let user_account = next_account_info(account_info_iter)?;
 		let user_yield_token_account = next_account_info(account_info_iter)?;
 		let token_program = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
 
-		let amount = spl_token::ui_amount_to_amount(amount as f64, 6);
+		let amount = ui_amount_to_amount(amount, 6)?;
 
 		if lysergic_tokenizer_account.owner != &crate::id() {
 			return Err(TokenizerError::TokenizerNotInitialized.into());
 		}
 
-		let lysergic_tokenizer_state =
+		let mut lysergic_tokenizer_state =
 			TokenizerState::try_from_slice(&lysergic_tokenizer_account.data.borrow()[..])?;
 
 		if lysergic_tokenizer_state.expiry_date < clock::Clock::get()?.unix_timestamp {
@@ -684,6 +817,18 @@ impl TokenizerProcessor {
 			return Err(ProgramError::IncorrectProgramId);
 		}
 
+		if stake_pool_account.key != &lysergic_tokenizer_state.stake_pool {
+			return Err(TokenizerError::IncorrectStakePoolAddress.into());
+		}
+
+		// Yield tokens are denominated in the accounting asset
+		let exchange_rate =
+			Self::update_yield_index(&mut lysergic_tokenizer_state, stake_pool_account)?;
+		let yield_amount = underlying_to_asset(amount, exchange_rate)?;
+
+		lysergic_tokenizer_state
+			.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+
 		// We may want to create a yield token account for the user if it doesn't exist
 		if user_yield_token_account.owner != token_program.key {
 			msg!("No user yield account found, creating...");
@@ -699,7 +844,7 @@ impl TokenizerProcessor {
 			}
 
 			invoke(
-				&spl_associated_token_account::instruction::create_associated_token_account(
+				&spl_associated_token_account::instruction::create_associated_token_account_idempotent(
 					user_account.key,
 					user_account.key,
 					&lysergic_tokenizer_state.yield_token_mint,
@@ -726,7 +871,7 @@ impl TokenizerProcessor {
 				user_yield_token_account.key,
 				lysergic_tokenizer_account.key,
 				&[],
-				amount,
+				yield_amount,
 			)?,
 			&[
 				yield_token_mint_account.clone(),
@@ -742,6 +887,16 @@ impl TokenizerProcessor {
 			]],
 		)?;
 
+		TokenizerEvent::YieldTokenized {
+			tokenizer: *lysergic_tokenizer_account.key,
+			user: *user_account.key,
+			underlying_amount: amount,
+			yield_amount,
+			exchange_rate,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
 		Ok(())
 	}
 
@@ -756,6 +911,7 @@ impl TokenizerProcessor {
 		let user_principal_token_account = next_account_info(account_info_iter)?;
 		let user_yield_token_account = next_account_info(account_info_iter)?;
 		let token_program = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
 		let system_program = next_account_info(account_info_iter)?;
 		let atoken_program = next_account_info(account_info_iter)?;
 
@@ -773,6 +929,7 @@ impl TokenizerProcessor {
 			user_account.clone(),
 			user_principal_token_account.clone(),
 			token_program.clone(),
+			stake_pool_account.clone(),
 			system_program.clone(),
 			atoken_program.clone(),
 		];
@@ -783,6 +940,7 @@ impl TokenizerProcessor {
 			user_account.clone(),
 			user_yield_token_account.clone(),
 			token_program.clone(),
+			stake_pool_account.clone(),
 			system_program.clone(),
 			atoken_program.clone(),
 		];
@@ -807,6 +965,7 @@ impl TokenizerProcessor {
 		let user_principal_token_account = next_account_info(account_info_iter)?;
 		let user_yield_token_account = next_account_info(account_info_iter)?;
 		let token_program = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
 
 		let redeem_principal_accounts = [
 			lysergic_tokenizer_account.clone(),
@@ -817,6 +976,7 @@ impl TokenizerProcessor {
 			user_underlying_token_account.clone(),
 			user_principal_token_account.clone(),
 			token_program.clone(),
+			stake_pool_account.clone(),
 		];
 
 		let claim_yield_accounts = [
@@ -828,6 +988,8 @@ impl TokenizerProcessor {
 			user_underlying_token_account.clone(),
 			user_yield_token_account.clone(),
 			token_program.clone(),
+			principal_token_mint_account.clone(),
+			stake_pool_account.clone(),
 		];
 
 		Self::process_redeem_principal(
@@ -859,14 +1021,15 @@ impl TokenizerProcessor {
 		let user_underlying_token_account = next_account_info(account_info_iter)?;
 		let user_principal_token_account = next_account_info(account_info_iter)?;
 		let token_program = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
 
-		let amount = spl_token::ui_amount_to_amount(amount as f64, 6);
+		let amount = ui_amount_to_amount(amount, 6)?;
 
 		if lysergic_tokenizer_account.owner != &crate::id() {
 			return Err(TokenizerError::TokenizerNotInitialized.into());
 		}
 
-		let lysergic_tokenizer_state =
+		let mut lysergic_tokenizer_state =
 			TokenizerState::try_from_slice(&lysergic_tokenizer_account.data.borrow()[..])?;
 
 		if let RedemptionMode::Mature = redemption_mode {
@@ -875,6 +1038,10 @@ impl TokenizerProcessor {
 			}
 		}
 
+		if stake_pool_account.key != &lysergic_tokenizer_state.stake_pool {
+			return Err(TokenizerError::IncorrectStakePoolAddress.into());
+		}
+
 		if underlying_vault_account.owner != &spl_token::id() {
 			return Err(TokenizerError::IncorrectVaultAddress.into());
 		}
@@ -924,27 +1091,58 @@ impl TokenizerProcessor {
 			return Err(TokenizerError::InsufficientFunds.into());
 		}
 
+		// Mature principal settles for one unit of the accounting asset at expiry, otherwise
+		// the principal is redeemed alongside its yield at the current exchange rate
+		let exchange_rate =
+			if lysergic_tokenizer_state.expiry_date < clock::Clock::get()?.unix_timestamp {
+				Self::settle_expiry(
+					lysergic_tokenizer_account,
+					&mut lysergic_tokenizer_state,
+					underlying_vault_account,
+					principal_token_mint_account,
+					stake_pool_account,
+				)?;
+				Self::mature_principal_exchange_rate(
+					&mut lysergic_tokenizer_state,
+					stake_pool_account,
+				)?
+			} else {
+				Self::update_yield_index(&mut lysergic_tokenizer_state, stake_pool_account)?
+			};
+		let underlying_amount = asset_to_underlying(amount, exchange_rate)?;
+
+		lysergic_tokenizer_state
+			.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+
 		// In the rather unlikely event that a user does not have an underlying token account;
 		// create one for them
 		if user_underlying_token_account.owner != token_program.key {
 			let system_program = next_account_info(account_info_iter)?;
+			let atoken_program = next_account_info(account_info_iter)?;
+
 			if system_program.key != &system_program::id() {
 				return Err(ProgramError::IncorrectProgramId);
 			}
 
+			if atoken_program.key != &spl_associated_token_account::id() {
+				return Err(ProgramError::IncorrectProgramId);
+			}
+
 			invoke(
-				&spl_associated_token_account::instruction::create_associated_token_account(
+				&spl_associated_token_account::instruction::create_associated_token_account_idempotent(
 					user_account.key,
-					user_underlying_token_account.key,
-					&lysergic_tokenizer_state.underlying_mint,
 					user_account.key,
+					&lysergic_tokenizer_state.underlying_mint,
+					token_program.key,
 				),
 				&[
+					user_account.clone(),
 					user_underlying_token_account.clone(),
 					user_account.clone(),
 					underlying_mint_account.clone(),
-					token_program.clone(),
 					system_program.clone(),
+					token_program.clone(),
+					atoken_program.clone(),
 				],
 			)?;
 		}
@@ -973,7 +1171,7 @@ impl TokenizerProcessor {
 				user_underlying_token_account.key,
 				lysergic_tokenizer_account.key,
 				&[],
-				amount,
+				underlying_amount,
 			)?,
 			&[
 				underlying_vault_account.clone(),
@@ -988,9 +1186,115 @@ impl TokenizerProcessor {
 			]],
 		)?;
 
+		TokenizerEvent::PrincipalRedeemed {
+			tokenizer: *lysergic_tokenizer_account.key,
+			user: *user_account.key,
+			principal_amount: amount,
+			underlying_amount,
+			exchange_rate,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
+		Ok(())
+	}
+
+	// The exchange rate and the underlying owed to the yield token holders are frozen on the
+	// first interaction after the expiry date, yield tokens stop accruing from this point and
+	// every principal token holder settles against the same rate regardless of when they redeem
+	fn settle_expiry(
+		lysergic_tokenizer_account: &AccountInfo,
+		lysergic_tokenizer_state: &mut TokenizerState,
+		underlying_vault_account: &AccountInfo,
+		principal_token_mint_account: &AccountInfo,
+		stake_pool_account: &AccountInfo,
+	) -> ProgramResult {
+		if lysergic_tokenizer_state.expiry_exchange_rate != 0 {
+			return Ok(());
+		}
+
+		let expiry_exchange_rate =
+			Self::update_yield_index(lysergic_tokenizer_state, stake_pool_account)?;
+		let principal_supply =
+			spl_token::state::Mint::unpack_from_slice(&principal_token_mint_account.data.borrow())?
+				.supply;
+		let vault_balance =
+			spl_token::state::Account::unpack_from_slice(&underlying_vault_account.data.borrow())?
+				.amount;
+
+		lysergic_tokenizer_state.expiry_exchange_rate = expiry_exchange_rate;
+		lysergic_tokenizer_state.expiry_yield_reserve = vault_balance
+			.saturating_sub(asset_to_underlying_ceil(principal_supply, expiry_exchange_rate)?);
+
+		msg!(
+			"Expiry settled, exchange rate: {}, yield reserve: {}",
+			lysergic_tokenizer_state.expiry_exchange_rate,
+			lysergic_tokenizer_state.expiry_yield_reserve
+		);
+
+		lysergic_tokenizer_state
+			.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+
+		TokenizerEvent::ExpirySettled {
+			tokenizer: *lysergic_tokenizer_account.key,
+			expiry_exchange_rate: lysergic_tokenizer_state.expiry_exchange_rate,
+			expiry_yield_reserve: lysergic_tokenizer_state.expiry_yield_reserve,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
 		Ok(())
 	}
 
+	// Returns the exchange rate mature principal tokens are redeemed at, this must only be
+	// called once the expiry has been settled
+	fn mature_principal_exchange_rate(
+		lysergic_tokenizer_state: &mut TokenizerState,
+		stake_pool_account: &AccountInfo,
+	) -> Result<u128, ProgramError> {
+		match lysergic_tokenizer_state.post_expiry_yield {
+			PostExpiryYieldRecipient::PrincipalHolders => {
+				Ok(lysergic_tokenizer_state.expiry_exchange_rate)
+			}
+			// Principal is redeemed for one unit of the accounting asset at the current rate,
+			// leaving the yield earned since expiry in the vault for the treasury. The rate is
+			// never taken below the expiry rate so principal never receives more underlying
+			// than it was owed at expiry
+			PostExpiryYieldRecipient::Treasury => {
+				Ok(
+					Self::update_yield_index(lysergic_tokenizer_state, stake_pool_account)?
+						.max(lysergic_tokenizer_state.expiry_exchange_rate),
+				)
+			}
+		}
+	}
+
+	// Reads the exchange rate through the yield index, the rate reported by the stake pool is
+	// only accepted if it moved within the allowed bounds since the index was last updated.
+	// The caller is responsible for serializing the updated state
+	fn update_yield_index(
+		lysergic_tokenizer_state: &mut TokenizerState,
+		stake_pool_account: &AccountInfo,
+	) -> Result<u128, ProgramError> {
+		let clock = Clock::get()?;
+		let exchange_rate =
+			get_exchange_rate(stake_pool_account, &lysergic_tokenizer_state.underlying_mint)?;
+
+		check_rate_change(
+			lysergic_tokenizer_state.yield_index,
+			exchange_rate,
+			clock
+				.unix_timestamp
+				.saturating_sub(lysergic_tokenizer_state.last_update_timestamp),
+		)?;
+
+		lysergic_tokenizer_state.yield_index = exchange_rate;
+		lysergic_tokenizer_state.last_update_slot = clock.slot;
+		lysergic_tokenizer_state.last_update_timestamp = clock.unix_timestamp;
+
+		Ok(exchange_rate)
+	}
+
 	fn process_claim_yield(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
 		msg!("Claiming yield...");
 		let account_info_iter = &mut accounts.iter();
@@ -1002,14 +1306,16 @@ impl TokenizerProcessor {
 		let user_underlying_token_account = next_account_info(account_info_iter)?;
 		let user_yield_token_account = next_account_info(account_info_iter)?;
 		let token_program = next_account_info(account_info_iter)?;
+		let principal_token_mint_account = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
 
-		let amount = spl_token::ui_amount_to_amount(amount as f64, 6);
+		let amount = ui_amount_to_amount(amount, 6)?;
 
 		if lysergic_tokenizer_account.owner != &crate::id() {
 			return Err(TokenizerError::TokenizerNotInitialized.into());
 		}
 
-		let lysergic_tokenizer_state =
+		let mut lysergic_tokenizer_state =
 			TokenizerState::try_from_slice(&lysergic_tokenizer_account.data.borrow()[..])?;
 
 		if underlying_vault_account.owner != &spl_token::id() {
@@ -1020,10 +1326,22 @@ impl TokenizerProcessor {
 			return Err(TokenizerError::IncorrectVaultAddress.into());
 		}
 
+		if underlying_mint_account.key != &lysergic_tokenizer_state.underlying_mint {
+			return Err(TokenizerError::IncorrectUnderlyingMintAddress.into());
+		}
+
 		if yield_token_mint_account.key != &lysergic_tokenizer_state.yield_token_mint {
 			return Err(TokenizerError::IncorrectYieldMintAddress.into());
 		}
 
+		if principal_token_mint_account.key != &lysergic_tokenizer_state.principal_token_mint {
+			return Err(TokenizerError::IncorrectPrincipalMintAddress.into());
+		}
+
+		if stake_pool_account.key != &lysergic_tokenizer_state.stake_pool {
+			return Err(TokenizerError::IncorrectStakePoolAddress.into());
+		}
+
 		if !user_account.is_signer {
 			return Err(ProgramError::MissingRequiredSignature);
 		}
@@ -1055,28 +1373,86 @@ impl TokenizerProcessor {
 			return Err(TokenizerError::InsufficientFunds.into());
 		}
 
+		let yield_supply =
+			spl_token::state::Mint::unpack_from_slice(&yield_token_mint_account.data.borrow())?
+				.supply;
+
+		// Yield stops accruing at expiry, afterwards yield tokens share the frozen reserve,
+		// before expiry they share the underlying not required to back the principal tokens
+		let underlying_amount =
+			if lysergic_tokenizer_state.expiry_date < clock::Clock::get()?.unix_timestamp {
+				Self::settle_expiry(
+					lysergic_tokenizer_account,
+					&mut lysergic_tokenizer_state,
+					underlying_vault_account,
+					principal_token_mint_account,
+					stake_pool_account,
+				)?;
+
+				let underlying_amount = pro_rata_share(
+					amount,
+					lysergic_tokenizer_state.expiry_yield_reserve,
+					yield_supply,
+				)?;
+
+				lysergic_tokenizer_state.expiry_yield_reserve -= underlying_amount;
+				lysergic_tokenizer_state
+					.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+
+				underlying_amount
+			} else {
+				let principal_supply = spl_token::state::Mint::unpack_from_slice(
+					&principal_token_mint_account.data.borrow(),
+				)?
+				.supply;
+				let vault_balance = spl_token::state::Account::unpack_from_slice(
+					&underlying_vault_account.data.borrow(),
+				)?
+				.amount;
+				let principal_backing = asset_to_underlying_ceil(
+					principal_supply,
+					Self::update_yield_index(&mut lysergic_tokenizer_state, stake_pool_account)?,
+				)?;
+
+				lysergic_tokenizer_state
+					.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+
+				pro_rata_share(
+					amount,
+					vault_balance.saturating_sub(principal_backing),
+					yield_supply,
+				)?
+			};
+
 		// In the rather unlikely event that a user does not have an underlying token account;
 		// create one for them
 		if user_underlying_token_account.owner != token_program.key {
 			let system_program = next_account_info(account_info_iter)?;
+			let atoken_program = next_account_info(account_info_iter)?;
 
 			if system_program.key != &system_program::id() {
 				return Err(ProgramError::IncorrectProgramId);
 			}
 
+			if atoken_program.key != &spl_associated_token_account::id() {
+				return Err(ProgramError::IncorrectProgramId);
+			}
+
 			invoke(
-				&spl_associated_token_account::instruction::create_associated_token_account(
+				&spl_associated_token_account::instruction::create_associated_token_account_idempotent(
 					user_account.key,
-					user_underlying_token_account.key,
-					&lysergic_tokenizer_state.underlying_mint,
 					user_account.key,
+					&lysergic_tokenizer_state.underlying_mint,
+					token_program.key,
 				),
 				&[
+					user_account.clone(),
 					user_underlying_token_account.clone(),
 					user_account.clone(),
 					underlying_mint_account.clone(),
-					token_program.clone(),
 					system_program.clone(),
+					token_program.clone(),
+					atoken_program.clone(),
 				],
 			)?;
 		}
@@ -1105,7 +1481,7 @@ impl TokenizerProcessor {
 				user_underlying_token_account.key,
 				lysergic_tokenizer_account.key,
 				&[],
-				amount,
+				underlying_amount,
 			)?,
 			&[
 				underlying_vault_account.clone(),
@@ -1120,6 +1496,15 @@ impl TokenizerProcessor {
 			]],
 		)?;
 
+		TokenizerEvent::YieldClaimed {
+			tokenizer: *lysergic_tokenizer_account.key,
+			user: *user_account.key,
+			yield_amount: amount,
+			underlying_amount,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
 		Ok(())
 	}
 
@@ -1150,8 +1535,8 @@ impl TokenizerProcessor {
 			system_program.clone(),
 		];
 
-		Self::process_terminate_mints(&terminate_tokenizer_accounts)?;
-		Self::process_terminate_lysergic_tokenizer(&terminate_mint_accounts)?;
+		Self::process_terminate_mints(&terminate_mint_accounts)?;
+		Self::process_terminate_lysergic_tokenizer(&terminate_tokenizer_accounts)?;
 
 		Ok(())
 	}
@@ -1202,24 +1587,22 @@ impl TokenizerProcessor {
 			return Err(ProgramError::IncorrectProgramId);
 		}
 
+		msg!("Closing underlying vault account");
+		// Close the underlying vault and return its rent to the authority, this must happen
+		// before the tokenizer account is closed as the tokenizer is the owner of the vault
 		invoke_signed(
-			&system_instruction::transfer(
-				lysergic_tokenizer_account.key,
+			&spl_token::instruction::close_account(
+				token_program.key,
+				underlying_vault_account.key,
 				authority.key,
-				match lysergic_tokenizer_account
-					.lamports
-					.as_ref()
-					.try_borrow()
-					.as_deref()
-				{
-					Ok(lamports) => **lamports,
-					Err(_) => return Err(ProgramError::InvalidAccountData),
-				},
-			),
+				lysergic_tokenizer_account.key,
+				&[],
+			)?,
 			&[
-				lysergic_tokenizer_account.clone(),
+				underlying_vault_account.clone(),
 				authority.clone(),
-				system_program.clone(),
+				lysergic_tokenizer_account.clone(),
+				token_program.clone(),
 			],
 			&[&[
 				b"tokenizer",
@@ -1229,10 +1612,26 @@ impl TokenizerProcessor {
 			]],
 		)?;
 
+		// The tokenizer account is owned by this program, which moves its rent to the authority
+		// directly as the system program only debits accounts it owns
+		let lamports = lysergic_tokenizer_account.lamports();
+		**authority.lamports.borrow_mut() = authority
+			.lamports()
+			.checked_add(lamports)
+			.ok_or(TokenizerError::CalculationFailure)?;
+		**lysergic_tokenizer_account.lamports.borrow_mut() = 0;
+
 		// Terminate the Lysergic tokenizer account
 		lysergic_tokenizer_account.assign(&system_program::id());
 		lysergic_tokenizer_account.realloc(0, false)?;
 
+		TokenizerEvent::TokenizerTerminated {
+			tokenizer: *lysergic_tokenizer_account.key,
+			authority: *authority.key,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
 		Ok(())
 	}
 
@@ -1280,17 +1679,273 @@ impl TokenizerProcessor {
 			return Err(ProgramError::IncorrectProgramId);
 		}
 
+		// Mints of the spl-token program cannot be closed, and outstanding principal and yield
+		// tokens are burnt against them when redeemed from the claims escrow, so the tokenizer
+		// revokes its authority to mint instead
+		for mint_account in [principal_token_mint_account, yield_token_mint_account] {
+			invoke_signed(
+				&spl_token::instruction::set_authority(
+					token_program.key,
+					mint_account.key,
+					None,
+					spl_token::instruction::AuthorityType::MintTokens,
+					lysergic_tokenizer_account.key,
+					&[],
+				)?,
+				&[
+					mint_account.clone(),
+					lysergic_tokenizer_account.clone(),
+					token_program.clone(),
+				],
+				&[&[
+					b"tokenizer",
+					&lysergic_tokenizer_state.underlying_mint.to_bytes()[..],
+					&lysergic_tokenizer_state.expiry_date.to_le_bytes(),
+					&[lysergic_tokenizer_state.bump],
+				]],
+			)?;
+		}
+
+		TokenizerEvent::MintsTerminated {
+			tokenizer: *lysergic_tokenizer_account.key,
+			authority: *authority.key,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
+		Ok(())
+	}
+
+	fn process_sweep_to_escrow(accounts: &[AccountInfo]) -> ProgramResult {
+		let account_info_iter = &mut accounts.iter();
+		let lysergic_tokenizer_account = next_account_info(account_info_iter)?;
+		let authority = next_account_info(account_info_iter)?;
+		let underlying_vault_account = next_account_info(account_info_iter)?;
+		let escrow_account = next_account_info(account_info_iter)?;
+		let escrow_vault_account = next_account_info(account_info_iter)?;
+		let underlying_mint_account = next_account_info(account_info_iter)?;
+		let principal_token_mint_account = next_account_info(account_info_iter)?;
+		let token_program = next_account_info(account_info_iter)?;
+		let system_program = next_account_info(account_info_iter)?;
+		let atoken_program = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
+		let treasury_token_account = next_account_info(account_info_iter)?;
+
+		if lysergic_tokenizer_account.owner != &crate::id() {
+			return Err(TokenizerError::TokenizerNotInitialized.into());
+		}
+
+		if !authority.is_signer {
+			return Err(ProgramError::MissingRequiredSignature);
+		}
+
+		let mut lysergic_tokenizer_state =
+			TokenizerState::try_from_slice(&lysergic_tokenizer_account.data.borrow()[..])?;
+
+		if authority.key != &lysergic_tokenizer_state.authority {
+			return Err(TokenizerError::Unauthorised.into());
+		}
+
+		// The settlement window gives holders time to redeem against the tokenizer before
+		// the remaining underlying is moved into the escrow
+		if lysergic_tokenizer_state
+			.expiry_date
+			.saturating_add(lysergic_tokenizer_state.grace_period)
+			>= clock::Clock::get()?.unix_timestamp
+		{
+			return Err(TokenizerError::SettlementWindowNotElapsed.into());
+		}
+
+		if underlying_vault_account.key != &lysergic_tokenizer_state.underlying_vault {
+			return Err(TokenizerError::IncorrectVaultAddress.into());
+		}
+
+		let (escrow_key, escrow_bump) = get_escrow_address(lysergic_tokenizer_account.key);
+
+		if escrow_account.key != &escrow_key {
+			return Err(TokenizerError::IncorrectEscrowAddress.into());
+		}
+
+		if escrow_vault_account.key
+			!= &spl_associated_token_account::get_associated_token_address(
+				&escrow_key,
+				&lysergic_tokenizer_state.underlying_mint,
+			) {
+			return Err(TokenizerError::IncorrectVaultAddress.into());
+		}
+
+		if underlying_mint_account.key != &lysergic_tokenizer_state.underlying_mint {
+			return Err(TokenizerError::IncorrectUnderlyingMintAddress.into());
+		}
+
+		if principal_token_mint_account.key != &lysergic_tokenizer_state.principal_token_mint {
+			return Err(TokenizerError::IncorrectPrincipalMintAddress.into());
+		}
+
+		if token_program.key != &spl_token::id() {
+			return Err(ProgramError::IncorrectProgramId);
+		}
+
+		if system_program.key != &system_program::id() {
+			return Err(ProgramError::IncorrectProgramId);
+		}
+
+		if atoken_program.key != &spl_associated_token_account::id() {
+			return Err(ProgramError::IncorrectProgramId);
+		}
+
+		if stake_pool_account.key != &lysergic_tokenizer_state.stake_pool {
+			return Err(TokenizerError::IncorrectStakePoolAddress.into());
+		}
+
+		if treasury_token_account.key
+			!= &spl_associated_token_account::get_associated_token_address(
+				&lysergic_tokenizer_state.treasury,
+				&lysergic_tokenizer_state.underlying_mint,
+			) {
+			return Err(TokenizerError::IncorrectTreasuryAddress.into());
+		}
+
+		Self::settle_expiry(
+			lysergic_tokenizer_account,
+			&mut lysergic_tokenizer_state,
+			underlying_vault_account,
+			principal_token_mint_account,
+			stake_pool_account,
+		)?;
+
+		// The escrow may already exist if underlying was sent to the vault after a previous sweep
+		let escrow_initialized = escrow_account.owner == &crate::id();
+
+		// Principal moved into the escrow settles at the same rate as principal redeemed from
+		// the tokenizer, so the yield owed to the treasury is swept first. Once the escrow
+		// exists it backs every principal token and the vault only holds underlying sent after
+		// the previous sweep
+		let principal_exchange_rate = Self::mature_principal_exchange_rate(
+			&mut lysergic_tokenizer_state,
+			stake_pool_account,
+		)?;
+
+		if lysergic_tokenizer_state.post_expiry_yield == PostExpiryYieldRecipient::Treasury {
+			let principal_backing = if escrow_initialized {
+				0
+			} else {
+				asset_to_underlying_ceil(
+					spl_token::state::Mint::unpack_from_slice(
+						&principal_token_mint_account.data.borrow(),
+					)?
+					.supply,
+					principal_exchange_rate,
+				)?
+			};
+
+			Self::sweep_post_expiry_yield(
+				lysergic_tokenizer_account,
+				&lysergic_tokenizer_state,
+				underlying_vault_account,
+				treasury_token_account,
+				token_program,
+				principal_backing,
+			)?;
+		}
+
+		let mut escrow_state = if escrow_initialized {
+			ClaimsEscrowState::try_from_slice(&escrow_account.data.borrow()[..])?
+		} else {
+			let rent = rent::Rent::get()?;
+			let required_lamports = rent
+				.minimum_balance(ESCROW_STATE_SIZE)
+				.max(1)
+				.saturating_sub(escrow_account.lamports());
+
+			msg!("Creating claims escrow account");
+			invoke_signed(
+				&system_instruction::create_account(
+					authority.key,
+					&escrow_key,
+					required_lamports,
+					ESCROW_STATE_SIZE as u64,
+					&crate::id(),
+				),
+				&[
+					authority.clone(),
+					escrow_account.clone(),
+					system_program.clone(),
+				],
+				&[&[
+					b"escrow",
+					&lysergic_tokenizer_account.key.to_bytes()[..],
+					&[escrow_bump],
+				]],
+			)?;
+
+			msg!("Creating claims escrow vault account");
+			invoke(
+				&spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+					authority.key,
+					escrow_account.key,
+					&lysergic_tokenizer_state.underlying_mint,
+					token_program.key,
+				),
+				&[
+					authority.clone(),
+					escrow_vault_account.clone(),
+					escrow_account.clone(),
+					underlying_mint_account.clone(),
+					system_program.clone(),
+					token_program.clone(),
+					atoken_program.clone(),
+				],
+			)?;
+
+			ClaimsEscrowState {
+				bump: escrow_bump,
+				tokenizer: *lysergic_tokenizer_account.key,
+				principal_token_mint: lysergic_tokenizer_state.principal_token_mint,
+				yield_token_mint: lysergic_tokenizer_state.yield_token_mint,
+				underlying_mint: lysergic_tokenizer_state.underlying_mint,
+				escrow_vault: *escrow_vault_account.key,
+				expiry_date: lysergic_tokenizer_state.expiry_date,
+				expiry_exchange_rate: principal_exchange_rate,
+				expiry_yield_reserve: 0,
+			}
+		};
+
+		let amount =
+			spl_token::state::Account::unpack_from_slice(&underlying_vault_account.data.borrow())?
+				.amount;
+
+		// The yield reserve is now held by the escrow. The first sweep backs every principal
+		// token, so any underlying swept after it is owed to the yield token holders
+		let reserve_increase = if escrow_initialized {
+			amount
+		} else {
+			lysergic_tokenizer_state.expiry_yield_reserve
+		};
+
+		escrow_state.expiry_yield_reserve = escrow_state
+			.expiry_yield_reserve
+			.checked_add(reserve_increase)
+			.ok_or(TokenizerError::CalculationFailure)?;
+		lysergic_tokenizer_state.expiry_yield_reserve = 0;
+
+		escrow_state.serialize(&mut &mut escrow_account.data.borrow_mut()[..])?;
+		lysergic_tokenizer_state
+			.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+
+		msg!("Sweeping underlying to claims escrow...");
 		invoke_signed(
-			&spl_token::instruction::close_account(
+			&spl_token::instruction::transfer(
 				token_program.key,
-				principal_token_mint_account.key,
-				authority.key,
+				underlying_vault_account.key,
+				escrow_vault_account.key,
 				lysergic_tokenizer_account.key,
 				&[],
+				amount,
 			)?,
 			&[
-				principal_token_mint_account.clone(),
-				authority.clone(),
+				underlying_vault_account.clone(),
+				escrow_vault_account.clone(),
 				lysergic_tokenizer_account.clone(),
 				token_program.clone(),
 			],
@@ -1302,46 +1957,287 @@ impl TokenizerProcessor {
 			]],
 		)?;
 
+		TokenizerEvent::SweptToEscrow {
+			tokenizer: *lysergic_tokenizer_account.key,
+			escrow: escrow_key,
+			underlying_amount: amount,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
+		Ok(())
+	}
+
+	fn process_redeem_from_escrow(
+		accounts: &[AccountInfo],
+		redemption: EscrowRedemption,
+		amount: u64,
+	) -> ProgramResult {
+		msg!("Redeeming from claims escrow...");
+		let account_info_iter = &mut accounts.iter();
+		let escrow_account = next_account_info(account_info_iter)?;
+		let escrow_vault_account = next_account_info(account_info_iter)?;
+		let token_mint_account = next_account_info(account_info_iter)?;
+		let user_account = next_account_info(account_info_iter)?;
+		let user_underlying_token_account = next_account_info(account_info_iter)?;
+		let user_token_account = next_account_info(account_info_iter)?;
+		let token_program = next_account_info(account_info_iter)?;
+
+		let amount = ui_amount_to_amount(amount, 6)?;
+
+		if escrow_account.owner != &crate::id() {
+			return Err(TokenizerError::EscrowNotInitialized.into());
+		}
+
+		let mut escrow_state = ClaimsEscrowState::try_from_slice(&escrow_account.data.borrow()[..])?;
+
+		if escrow_account.key != &get_escrow_address(&escrow_state.tokenizer).0 {
+			return Err(TokenizerError::IncorrectEscrowAddress.into());
+		}
+
+		if escrow_vault_account.key != &escrow_state.escrow_vault {
+			return Err(TokenizerError::IncorrectVaultAddress.into());
+		}
+
+		match redemption {
+			EscrowRedemption::Principal => {
+				if token_mint_account.key != &escrow_state.principal_token_mint {
+					return Err(TokenizerError::IncorrectPrincipalMintAddress.into());
+				}
+			}
+			EscrowRedemption::Yield => {
+				if token_mint_account.key != &escrow_state.yield_token_mint {
+					return Err(TokenizerError::IncorrectYieldMintAddress.into());
+				}
+			}
+		}
+
+		if !user_account.is_signer {
+			return Err(ProgramError::MissingRequiredSignature);
+		}
+
+		if user_underlying_token_account.key
+			!= &spl_associated_token_account::get_associated_token_address(
+				user_account.key,
+				&escrow_state.underlying_mint,
+			) {
+			return Err(TokenizerError::InvalidUserAccount.into());
+		}
+
+		if user_token_account.key
+			!= &spl_associated_token_account::get_associated_token_address(
+				user_account.key,
+				token_mint_account.key,
+			) {
+			return Err(TokenizerError::InvalidUserAccount.into());
+		}
+
+		if token_program.key != &spl_token::id() {
+			return Err(ProgramError::IncorrectProgramId);
+		}
+
+		// Check if the user has enough tokens to redeem
+		if spl_token::state::Account::unpack_from_slice(&user_token_account.data.borrow())?.amount
+			< amount
+		{
+			return Err(TokenizerError::InsufficientFunds.into());
+		}
+
+		let underlying_amount = match redemption {
+			EscrowRedemption::Principal => {
+				asset_to_underlying(amount, escrow_state.expiry_exchange_rate)?
+			}
+			EscrowRedemption::Yield => {
+				let yield_supply =
+					spl_token::state::Mint::unpack_from_slice(&token_mint_account.data.borrow())?
+						.supply;
+				let underlying_amount =
+					pro_rata_share(amount, escrow_state.expiry_yield_reserve, yield_supply)?;
+
+				escrow_state.expiry_yield_reserve -= underlying_amount;
+				escrow_state.serialize(&mut &mut escrow_account.data.borrow_mut()[..])?;
+
+				underlying_amount
+			}
+		};
+
+		invoke(
+			&spl_token::instruction::burn(
+				token_program.key,
+				user_token_account.key,
+				token_mint_account.key,
+				user_account.key,
+				&[],
+				amount,
+			)?,
+			&[
+				user_token_account.clone(),
+				token_mint_account.clone(),
+				user_account.clone(),
+				token_program.clone(),
+			],
+		)?;
+
 		invoke_signed(
-			&spl_token::instruction::close_account(
+			&spl_token::instruction::transfer(
 				token_program.key,
-				yield_token_mint_account.key,
-				authority.key,
-				lysergic_tokenizer_account.key,
+				escrow_vault_account.key,
+				user_underlying_token_account.key,
+				escrow_account.key,
 				&[],
+				underlying_amount,
 			)?,
 			&[
-				yield_token_mint_account.clone(),
-				authority.clone(),
-				lysergic_tokenizer_account.clone(),
+				escrow_vault_account.clone(),
+				user_underlying_token_account.clone(),
+				escrow_account.clone(),
 				token_program.clone(),
 			],
 			&[&[
-				b"tokenizer",
-				&lysergic_tokenizer_state.underlying_mint.to_bytes()[..],
-				&lysergic_tokenizer_state.expiry_date.to_le_bytes(),
-				&[lysergic_tokenizer_state.bump],
+				b"escrow",
+				&escrow_state.tokenizer.to_bytes()[..],
+				&[escrow_state.bump],
 			]],
 		)?;
 
+		let timestamp = Clock::get()?.unix_timestamp;
+		match redemption {
+			EscrowRedemption::Principal => TokenizerEvent::EscrowPrincipalRedeemed {
+				tokenizer: escrow_state.tokenizer,
+				user: *user_account.key,
+				principal_amount: amount,
+				underlying_amount,
+				timestamp,
+			},
+			EscrowRedemption::Yield => TokenizerEvent::EscrowYieldClaimed {
+				tokenizer: escrow_state.tokenizer,
+				user: *user_account.key,
+				yield_amount: amount,
+				underlying_amount,
+				timestamp,
+			},
+		}
+		.emit()?;
+
+		Ok(())
+	}
+
+	fn process_settle(accounts: &[AccountInfo]) -> ProgramResult {
+		let account_info_iter = &mut accounts.iter();
+		let lysergic_tokenizer_account = next_account_info(account_info_iter)?;
+		let underlying_vault_account = next_account_info(account_info_iter)?;
+		let principal_token_mint_account = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
+		let treasury_token_account = next_account_info(account_info_iter)?;
+		let token_program = next_account_info(account_info_iter)?;
+
+		if lysergic_tokenizer_account.owner != &crate::id() {
+			return Err(TokenizerError::TokenizerNotInitialized.into());
+		}
+
+		let mut lysergic_tokenizer_state =
+			TokenizerState::try_from_slice(&lysergic_tokenizer_account.data.borrow()[..])?;
+
+		if lysergic_tokenizer_state.expiry_date >= clock::Clock::get()?.unix_timestamp {
+			return Err(TokenizerError::ExpiryDateNotElapsed.into());
+		}
+
+		if underlying_vault_account.key != &lysergic_tokenizer_state.underlying_vault {
+			return Err(TokenizerError::IncorrectVaultAddress.into());
+		}
+
+		if principal_token_mint_account.key != &lysergic_tokenizer_state.principal_token_mint {
+			return Err(TokenizerError::IncorrectPrincipalMintAddress.into());
+		}
+
+		if stake_pool_account.key != &lysergic_tokenizer_state.stake_pool {
+			return Err(TokenizerError::IncorrectStakePoolAddress.into());
+		}
+
+		if treasury_token_account.key
+			!= &spl_associated_token_account::get_associated_token_address(
+				&lysergic_tokenizer_state.treasury,
+				&lysergic_tokenizer_state.underlying_mint,
+			) {
+			return Err(TokenizerError::IncorrectTreasuryAddress.into());
+		}
+
+		if token_program.key != &spl_token::id() {
+			return Err(ProgramError::IncorrectProgramId);
+		}
+
+		Self::settle_expiry(
+			lysergic_tokenizer_account,
+			&mut lysergic_tokenizer_state,
+			underlying_vault_account,
+			principal_token_mint_account,
+			stake_pool_account,
+		)?;
+
+		// Yield earned after expiry stays in the vault for the principal token holders
+		if lysergic_tokenizer_state.post_expiry_yield == PostExpiryYieldRecipient::PrincipalHolders
+		{
+			return Ok(());
+		}
+
+		let principal_supply =
+			spl_token::state::Mint::unpack_from_slice(&principal_token_mint_account.data.borrow())?
+				.supply;
+
+		let principal_backing = asset_to_underlying_ceil(
+			principal_supply,
+			Self::mature_principal_exchange_rate(&mut lysergic_tokenizer_state, stake_pool_account)?,
+		)?;
+
+		lysergic_tokenizer_state
+			.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+
+		Self::sweep_post_expiry_yield(
+			lysergic_tokenizer_account,
+			&lysergic_tokenizer_state,
+			underlying_vault_account,
+			treasury_token_account,
+			token_program,
+			principal_backing,
+		)
+	}
+
+	// Sweeps the underlying neither backing the principal tokens nor held in the yield reserve
+	// to the treasury
+	fn sweep_post_expiry_yield<'a>(
+		lysergic_tokenizer_account: &AccountInfo<'a>,
+		lysergic_tokenizer_state: &TokenizerState,
+		underlying_vault_account: &AccountInfo<'a>,
+		treasury_token_account: &AccountInfo<'a>,
+		token_program: &AccountInfo<'a>,
+		principal_backing: u64,
+	) -> ProgramResult {
+		let vault_balance =
+			spl_token::state::Account::unpack_from_slice(&underlying_vault_account.data.borrow())?
+				.amount;
+		let post_expiry_yield = vault_balance
+			.saturating_sub(principal_backing)
+			.saturating_sub(lysergic_tokenizer_state.expiry_yield_reserve);
+
+		if post_expiry_yield == 0 {
+			return Ok(());
+		}
+
+		msg!("Sweeping post expiry yield to treasury...");
 		invoke_signed(
-			&system_instruction::transfer(
+			&spl_token::instruction::transfer(
+				token_program.key,
+				underlying_vault_account.key,
+				treasury_token_account.key,
 				lysergic_tokenizer_account.key,
-				authority.key,
-				match lysergic_tokenizer_account
-					.lamports
-					.as_ref()
-					.try_borrow()
-					.as_deref()
-				{
-					Ok(lamports) => **lamports,
-					Err(_) => return Err(ProgramError::InvalidAccountData),
-				},
-			),
+				&[],
+				post_expiry_yield,
+			)?,
 			&[
+				underlying_vault_account.clone(),
+				treasury_token_account.clone(),
 				lysergic_tokenizer_account.clone(),
-				authority.clone(),
-				system_program.clone(),
+				token_program.clone(),
 			],
 			&[&[
 				b"tokenizer",
@@ -1351,6 +2247,105 @@ impl TokenizerProcessor {
 			]],
 		)?;
 
+		TokenizerEvent::PostExpiryYieldSwept {
+			tokenizer: *lysergic_tokenizer_account.key,
+			treasury_token_account: *treasury_token_account.key,
+			underlying_amount: post_expiry_yield,
+			timestamp: Clock::get()?.unix_timestamp,
+		}
+		.emit()?;
+
+		Ok(())
+	}
+
+	fn process_checkpoint(accounts: &[AccountInfo]) -> ProgramResult {
+		let account_info_iter = &mut accounts.iter();
+		let lysergic_tokenizer_account = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
+
+		if lysergic_tokenizer_account.owner != &crate::id() {
+			return Err(TokenizerError::TokenizerNotInitialized.into());
+		}
+
+		let mut lysergic_tokenizer_state =
+			TokenizerState::try_from_slice(&lysergic_tokenizer_account.data.borrow()[..])?;
+
+		if stake_pool_account.key != &lysergic_tokenizer_state.stake_pool {
+			return Err(TokenizerError::IncorrectStakePoolAddress.into());
+		}
+
+		let exchange_rate =
+			Self::update_yield_index(&mut lysergic_tokenizer_state, stake_pool_account)?;
+
+		lysergic_tokenizer_state
+			.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+		msg!("Yield index updated to {}", exchange_rate);
+
+		TokenizerEvent::Checkpoint {
+			tokenizer: *lysergic_tokenizer_account.key,
+			yield_index: exchange_rate,
+			slot: lysergic_tokenizer_state.last_update_slot,
+			timestamp: lysergic_tokenizer_state.last_update_timestamp,
+		}
+		.emit()?;
+
+		Ok(())
+	}
+
+	fn process_reset_yield_index(accounts: &[AccountInfo]) -> ProgramResult {
+		let account_info_iter = &mut accounts.iter();
+		let lysergic_tokenizer_account = next_account_info(account_info_iter)?;
+		let authority = next_account_info(account_info_iter)?;
+		let stake_pool_account = next_account_info(account_info_iter)?;
+
+		if lysergic_tokenizer_account.owner != &crate::id() {
+			return Err(TokenizerError::TokenizerNotInitialized.into());
+		}
+
+		if !authority.is_signer {
+			return Err(ProgramError::MissingRequiredSignature);
+		}
+
+		let mut lysergic_tokenizer_state =
+			TokenizerState::try_from_slice(&lysergic_tokenizer_account.data.borrow()[..])?;
+
+		if authority.key != &lysergic_tokenizer_state.authority {
+			return Err(TokenizerError::Unauthorised.into());
+		}
+
+		if stake_pool_account.key != &lysergic_tokenizer_state.stake_pool {
+			return Err(TokenizerError::IncorrectStakePoolAddress.into());
+		}
+
+		// The rate of change bounds are skipped, allowing the authority to recover from a
+		// change the bounds reject such as a slashing of the stake pool
+		let clock = Clock::get()?;
+		let previous_yield_index = lysergic_tokenizer_state.yield_index;
+		let exchange_rate =
+			get_exchange_rate(stake_pool_account, &lysergic_tokenizer_state.underlying_mint)?;
+
+		lysergic_tokenizer_state.yield_index = exchange_rate;
+		lysergic_tokenizer_state.last_update_slot = clock.slot;
+		lysergic_tokenizer_state.last_update_timestamp = clock.unix_timestamp;
+
+		lysergic_tokenizer_state
+			.serialize(&mut &mut lysergic_tokenizer_account.data.borrow_mut()[..])?;
+		msg!(
+			"Yield index reset from {} to {}",
+			previous_yield_index,
+			exchange_rate
+		);
+
+		TokenizerEvent::YieldIndexReset {
+			tokenizer: *lysergic_tokenizer_account.key,
+			authority: *authority.key,
+			previous_yield_index,
+			yield_index: exchange_rate,
+			slot: clock.slot,
+			timestamp: clock.unix_timestamp,
+		}
+		.emit()?;
+
 		Ok(())
 	}
 }
@@ -0,0 +1,156 @@
+use {
+	crate::{
+		error::TokenizerError,
+		math::{mul_div, mul_div_ceil, WAD},
+	},
+	solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey, pubkey::Pubkey},
+};
+
+/// The SPL stake pool program, the issuer of the underlying liquid staking tokens
+pub const STAKE_POOL_PROGRAM_ID: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+// Offsets into the Borsh encoded `StakePool` account of the SPL stake pool program
+const ACCOUNT_TYPE_STAKE_POOL: u8 = 1;
+const POOL_MINT_OFFSET: usize = 1 + 32 + 32 + 32 + 1 + 32 + 32;
+const TOTAL_LAMPORTS_OFFSET: usize = POOL_MINT_OFFSET + 32 + 32 + 32;
+const POOL_TOKEN_SUPPLY_OFFSET: usize = TOTAL_LAMPORTS_OFFSET + 8;
+const STAKE_POOL_MIN_SIZE: usize = POOL_TOKEN_SUPPLY_OFFSET + 8;
+
+/// The maximum annualised growth of the exchange rate between two checkpoints, in basis points
+pub const MAX_RATE_INCREASE_BPS_PER_YEAR: u128 = 2_000;
+
+/// The maximum decrease of the exchange rate per `MIN_RATE_CHANGE_WINDOW` since the previous
+/// checkpoint, in basis points, allowing for slashing of the stake pool. A larger decrease is
+/// accepted once enough time has passed, or immediately through `ResetYieldIndex`
+pub const MAX_RATE_DECREASE_BPS_PER_WINDOW: u128 = 100;
+
+/// The shortest period the rate of change bounds are measured over, roughly one epoch, as the
+/// stake pool only updates its exchange rate once rewards are distributed at epoch boundaries
+pub const MIN_RATE_CHANGE_WINDOW: i64 = 172_800;
+
+/// Basis points in one whole
+pub const BPS: u128 = 10_000;
+
+/// Seconds in a 365 day year, the period rates of change are annualised over
+pub const SECONDS_PER_YEAR: u128 = 31_536_000;
+
+// Narrows the result of a calculation on amounts back to an amount
+fn to_amount(value: u128) -> Result<u64, ProgramError> {
+	u64::try_from(value).map_err(|_| TokenizerError::CalculationFailure.into())
+}
+
+// Reads a little endian `u64` from the given offset
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+	let mut bytes = [0u8; 8];
+	bytes.copy_from_slice(&data[offset..offset + 8]);
+	u64::from_le_bytes(bytes)
+}
+
+/// Returns the number of lamports backing a single underlying token, scaled by `WAD`
+pub fn get_exchange_rate(
+	stake_pool_account: &AccountInfo,
+	underlying_mint: &Pubkey,
+) -> Result<u128, ProgramError> {
+	if stake_pool_account.owner != &STAKE_POOL_PROGRAM_ID {
+		return Err(TokenizerError::IncorrectStakePoolAddress.into());
+	}
+
+	exchange_rate_from_data(&stake_pool_account.data.borrow(), underlying_mint)
+}
+
+/// Returns the exchange rate reported by the data of a stake pool account, scaled by `WAD`
+pub fn exchange_rate_from_data(data: &[u8], underlying_mint: &Pubkey) -> Result<u128, ProgramError> {
+	if data.len() < STAKE_POOL_MIN_SIZE || data[0] != ACCOUNT_TYPE_STAKE_POOL {
+		return Err(ProgramError::InvalidAccountData);
+	}
+
+	if &data[POOL_MINT_OFFSET..POOL_MINT_OFFSET + 32] != underlying_mint.as_ref() {
+		return Err(TokenizerError::IncorrectUnderlyingMintAddress.into());
+	}
+
+	let total_lamports = read_u64(data, TOTAL_LAMPORTS_OFFSET);
+	let pool_token_supply = read_u64(data, POOL_TOKEN_SUPPLY_OFFSET);
+
+	// An empty pool is valued at par
+	if pool_token_supply == 0 {
+		return Ok(WAD);
+	}
+
+	let rate = mul_div(total_lamports as u128, WAD, pool_token_supply as u128)?;
+
+	if rate == 0 {
+		return Err(TokenizerError::InvalidExchangeRate.into());
+	}
+
+	Ok(rate)
+}
+
+/// Checks the exchange rate has moved within the allowed bounds since the previous checkpoint,
+/// rejecting sources that report an implausible jump
+pub fn check_rate_change(
+	previous_rate: u128,
+	current_rate: u128,
+	elapsed: i64,
+) -> Result<(), ProgramError> {
+	let window = elapsed.max(MIN_RATE_CHANGE_WINDOW) as u128;
+
+	if current_rate < previous_rate {
+		let max_decrease = mul_div(
+			previous_rate,
+			MAX_RATE_DECREASE_BPS_PER_WINDOW * window,
+			BPS * MIN_RATE_CHANGE_WINDOW as u128,
+		)?;
+
+		if previous_rate - current_rate > max_decrease {
+			return Err(TokenizerError::ExchangeRateOutOfBounds.into());
+		}
+
+		return Ok(());
+	}
+
+	let max_increase = mul_div(
+		previous_rate,
+		MAX_RATE_INCREASE_BPS_PER_YEAR * window,
+		BPS * SECONDS_PER_YEAR,
+	)?;
+
+	if current_rate - previous_rate > max_increase {
+		return Err(TokenizerError::ExchangeRateOutOfBounds.into());
+	}
+
+	Ok(())
+}
+
+/// Converts an amount of the underlying token into the accounting asset at the given rate
+pub fn underlying_to_asset(amount: u64, exchange_rate: u128) -> Result<u64, ProgramError> {
+	to_amount(mul_div(amount as u128, exchange_rate, WAD)?)
+}
+
+/// Returns the share of `pool` owed to `amount` out of a total `supply`, rounding down
+pub fn pro_rata_share(amount: u64, pool: u64, supply: u64) -> Result<u64, ProgramError> {
+	if supply == 0 {
+		return Ok(0);
+	}
+
+	to_amount(mul_div(amount as u128, pool as u128, supply as u128)?)
+}
+
+/// Converts an amount of the accounting asset into the underlying token at the given rate,
+/// rounding up, used when reserving underlying in the vault
+pub fn asset_to_underlying_ceil(amount: u64, exchange_rate: u128) -> Result<u64, ProgramError> {
+	if exchange_rate == 0 {
+		return Err(TokenizerError::InvalidExchangeRate.into());
+	}
+
+	to_amount(mul_div_ceil(amount as u128, WAD, exchange_rate)?)
+}
+
+/// Converts an amount of the accounting asset into the underlying token at the given rate,
+/// rounding down in favour of the vault
+pub fn asset_to_underlying(amount: u64, exchange_rate: u128) -> Result<u64, ProgramError> {
+	if exchange_rate == 0 {
+		return Err(TokenizerError::InvalidExchangeRate.into());
+	}
+
+	to_amount(mul_div(amount as u128, WAD, exchange_rate)?)
+}
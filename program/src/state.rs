@@ -3,9 +3,24 @@ use {
 	solana_program::pubkey::Pubkey,
 };
 
-pub const STATE_SIZE: usize = 1 + 32 + 32 + 32 + 32 + 32 + 8 + 8; // 184 bytes
+pub const STATE_SIZE: usize = 1 + 32 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 32 + 16 + 1 + 32 + 8 + 16 + 8 + 8; // 306 bytes
+// Offsets of the fields of `TokenizerState` used to filter tokenizer accounts
+pub const AUTHORITY_OFFSET: usize = 1;
+pub const UNDERLYING_MINT_OFFSET: usize = 1 + 32 + 32 + 32;
+pub const ESCROW_STATE_SIZE: usize = 1 + 32 + 32 + 32 + 32 + 32 + 8 + 16 + 8; // 193 bytes
 
-#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+/// The recipient of the yield earned by the underlying backing unredeemed principal tokens
+/// after the expiry date
+#[derive(Clone, BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub enum PostExpiryYieldRecipient {
+	/// The yield is swept to the treasury and principal tokens settle for exactly one unit
+	/// of the accounting asset
+	Treasury,
+	/// The yield remains in the vault and accrues to the principal token holders
+	PrincipalHolders,
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
 pub struct TokenizerState {
     pub bump: u8,
 	pub authority: Pubkey,
@@ -15,4 +30,34 @@ pub struct TokenizerState {
 	pub underlying_vault: Pubkey,
 	pub expiry_date: i64,
 	pub fixed_apy: u64,
+	pub grace_period: i64,
+	/// The stake pool that issues the underlying token and reports its exchange rate
+	pub stake_pool: Pubkey,
+	/// The exchange rate of the underlying frozen on the first interaction after expiry,
+	/// zero until it has been recorded
+	pub expiry_exchange_rate: u128,
+	pub post_expiry_yield: PostExpiryYieldRecipient,
+	/// The wallet whose underlying token account receives the post expiry yield
+	pub treasury: Pubkey,
+	/// The underlying owed to the yield token holders, frozen alongside the exchange rate
+	pub expiry_yield_reserve: u64,
+	/// The exchange rate of the underlying recorded at the last checkpoint, scaled by `WAD`
+	pub yield_index: u128,
+	pub last_update_slot: u64,
+	pub last_update_timestamp: i64,
+}
+
+/// Holds the underlying swept from a tokenizer once its settlement window has elapsed so that
+/// late principal and yield token holders can still redeem after the tokenizer is terminated
+#[derive(Clone, BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct ClaimsEscrowState {
+	pub bump: u8,
+	pub tokenizer: Pubkey,
+	pub principal_token_mint: Pubkey,
+	pub yield_token_mint: Pubkey,
+	pub underlying_mint: Pubkey,
+	pub escrow_vault: Pubkey,
+	pub expiry_date: i64,
+	pub expiry_exchange_rate: u128,
+	pub expiry_yield_reserve: u64,
 }
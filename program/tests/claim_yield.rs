@@ -0,0 +1,50 @@
+mod common;
+
+use {
+	common::{rate_bps, TestTokenizer, DAY, ONE, START},
+	sclr_token::state::PostExpiryYieldRecipient,
+	solana_sdk::signature::Signer,
+};
+
+const GRACE_PERIOD: i64 = 30 * DAY;
+
+#[tokio::test]
+async fn claims_share_surplus_before_expiry_and_reserve_after() {
+	let mut test =
+		TestTokenizer::new(PostExpiryYieldRecipient::PrincipalHolders, GRACE_PERIOD).await;
+	let alice = test.add_user(100 * ONE);
+	let bob = test.add_user(50 * ONE);
+	test.deposit_and_tokenize(&alice, 100).await.unwrap();
+	test.deposit_and_tokenize(&bob, 50).await.unwrap();
+
+	// At 1.04 the 150 principal tokens are backed by 144.230770 of the 150 underlying in the
+	// vault, the 5.769230 surplus is shared by the 150 yield tokens
+	test.set_clock(START + 180 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_400));
+	test.claim_yield(&alice, 50).await.unwrap();
+
+	assert_eq!(test.underlying_balance(&alice.pubkey()).await, 1_923_076);
+	assert_eq!(test.vault_balance().await, 148_076_924);
+	let yield_token_mint = test.state().await.yield_token_mint;
+	assert_eq!(test.mint_supply(&yield_token_mint).await, 100 * ONE);
+
+	// At expiry the 150 principal tokens are backed by 141.509434 underlying, the remaining
+	// 6.567490 is reserved for the 100 outstanding yield tokens
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_600));
+	test.claim_yield(&bob, 50).await.unwrap();
+
+	assert_eq!(test.underlying_balance(&bob.pubkey()).await, 3_283_745);
+	assert_eq!(test.state().await.expiry_yield_reserve, 3_283_745);
+
+	// Yield no longer accrues after expiry, later claims share the same reserve
+	test.set_clock(test.expiry_date + 10 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_650));
+	test.claim_yield(&alice, 50).await.unwrap();
+
+	assert_eq!(
+		test.underlying_balance(&alice.pubkey()).await,
+		1_923_076 + 3_283_745
+	);
+	assert_eq!(test.state().await.expiry_yield_reserve, 0);
+}
@@ -0,0 +1,508 @@
+#![allow(dead_code)]
+
+use {
+	borsh::BorshDeserialize,
+	sclr_token::{
+		entrypoint::process_instruction,
+		get_escrow_address,
+		instruction::{
+			self, CheckpointAccounts, ClaimEscrowYieldAccounts, ClaimYieldAccounts,
+			DepositAndTokenizeAccounts, InitializeTokenizerAccounts, RedeemEscrowPrincipalAccounts,
+			RedeemMaturePrincipalAccounts, ResetYieldIndexAccounts, SettleAccounts,
+			SweepToEscrowAccounts, TerminateAccounts,
+		},
+		math::WAD,
+		rate::STAKE_POOL_PROGRAM_ID,
+		state::{ClaimsEscrowState, PostExpiryYieldRecipient, TokenizerState},
+		Expiry,
+	},
+	solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext},
+	solana_sdk::{
+		account::{Account, AccountSharedData},
+		clock::Clock,
+		instruction::Instruction,
+		native_token::LAMPORTS_PER_SOL,
+		program_option::COption,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		signature::{Keypair, Signer},
+		system_program,
+		transaction::Transaction,
+	},
+	spl_associated_token_account::get_associated_token_address,
+	spl_token::state::{Account as TokenAccount, AccountState, Mint},
+};
+
+/// One whole token of the underlying, principal and yield mints
+pub const ONE: u64 = 1_000_000;
+
+/// The time the tokenizer is initialized at, the start of a day
+pub const START: i64 = 1_704_067_200;
+
+/// The number of seconds in a day
+pub const DAY: i64 = 86_400;
+
+/// The size of an encoded `StakePool` account up to the last field read by the tokenizer
+pub const STAKE_POOL_SIZE: usize = 274;
+
+// The pool token supply of the test stake pool, exchange rates are expressed against it
+const POOL_TOKEN_SUPPLY: u64 = 1_000_000_000;
+
+// Offsets into the Borsh encoded `StakePool` account of the SPL stake pool program
+const POOL_MINT_OFFSET: usize = 162;
+const TOTAL_LAMPORTS_OFFSET: usize = 258;
+const POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+
+/// Returns the `WAD` scaled exchange rate of the given number of basis points, e.g. 10_500
+/// for a rate of 1.05
+pub fn rate_bps(bps: u128) -> u128 {
+	WAD * bps / 10_000
+}
+
+/// Encodes the fields of a stake pool account read by the tokenizer
+pub fn stake_pool_data(pool_mint: &Pubkey, exchange_rate: u128) -> Vec<u8> {
+	let total_lamports = (exchange_rate * POOL_TOKEN_SUPPLY as u128 / WAD) as u64;
+	stake_pool_data_with_balances(pool_mint, total_lamports, POOL_TOKEN_SUPPLY)
+}
+
+/// Encodes the fields of a stake pool account with the given total lamports and supply
+pub fn stake_pool_data_with_balances(
+	pool_mint: &Pubkey,
+	total_lamports: u64,
+	pool_token_supply: u64,
+) -> Vec<u8> {
+	let mut data = vec![0u8; STAKE_POOL_SIZE];
+	data[0] = 1;
+	data[POOL_MINT_OFFSET..POOL_MINT_OFFSET + 32].copy_from_slice(pool_mint.as_ref());
+	data[TOTAL_LAMPORTS_OFFSET..TOTAL_LAMPORTS_OFFSET + 8]
+		.copy_from_slice(&total_lamports.to_le_bytes());
+	data[POOL_TOKEN_SUPPLY_OFFSET..POOL_TOKEN_SUPPLY_OFFSET + 8]
+		.copy_from_slice(&pool_token_supply.to_le_bytes());
+	data
+}
+
+fn packed_account<T: Pack>(state: T, owner: &Pubkey) -> Account {
+	let mut data = vec![0u8; T::LEN];
+	T::pack(state, &mut data).unwrap();
+
+	Account {
+		lamports: LAMPORTS_PER_SOL,
+		data,
+		owner: *owner,
+		executable: false,
+		rent_epoch: 0,
+	}
+}
+
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+	packed_account(
+		TokenAccount {
+			mint: *mint,
+			owner: *owner,
+			amount,
+			delegate: COption::None,
+			state: AccountState::Initialized,
+			is_native: COption::None,
+			delegated_amount: 0,
+			close_authority: COption::None,
+		},
+		&spl_token::id(),
+	)
+}
+
+/// A tokenizer over a test stake pool, initialized at `START` with an exchange rate of one
+pub struct TestTokenizer {
+	pub context: ProgramTestContext,
+	pub underlying_mint: Pubkey,
+	pub stake_pool: Pubkey,
+	pub treasury: Pubkey,
+	pub tokenizer: Pubkey,
+	pub expiry_date: i64,
+}
+
+impl TestTokenizer {
+	pub async fn new(post_expiry_yield: PostExpiryYieldRecipient, grace_period: i64) -> Self {
+		let underlying_mint = Pubkey::new_unique();
+		let stake_pool = Pubkey::new_unique();
+		let treasury = Pubkey::new_unique();
+
+		let mut program_test = ProgramTest::new(
+			"sclr_token",
+			sclr_token::id(),
+			processor!(process_instruction),
+		);
+		program_test.add_account(
+			underlying_mint,
+			packed_account(
+				Mint {
+					mint_authority: COption::Some(Pubkey::new_unique()),
+					supply: u64::MAX / 2,
+					decimals: 6,
+					is_initialized: true,
+					freeze_authority: COption::None,
+				},
+				&spl_token::id(),
+			),
+		);
+		program_test.add_account(
+			stake_pool,
+			Account {
+				lamports: LAMPORTS_PER_SOL,
+				data: stake_pool_data(&underlying_mint, WAD),
+				owner: STAKE_POOL_PROGRAM_ID,
+				executable: false,
+				rent_epoch: 0,
+			},
+		);
+		program_test.add_account(
+			get_associated_token_address(&treasury, &underlying_mint),
+			token_account(&underlying_mint, &treasury, 0),
+		);
+
+		let context = program_test.start_with_context().await;
+		let expiry_date = Expiry::TwelveMonths.to_expiry_date(START).unwrap();
+
+		let mut test_tokenizer = TestTokenizer {
+			context,
+			underlying_mint,
+			stake_pool,
+			treasury,
+			tokenizer: sclr_token::get_tokenizer_address(&underlying_mint, expiry_date).0,
+			expiry_date,
+		};
+		test_tokenizer.set_clock(START).await;
+
+		let accounts = InitializeTokenizerAccounts::resolve(
+			&underlying_mint,
+			expiry_date,
+			&test_tokenizer.context.payer.pubkey(),
+			&stake_pool,
+		);
+		let instruction = instruction::init_tokenizer_and_mints(
+			&accounts,
+			&treasury,
+			Expiry::TwelveMonths,
+			0,
+			grace_period,
+			post_expiry_yield,
+		)
+		.unwrap();
+		test_tokenizer.process(&[instruction], &[]).await.unwrap();
+
+		test_tokenizer
+	}
+
+	// Signs a transaction paid for by the tokenizer authority with a new blockhash
+	async fn transaction(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+		let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+
+		let mut all_signers = vec![&self.context.payer];
+		all_signers.extend_from_slice(signers);
+
+		Transaction::new_signed_with_payer(
+			instructions,
+			Some(&self.context.payer.pubkey()),
+			&all_signers,
+			blockhash,
+		)
+	}
+
+	/// Sends a transaction paid for and signed by the tokenizer authority
+	pub async fn process(
+		&mut self,
+		instructions: &[Instruction],
+		signers: &[&Keypair],
+	) -> Result<(), BanksClientError> {
+		let transaction = self.transaction(instructions, signers).await;
+
+		self.context
+			.banks_client
+			.process_transaction(transaction)
+			.await
+	}
+
+	/// Sends a transaction like `process` and returns the compute units it consumed, which
+	/// are only metered when the program is loaded from `SBF_OUT_DIR` rather than run natively
+	pub async fn process_with_compute_units(
+		&mut self,
+		instructions: &[Instruction],
+		signers: &[&Keypair],
+	) -> Result<u64, BanksClientError> {
+		let transaction = self.transaction(instructions, signers).await;
+
+		let result = self
+			.context
+			.banks_client
+			.process_transaction_with_metadata(transaction)
+			.await?;
+		result.result?;
+
+		Ok(result
+			.metadata
+			.map(|metadata| metadata.compute_units_consumed)
+			.unwrap_or_default())
+	}
+
+	/// Moves the clock to the given unix timestamp
+	pub async fn set_clock(&mut self, unix_timestamp: i64) {
+		let mut clock = self
+			.context
+			.banks_client
+			.get_sysvar::<Clock>()
+			.await
+			.unwrap();
+		clock.unix_timestamp = unix_timestamp;
+		self.context.set_sysvar(&clock);
+	}
+
+	/// Sets the exchange rate reported by the stake pool
+	pub fn set_exchange_rate(&mut self, exchange_rate: u128) {
+		let account = Account {
+			lamports: LAMPORTS_PER_SOL,
+			data: stake_pool_data(&self.underlying_mint, exchange_rate),
+			owner: STAKE_POOL_PROGRAM_ID,
+			executable: false,
+			rent_epoch: 0,
+		};
+		self.context
+			.set_account(&self.stake_pool, &AccountSharedData::from(account));
+	}
+
+	/// Creates a funded user holding the given amount of the underlying
+	pub fn add_user(&mut self, underlying_amount: u64) -> Keypair {
+		let user = Keypair::new();
+
+		self.context.set_account(
+			&user.pubkey(),
+			&AccountSharedData::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+		);
+		self.context.set_account(
+			&get_associated_token_address(&user.pubkey(), &self.underlying_mint),
+			&AccountSharedData::from(token_account(
+				&self.underlying_mint,
+				&user.pubkey(),
+				underlying_amount,
+			)),
+		);
+
+		user
+	}
+
+	/// Sends underlying straight to a token account, bypassing the tokenizer
+	pub async fn airdrop_underlying(&mut self, token_account_address: &Pubkey, amount: u64) {
+		let mut account = self
+			.context
+			.banks_client
+			.get_account(*token_account_address)
+			.await
+			.unwrap()
+			.unwrap();
+		let mut token_account = TokenAccount::unpack(&account.data).unwrap();
+		token_account.amount += amount;
+		TokenAccount::pack(token_account, &mut account.data).unwrap();
+
+		self.context
+			.set_account(token_account_address, &AccountSharedData::from(account));
+	}
+
+	/// Returns an account, `None` once it has been closed
+	pub async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+		self.context.banks_client.get_account(*address).await.unwrap()
+	}
+
+	pub async fn state(&mut self) -> TokenizerState {
+		let account = self
+			.context
+			.banks_client
+			.get_account(self.tokenizer)
+			.await
+			.unwrap()
+			.unwrap();
+
+		TokenizerState::try_from_slice(&account.data).unwrap()
+	}
+
+	pub async fn escrow_state(&mut self) -> ClaimsEscrowState {
+		let account = self
+			.context
+			.banks_client
+			.get_account(get_escrow_address(&self.tokenizer).0)
+			.await
+			.unwrap()
+			.unwrap();
+
+		ClaimsEscrowState::try_from_slice(&account.data).unwrap()
+	}
+
+	/// Returns the balance of a token account, zero if it does not exist
+	pub async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+		match self
+			.context
+			.banks_client
+			.get_account(*address)
+			.await
+			.unwrap()
+		{
+			Some(account) => TokenAccount::unpack(&account.data).unwrap().amount,
+			None => 0,
+		}
+	}
+
+	pub async fn mint_supply(&mut self, address: &Pubkey) -> u64 {
+		let account = self
+			.context
+			.banks_client
+			.get_account(*address)
+			.await
+			.unwrap()
+			.unwrap();
+
+		Mint::unpack(&account.data).unwrap().supply
+	}
+
+	pub async fn vault_balance(&mut self) -> u64 {
+		let vault = get_associated_token_address(&self.tokenizer, &self.underlying_mint);
+		self.token_balance(&vault).await
+	}
+
+	pub async fn escrow_vault_balance(&mut self) -> u64 {
+		let (escrow, _) = get_escrow_address(&self.tokenizer);
+		let escrow_vault = get_associated_token_address(&escrow, &self.underlying_mint);
+		self.token_balance(&escrow_vault).await
+	}
+
+	pub async fn treasury_balance(&mut self) -> u64 {
+		let treasury_token_account =
+			get_associated_token_address(&self.treasury, &self.underlying_mint);
+		self.token_balance(&treasury_token_account).await
+	}
+
+	pub async fn underlying_balance(&mut self, user: &Pubkey) -> u64 {
+		let user_token_account = get_associated_token_address(user, &self.underlying_mint);
+		self.token_balance(&user_token_account).await
+	}
+
+	pub async fn deposit_and_tokenize(
+		&mut self,
+		user: &Keypair,
+		amount: u64,
+	) -> Result<(), BanksClientError> {
+		let state = self.state().await;
+		let accounts = DepositAndTokenizeAccounts::resolve(&self.tokenizer, &state, &user.pubkey());
+		let instruction = instruction::deposit_and_tokenize(&accounts, amount).unwrap();
+
+		self.process(&[instruction], &[user]).await
+	}
+
+	pub async fn redeem_mature_principal(
+		&mut self,
+		user: &Keypair,
+		principal_amount: u64,
+	) -> Result<(), BanksClientError> {
+		let state = self.state().await;
+		let accounts =
+			RedeemMaturePrincipalAccounts::resolve(&self.tokenizer, &state, &user.pubkey());
+		let instruction =
+			instruction::redeem_mature_principal(&accounts, principal_amount).unwrap();
+
+		self.process(&[instruction], &[user]).await
+	}
+
+	pub async fn claim_yield(
+		&mut self,
+		user: &Keypair,
+		yield_amount: u64,
+	) -> Result<(), BanksClientError> {
+		let state = self.state().await;
+		let accounts = ClaimYieldAccounts::resolve(&self.tokenizer, &state, &user.pubkey());
+		let instruction = instruction::claim_yield(&accounts, yield_amount).unwrap();
+
+		self.process(&[instruction], &[user]).await
+	}
+
+	pub async fn settle(&mut self) -> Result<(), BanksClientError> {
+		let state = self.state().await;
+		let accounts = SettleAccounts::resolve(
+			&self.tokenizer,
+			&state.underlying_mint,
+			&state.stake_pool,
+			&state.treasury,
+		);
+		let instruction = instruction::settle(&accounts).unwrap();
+
+		self.process(&[instruction], &[]).await
+	}
+
+	pub async fn sweep_to_escrow(&mut self) -> Result<(), BanksClientError> {
+		let state = self.state().await;
+		let accounts = SweepToEscrowAccounts::resolve(
+			&self.tokenizer,
+			&state.underlying_mint,
+			&state.authority,
+			&state.stake_pool,
+			&state.treasury,
+		);
+		let instruction = instruction::sweep_to_escrow(&accounts).unwrap();
+
+		self.process(&[instruction], &[]).await
+	}
+
+	pub async fn redeem_escrow_principal(
+		&mut self,
+		user: &Keypair,
+		principal_amount: u64,
+	) -> Result<(), BanksClientError> {
+		let accounts = RedeemEscrowPrincipalAccounts::resolve(
+			&self.tokenizer,
+			&self.underlying_mint,
+			&user.pubkey(),
+		);
+		let instruction =
+			instruction::redeem_escrow_principal(&accounts, principal_amount).unwrap();
+
+		self.process(&[instruction], &[user]).await
+	}
+
+	pub async fn claim_escrow_yield(
+		&mut self,
+		user: &Keypair,
+		yield_amount: u64,
+	) -> Result<(), BanksClientError> {
+		let accounts = ClaimEscrowYieldAccounts::resolve(
+			&self.tokenizer,
+			&self.underlying_mint,
+			&user.pubkey(),
+		);
+		let instruction = instruction::claim_escrow_yield(&accounts, yield_amount).unwrap();
+
+		self.process(&[instruction], &[user]).await
+	}
+
+	pub async fn checkpoint(&mut self) -> Result<(), BanksClientError> {
+		let state = self.state().await;
+		let accounts = CheckpointAccounts::resolve(&self.tokenizer, &state.stake_pool);
+		let instruction = instruction::checkpoint(&accounts).unwrap();
+
+		self.process(&[instruction], &[]).await
+	}
+
+	pub async fn terminate(&mut self) -> Result<(), BanksClientError> {
+		let accounts = TerminateAccounts::resolve(
+			&self.tokenizer,
+			&self.underlying_mint,
+			&self.context.payer.pubkey(),
+		);
+		let instruction = instruction::terminate(&accounts).unwrap();
+
+		self.process(&[instruction], &[]).await
+	}
+
+	pub async fn reset_yield_index(&mut self) -> Result<(), BanksClientError> {
+		let state = self.state().await;
+		let accounts =
+			ResetYieldIndexAccounts::resolve(&self.tokenizer, &state.authority, &state.stake_pool);
+		let instruction = instruction::reset_yield_index(&accounts).unwrap();
+
+		self.process(&[instruction], &[]).await
+	}
+}
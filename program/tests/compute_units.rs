@@ -0,0 +1,235 @@
+mod common;
+
+use {
+	common::{rate_bps, TestTokenizer, DAY, ONE, START},
+	sclr_token::{
+		instruction::{
+			self, CheckpointAccounts, ClaimEscrowYieldAccounts, ClaimYieldAccounts,
+			DepositAndTokenizeAccounts, RedeemEscrowPrincipalAccounts,
+			RedeemMaturePrincipalAccounts, SettleAccounts, SweepToEscrowAccounts,
+		},
+		state::PostExpiryYieldRecipient,
+	},
+	solana_sdk::{
+		instruction::Instruction,
+		signature::{Keypair, Signer},
+	},
+};
+
+#[cfg(feature = "bench")]
+use sclr_token::{bench::MathBenchInstruction, math::WAD};
+
+const GRACE_PERIOD: i64 = 30 * DAY;
+
+// The compute units an instruction may consume. The native processor is not metered, so the
+// benchmarks are ignored by `cargo test` and run against the SBF build of the program with
+// `cargo test-sbf --features bench -- --ignored`
+const DEPOSIT_AND_TOKENIZE_BUDGET: u64 = 60_000;
+const CHECKPOINT_BUDGET: u64 = 15_000;
+const CLAIM_YIELD_BUDGET: u64 = 40_000;
+const SETTLE_BUDGET: u64 = 40_000;
+const REDEEM_MATURE_PRINCIPAL_BUDGET: u64 = 40_000;
+const SWEEP_TO_ESCROW_BUDGET: u64 = 80_000;
+const REDEEM_ESCROW_PRINCIPAL_BUDGET: u64 = 40_000;
+const CLAIM_ESCROW_YIELD_BUDGET: u64 = 40_000;
+#[cfg(feature = "bench")]
+const MUL_DIV_BUDGET: u64 = 5_000;
+#[cfg(feature = "bench")]
+const EXP_WAD_BUDGET: u64 = 30_000;
+#[cfg(feature = "bench")]
+const LN_WAD_BUDGET: u64 = 30_000;
+#[cfg(feature = "bench")]
+const POW_WAD_BUDGET: u64 = 60_000;
+#[cfg(feature = "bench")]
+const POWI_WAD_BUDGET: u64 = 30_000;
+
+// Processes a single instruction and checks the compute units it consumed against its budget,
+// failing when the program is run natively as its compute units are not metered
+async fn measure(
+	test: &mut TestTokenizer,
+	name: &str,
+	instruction: Instruction,
+	signers: &[&Keypair],
+	budget: u64,
+) {
+	assert!(
+		std::env::var("SBF_OUT_DIR").is_ok() || std::env::var("BPF_OUT_DIR").is_ok(),
+		"Compute units are only metered for the SBF build, run with `cargo test-sbf`"
+	);
+
+	let compute_units = test
+		.process_with_compute_units(&[instruction], signers)
+		.await
+		.unwrap();
+
+	assert!(
+		compute_units <= budget,
+		"{} consumed {} compute units, over its budget of {}",
+		name,
+		compute_units,
+		budget
+	);
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF build of the program, run with `cargo test-sbf -- --ignored`"]
+async fn instructions_stay_within_compute_budgets() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	let tokenizer = test.tokenizer;
+	let state = test.state().await;
+
+	measure(
+		&mut test,
+		"deposit_and_tokenize",
+		instruction::deposit_and_tokenize(
+			&DepositAndTokenizeAccounts::resolve(&tokenizer, &state, &user.pubkey()),
+			100,
+		)
+		.unwrap(),
+		&[&user],
+		DEPOSIT_AND_TOKENIZE_BUDGET,
+	)
+	.await;
+
+	test.set_clock(START + 180 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_400));
+	measure(
+		&mut test,
+		"checkpoint",
+		instruction::checkpoint(&CheckpointAccounts::resolve(&tokenizer, &state.stake_pool)).unwrap(),
+		&[],
+		CHECKPOINT_BUDGET,
+	)
+	.await;
+	measure(
+		&mut test,
+		"claim_yield",
+		instruction::claim_yield(
+			&ClaimYieldAccounts::resolve(&tokenizer, &state, &user.pubkey()),
+			50,
+		)
+		.unwrap(),
+		&[&user],
+		CLAIM_YIELD_BUDGET,
+	)
+	.await;
+
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	measure(
+		&mut test,
+		"settle",
+		instruction::settle(&SettleAccounts::resolve(
+			&tokenizer,
+			&state.underlying_mint,
+			&state.stake_pool,
+			&state.treasury,
+		))
+		.unwrap(),
+		&[],
+		SETTLE_BUDGET,
+	)
+	.await;
+	measure(
+		&mut test,
+		"redeem_mature_principal",
+		instruction::redeem_mature_principal(
+			&RedeemMaturePrincipalAccounts::resolve(&tokenizer, &state, &user.pubkey()),
+			50,
+		)
+		.unwrap(),
+		&[&user],
+		REDEEM_MATURE_PRINCIPAL_BUDGET,
+	)
+	.await;
+
+	test.set_clock(test.expiry_date + GRACE_PERIOD + DAY).await;
+	test.set_exchange_rate(rate_bps(10_600));
+	measure(
+		&mut test,
+		"sweep_to_escrow",
+		instruction::sweep_to_escrow(&SweepToEscrowAccounts::resolve(
+			&tokenizer,
+			&state.underlying_mint,
+			&state.authority,
+			&state.stake_pool,
+			&state.treasury,
+		))
+		.unwrap(),
+		&[],
+		SWEEP_TO_ESCROW_BUDGET,
+	)
+	.await;
+	measure(
+		&mut test,
+		"redeem_escrow_principal",
+		instruction::redeem_escrow_principal(
+			&RedeemEscrowPrincipalAccounts::resolve(&tokenizer, &state.underlying_mint, &user.pubkey()),
+			50,
+		)
+		.unwrap(),
+		&[&user],
+		REDEEM_ESCROW_PRINCIPAL_BUDGET,
+	)
+	.await;
+	measure(
+		&mut test,
+		"claim_escrow_yield",
+		instruction::claim_escrow_yield(
+			&ClaimEscrowYieldAccounts::resolve(&tokenizer, &state.underlying_mint, &user.pubkey()),
+			50,
+		)
+		.unwrap(),
+		&[&user],
+		CLAIM_ESCROW_YIELD_BUDGET,
+	)
+	.await;
+}
+
+#[cfg(feature = "bench")]
+#[tokio::test]
+#[ignore = "needs the SBF build of the program, run with `cargo test-sbf --features bench -- --ignored`"]
+async fn math_stays_within_compute_budgets() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let wad = WAD as i128;
+
+	let benchmarks = [
+		// A product overflowing a `u128`, taking the 256 bit path
+		(
+			"mul_div",
+			MathBenchInstruction::MulDiv {
+				a: u128::MAX / 2,
+				b: 3 * WAD,
+				denominator: 7 * WAD,
+			},
+			MUL_DIV_BUDGET,
+		),
+		("exp_wad", MathBenchInstruction::ExpWad { x: 40 * wad + 1 }, EXP_WAD_BUDGET),
+		("ln_wad", MathBenchInstruction::LnWad { x: u128::MAX / 3 }, LN_WAD_BUDGET),
+		// Half a year of growth at 5% a year
+		(
+			"pow_wad",
+			MathBenchInstruction::PowWad {
+				base: rate_bps(10_500),
+				exponent: wad / 2 + 1,
+			},
+			POW_WAD_BUDGET,
+		),
+		// A year of daily compounding at 5% a year
+		(
+			"powi_wad",
+			MathBenchInstruction::PowiWad {
+				base: WAD + WAD / 7_300,
+				exponent: 365,
+			},
+			POWI_WAD_BUDGET,
+		),
+	];
+
+	for (name, bench_instruction, budget) in benchmarks {
+		let instruction =
+			Instruction::new_with_bytes(sclr_token::id(), &bench_instruction.data(), vec![]);
+		measure(&mut test, name, instruction, &[], budget).await;
+	}
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use {
+	common::{rate_bps, TestTokenizer, DAY, ONE},
+	sclr_token::{error::TokenizerError, state::PostExpiryYieldRecipient},
+	solana_sdk::{
+		instruction::InstructionError, signature::Signer, transaction::TransactionError,
+	},
+};
+
+const GRACE_PERIOD: i64 = 30 * DAY;
+
+#[tokio::test]
+async fn sweep_settles_treasury_yield_before_escrow() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	// Expiry settles at 1.05, 100 principal tokens are backed by 95.238096 underlying
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.settle().await.unwrap();
+	assert_eq!(test.state().await.expiry_yield_reserve, 4_761_904);
+
+	// The rate grows to 1.06 by the end of the settlement window, the yield earned since
+	// expiry belongs to the treasury
+	test.set_clock(test.expiry_date + GRACE_PERIOD + DAY).await;
+	test.set_exchange_rate(rate_bps(10_600));
+	test.sweep_to_escrow().await.unwrap();
+
+	assert_eq!(test.treasury_balance().await, 898_473);
+	assert_eq!(test.vault_balance().await, 0);
+	assert_eq!(test.escrow_vault_balance().await, 99_101_527);
+	assert_eq!(test.state().await.expiry_yield_reserve, 0);
+
+	let escrow_state = test.escrow_state().await;
+	assert_eq!(escrow_state.expiry_exchange_rate, rate_bps(10_600));
+	assert_eq!(escrow_state.expiry_yield_reserve, 4_761_904);
+
+	// Principal settles at the same rate it would have before the sweep
+	test.redeem_escrow_principal(&user, 100).await.unwrap();
+	assert_eq!(test.underlying_balance(&user.pubkey()).await, 94_339_622);
+
+	test.claim_escrow_yield(&user, 100).await.unwrap();
+	assert_eq!(
+		test.underlying_balance(&user.pubkey()).await,
+		94_339_622 + 4_761_904
+	);
+	assert_eq!(test.escrow_state().await.expiry_yield_reserve, 0);
+	assert_eq!(test.escrow_vault_balance().await, 1);
+}
+
+#[tokio::test]
+async fn sweep_keeps_post_expiry_yield_for_principal_holders() {
+	let mut test =
+		TestTokenizer::new(PostExpiryYieldRecipient::PrincipalHolders, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	// The sweep is the first interaction after expiry and settles it at 1.05
+	test.set_clock(test.expiry_date + GRACE_PERIOD + DAY).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.sweep_to_escrow().await.unwrap();
+
+	assert_eq!(test.treasury_balance().await, 0);
+	assert_eq!(test.vault_balance().await, 0);
+	assert_eq!(test.escrow_vault_balance().await, 100 * ONE);
+
+	let escrow_state = test.escrow_state().await;
+	assert_eq!(escrow_state.expiry_exchange_rate, rate_bps(10_500));
+	assert_eq!(escrow_state.expiry_yield_reserve, 4_761_904);
+
+	test.redeem_escrow_principal(&user, 100).await.unwrap();
+	assert_eq!(test.underlying_balance(&user.pubkey()).await, 95_238_095);
+
+	test.claim_escrow_yield(&user, 100).await.unwrap();
+	assert_eq!(
+		test.underlying_balance(&user.pubkey()).await,
+		95_238_095 + 4_761_904
+	);
+}
+
+#[tokio::test]
+async fn second_sweep_adds_underlying_to_escrow_yield_reserve() {
+	let mut test =
+		TestTokenizer::new(PostExpiryYieldRecipient::PrincipalHolders, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	test.set_clock(test.expiry_date + GRACE_PERIOD + DAY).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.sweep_to_escrow().await.unwrap();
+
+	let vault = test.state().await.underlying_vault;
+	test.airdrop_underlying(&vault, ONE).await;
+	test.sweep_to_escrow().await.unwrap();
+
+	assert_eq!(test.vault_balance().await, 0);
+	assert_eq!(test.escrow_vault_balance().await, 101 * ONE);
+
+	let escrow_state = test.escrow_state().await;
+	assert_eq!(escrow_state.expiry_exchange_rate, rate_bps(10_500));
+	assert_eq!(escrow_state.expiry_yield_reserve, 4_761_904 + ONE);
+}
+
+#[tokio::test]
+async fn second_sweep_sends_underlying_to_treasury() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	test.set_clock(test.expiry_date + GRACE_PERIOD + DAY).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.sweep_to_escrow().await.unwrap();
+	assert_eq!(test.treasury_balance().await, 0);
+
+	let vault = test.state().await.underlying_vault;
+	test.airdrop_underlying(&vault, ONE).await;
+	test.sweep_to_escrow().await.unwrap();
+
+	assert_eq!(test.vault_balance().await, 0);
+	assert_eq!(test.treasury_balance().await, ONE);
+	assert_eq!(test.escrow_vault_balance().await, 100 * ONE);
+	assert_eq!(test.escrow_state().await.expiry_yield_reserve, 4_761_904);
+}
+
+#[tokio::test]
+async fn sweep_fails_within_settlement_window() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	test.set_clock(test.expiry_date + GRACE_PERIOD).await;
+	let error = test.sweep_to_escrow().await.unwrap_err().unwrap();
+
+	assert_eq!(
+		error,
+		TransactionError::InstructionError(
+			0,
+			InstructionError::Custom(TokenizerError::SettlementWindowNotElapsed as u32)
+		)
+	);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use {
+	common::{rate_bps, TestTokenizer, DAY, ONE},
+	sclr_token::state::PostExpiryYieldRecipient,
+	solana_sdk::signature::Signer,
+};
+
+const GRACE_PERIOD: i64 = 30 * DAY;
+
+#[tokio::test]
+async fn first_interaction_after_expiry_snapshots_rate_and_reserve() {
+	let mut test =
+		TestTokenizer::new(PostExpiryYieldRecipient::PrincipalHolders, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	let state = test.state().await;
+	assert_eq!(state.expiry_exchange_rate, 0);
+	assert_eq!(state.expiry_yield_reserve, 0);
+
+	// The redemption settles the expiry before paying out, 100 principal tokens at 1.05 are
+	// backed by 95.238096 underlying leaving 4.761904 for the yield token holders
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.redeem_mature_principal(&user, 50).await.unwrap();
+
+	let state = test.state().await;
+	assert_eq!(state.expiry_exchange_rate, rate_bps(10_500));
+	assert_eq!(state.expiry_yield_reserve, 4_761_904);
+	assert_eq!(test.underlying_balance(&user.pubkey()).await, 47_619_047);
+
+	// Later interactions leave the snapshot untouched
+	test.set_clock(test.expiry_date + 10 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_600));
+	test.settle().await.unwrap();
+
+	let state = test.state().await;
+	assert_eq!(state.expiry_exchange_rate, rate_bps(10_500));
+	assert_eq!(state.expiry_yield_reserve, 4_761_904);
+}
+
+#[tokio::test]
+async fn principal_holders_redeem_at_expiry_rate() {
+	let mut test =
+		TestTokenizer::new(PostExpiryYieldRecipient::PrincipalHolders, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.redeem_mature_principal(&user, 50).await.unwrap();
+	assert_eq!(test.underlying_balance(&user.pubkey()).await, 47_619_047);
+
+	// The yield earned after expiry stays with the principal, which keeps redeeming for the
+	// same amount of underlying
+	test.set_clock(test.expiry_date + 10 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_600));
+	test.redeem_mature_principal(&user, 50).await.unwrap();
+	assert_eq!(
+		test.underlying_balance(&user.pubkey()).await,
+		2 * 47_619_047
+	);
+}
+
+#[tokio::test]
+async fn treasury_mode_redeems_at_current_rate_floored_at_expiry_rate() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.redeem_mature_principal(&user, 40).await.unwrap();
+	assert_eq!(test.underlying_balance(&user.pubkey()).await, 38_095_238);
+
+	// Principal is redeemed for one unit of the accounting asset at the current rate
+	test.set_clock(test.expiry_date + 20 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_600));
+	test.redeem_mature_principal(&user, 30).await.unwrap();
+	assert_eq!(
+		test.underlying_balance(&user.pubkey()).await,
+		38_095_238 + 28_301_886
+	);
+
+	// A rate below the expiry rate never pays principal more than it was owed at expiry
+	test.set_clock(test.expiry_date + 30 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_450));
+	test.redeem_mature_principal(&user, 30).await.unwrap();
+	assert_eq!(
+		test.underlying_balance(&user.pubkey()).await,
+		38_095_238 + 28_301_886 + 28_571_428
+	);
+
+	let state = test.state().await;
+	assert_eq!(state.expiry_exchange_rate, rate_bps(10_500));
+	assert_eq!(state.expiry_yield_reserve, 4_761_904);
+}
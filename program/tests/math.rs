@@ -0,0 +1,331 @@
+use sclr_token::math::{
+	div_wad, div_wad_ceil, exp_wad, ln_wad, mul_div, mul_div_ceil, mul_wad, mul_wad_ceil, pow_wad,
+	powi_wad, ui_amount_to_amount, LN_2, WAD,
+};
+
+const W: i128 = WAD as i128;
+
+// Asserts the values differ by no more than one part in 10^15, or by `absolute` for values
+// near zero
+fn assert_close(actual: i128, expected: i128, absolute: i128) {
+	let tolerance = (expected.abs() / 1_000_000_000_000_000).max(absolute);
+
+	assert!(
+		(actual - expected).abs() <= tolerance,
+		"expected {}, got {}, difference {}",
+		expected,
+		actual,
+		actual - expected
+	);
+}
+
+fn exp(x: i128) -> i128 {
+	exp_wad(x).unwrap() as i128
+}
+
+fn ln(x: u128) -> i128 {
+	ln_wad(x).unwrap()
+}
+
+#[test]
+fn mul_div_rounds_down_and_up() {
+	assert_eq!(mul_div(10, 10, 3).unwrap(), 33);
+	assert_eq!(mul_div_ceil(10, 10, 3).unwrap(), 34);
+	assert_eq!(mul_div(10, 10, 4).unwrap(), 25);
+	assert_eq!(mul_div_ceil(10, 10, 4).unwrap(), 25);
+	assert_eq!(mul_div(0, u128::MAX, 1).unwrap(), 0);
+	assert_eq!(mul_div_ceil(0, u128::MAX, 7).unwrap(), 0);
+}
+
+#[test]
+fn mul_div_carries_products_beyond_u128() {
+	assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX).unwrap(), u128::MAX);
+	assert_eq!(
+		mul_div_ceil(u128::MAX, u128::MAX, u128::MAX).unwrap(),
+		u128::MAX
+	);
+	assert_eq!(mul_div(u128::MAX, 3, 6).unwrap(), u128::MAX / 2);
+	assert_eq!(mul_div_ceil(u128::MAX, 3, 6).unwrap(), u128::MAX / 2 + 1);
+	assert_eq!(mul_div(1 << 100, 1 << 100, 1 << 90).unwrap(), 1 << 110);
+	assert_eq!(mul_div_ceil(1 << 100, 1 << 100, 1 << 90).unwrap(), 1 << 110);
+	assert_eq!(mul_div(u128::MAX, WAD, WAD).unwrap(), u128::MAX);
+}
+
+#[test]
+fn mul_div_fails_on_overflow_and_zero_denominator() {
+	assert!(mul_div(u128::MAX, 2, 1).is_err());
+	assert!(mul_div_ceil(u128::MAX, 2, 1).is_err());
+	assert!(mul_div_ceil(u128::MAX, u128::MAX, u128::MAX - 1).is_err());
+	assert!(mul_div(1, 1, 0).is_err());
+	assert!(mul_div_ceil(1, 1, 0).is_err());
+}
+
+#[test]
+fn wad_multiplication_and_division() {
+	assert_eq!(mul_wad(2 * WAD, 3 * WAD).unwrap(), 6 * WAD);
+	assert_eq!(mul_wad(WAD / 2, WAD / 4).unwrap(), WAD / 8);
+	assert_eq!(div_wad(6 * WAD, 3 * WAD).unwrap(), 2 * WAD);
+	assert_eq!(div_wad(WAD, 4 * WAD).unwrap(), WAD / 4);
+
+	// One third cannot be represented exactly, rounding decides the last unit
+	assert_eq!(div_wad(WAD, 3 * WAD).unwrap(), 333_333_333_333_333_333);
+	assert_eq!(div_wad_ceil(WAD, 3 * WAD).unwrap(), 333_333_333_333_333_334);
+	assert_eq!(mul_wad(3, WAD / 2).unwrap(), 1);
+	assert_eq!(mul_wad_ceil(3, WAD / 2).unwrap(), 2);
+
+	// Values whose product exceeds a `u128` before scaling
+	assert_eq!(
+		mul_wad(1_000_000 * WAD, 1_000_000 * WAD).unwrap(),
+		1_000_000_000_000 * WAD
+	);
+	assert_eq!(
+		div_wad(1_000_000_000 * WAD, WAD / 1_000).unwrap(),
+		1_000_000_000_000 * WAD
+	);
+
+	assert!(mul_wad(u128::MAX, 2 * WAD).is_err());
+	assert!(div_wad(WAD, 0).is_err());
+	assert!(div_wad_ceil(WAD, 0).is_err());
+}
+
+#[test]
+fn exp_matches_known_values() {
+	assert_eq!(exp_wad(0).unwrap(), WAD);
+	assert_close(exp(W), 2_718_281_828_459_045_235, 10);
+	assert_close(exp(-W), 367_879_441_171_442_321, 10);
+	assert_close(exp(W / 2), 1_648_721_270_700_128_146, 10);
+	assert_close(exp(LN_2 as i128), 2 * W, 10);
+	assert_close(exp(10 * W), 22_026_465_794_806_716_516_957, 10);
+	assert_close(exp(-10 * W), 45_399_929_762_484, 10);
+	assert_close(
+		exp(40 * W),
+		235_385_266_837_019_985_407_899_910_749_034_804,
+		10,
+	);
+	assert_close(exp(-40 * W), 4, 1);
+}
+
+#[test]
+fn exp_bounds() {
+	// e^47.276... is the largest result that fits in a `u128`
+	assert!(exp_wad(47 * W).is_ok());
+	assert!(exp_wad(47_276_307_437_780_000_000).is_ok());
+	assert!(exp_wad(47_276_307_437_790_000_000).is_err());
+	assert!(exp_wad(48 * W).is_err());
+	assert!(exp_wad(i128::MAX).is_err());
+
+	// Results below one unit of precision round down to zero
+	assert!(exp_wad(-41 * W).unwrap() > 0);
+	assert_eq!(exp_wad(-42 * W).unwrap(), 0);
+	assert_eq!(exp_wad(i128::MIN).unwrap(), 0);
+}
+
+#[test]
+fn exp_is_increasing_and_multiplicative() {
+	// Below e^-30 neighbouring results round to the same few units
+	let points = (-300..=460).map(|step| step * W / 10).collect::<Vec<_>>();
+
+	for pair in points.windows(2) {
+		assert!(exp(pair[0]) < exp(pair[1]));
+	}
+
+	// e^(a + b) = e^a * e^b
+	for (a, b) in [
+		(W, W),
+		(W / 3, 2 * W),
+		(-5 * W, 7 * W / 2),
+		(12 * W, -W / 7),
+	] {
+		assert_close(
+			exp(a + b),
+			mul_wad(exp(a) as u128, exp(b) as u128).unwrap() as i128,
+			10,
+		);
+	}
+}
+
+#[test]
+fn ln_matches_known_values() {
+	assert_eq!(ln_wad(WAD).unwrap(), 0);
+	assert_close(ln(2 * WAD), LN_2 as i128, 10);
+	assert_close(ln(WAD / 2), -693_147_180_559_945_310, 10);
+	assert_close(ln(10 * WAD), 2_302_585_092_994_045_684, 10);
+	assert_close(ln(2_718_281_828_459_045_235), W, 10);
+	assert_close(ln(100 * WAD * WAD), 46_051_701_859_880_913_680, 10);
+	assert_close(ln(1), -41_446_531_673_892_822_313, 10);
+	assert_close(ln(u128::MAX), 47_276_307_437_780_177_293, 10);
+
+	assert!(ln_wad(0).is_err());
+}
+
+#[test]
+fn ln_is_increasing_and_additive() {
+	let points = (1..=1_000).map(|step| step * WAD / 100).collect::<Vec<_>>();
+
+	for pair in points.windows(2) {
+		assert!(ln(pair[0]) < ln(pair[1]));
+	}
+
+	// ln(a * b) = ln(a) + ln(b)
+	for (a, b) in [
+		(3 * WAD, 7 * WAD),
+		(WAD / 9, 5 * WAD),
+		(123_456 * WAD, WAD / 1_000),
+	] {
+		assert_close(ln(mul_wad(a, b).unwrap()), ln(a) + ln(b), 10);
+	}
+
+	// Every power of two shifts the normalisation of the argument
+	for shift in 0..128 {
+		let x = 1u128 << shift;
+		let expected = (x as f64 / WAD as f64).ln();
+
+		assert!((ln(x) as f64 / WAD as f64 - expected).abs() < 1e-12);
+	}
+}
+
+#[test]
+fn exp_and_ln_invert_each_other() {
+	// Below e^-6 the result has too few digits to recover the exponent within the tolerance
+	for step in -60..=470 {
+		let x = step * W / 10;
+		assert_close(ln(exp(x) as u128), x, 1_000);
+	}
+
+	for x in [
+		1,
+		1_000,
+		WAD / 3,
+		WAD,
+		7 * WAD / 3,
+		1_000_000 * WAD,
+		u128::MAX / 2,
+	] {
+		assert_close(exp(ln(x)), x as i128, 1);
+	}
+}
+
+#[test]
+fn pow_matches_known_values() {
+	assert_close(
+		pow_wad(2 * WAD, W / 2).unwrap() as i128,
+		1_414_213_562_373_095_048,
+		10,
+	);
+	assert_close(
+		pow_wad(1_050_000_000_000_000_000, 30 * W).unwrap() as i128,
+		4_321_942_375_150_662_009,
+		100,
+	);
+	assert_close(
+		pow_wad(1_500_000_000_000_000_000, 5 * W / 2).unwrap() as i128,
+		2_755_675_960_631_075_360,
+		100,
+	);
+	assert_close(
+		pow_wad(900_000_000_000_000_000, -37 * W / 10).unwrap() as i128,
+		1_476_735_499_462_373_671,
+		100,
+	);
+	assert_close(pow_wad(4 * WAD, -W / 2).unwrap() as i128, W / 2, 10);
+}
+
+#[test]
+fn pow_edge_cases() {
+	assert_eq!(pow_wad(0, 0).unwrap(), WAD);
+	assert_eq!(pow_wad(12_345, 0).unwrap(), WAD);
+	assert_eq!(pow_wad(0, W).unwrap(), 0);
+	assert!(pow_wad(0, -W).is_err());
+	assert_close(pow_wad(WAD, 1_000 * W).unwrap() as i128, W, 0);
+	assert_close(pow_wad(7 * WAD, W).unwrap() as i128, 7 * W, 10);
+	assert!(pow_wad(10 * WAD, 100 * W).is_err());
+}
+
+#[test]
+fn powi_matches_known_values() {
+	assert_eq!(powi_wad(2 * WAD, 10).unwrap(), 1_024 * WAD);
+	assert_eq!(
+		powi_wad(1_100_000_000_000_000_000, 2).unwrap(),
+		1_210_000_000_000_000_000
+	);
+	assert_eq!(powi_wad(WAD / 2, 3).unwrap(), WAD / 8);
+	assert_eq!(powi_wad(WAD, u64::MAX).unwrap(), WAD);
+	assert_eq!(powi_wad(0, 5).unwrap(), 0);
+	assert_eq!(powi_wad(0, 0).unwrap(), WAD);
+	assert_eq!(powi_wad(123 * WAD, 0).unwrap(), WAD);
+	assert_eq!(powi_wad(123 * WAD, 1).unwrap(), 123 * WAD);
+
+	assert_close(
+		powi_wad(1_050_000_000_000_000_000, 30).unwrap() as i128,
+		4_321_942_375_150_662_009,
+		100,
+	);
+	// A daily rate of one basis point compounded over a year
+	assert_close(
+		powi_wad(1_000_100_000_000_000_000, 365).unwrap() as i128,
+		1_037_172_411_302_551_929,
+		1_000,
+	);
+
+	assert!(powi_wad(10 * WAD, 21).is_err());
+}
+
+#[test]
+fn powi_agrees_with_pow() {
+	for base in [
+		WAD / 10,
+		999 * WAD / 1_000,
+		WAD,
+		1_001 * WAD / 1_000,
+		3 * WAD,
+	] {
+		for exponent in [0u64, 1, 2, 7, 16, 33] {
+			assert_close(
+				powi_wad(base, exponent).unwrap() as i128,
+				pow_wad(base, exponent as i128 * W).unwrap() as i128,
+				100,
+			);
+		}
+	}
+}
+
+#[test]
+fn continuous_accrual_over_a_year() {
+	// An index accruing 5% a year continuously grows by e^0.05 and decays back by e^-0.05
+	let rate = W / 20;
+	let growth = exp(rate);
+	let decay = exp(-rate);
+
+	assert_close(growth, 1_051_271_096_376_024_039, 10);
+	assert_close(
+		mul_wad(growth as u128, decay as u128).unwrap() as i128,
+		W,
+		10,
+	);
+
+	// Accruing every second over a year approaches continuous accrual
+	let seconds_per_year = 31_536_000;
+	let per_second = WAD + (rate as u128) / seconds_per_year as u128;
+	let compounded = powi_wad(per_second, seconds_per_year).unwrap() as i128;
+	assert!((compounded - growth).abs() < W / 1_000_000_000);
+}
+
+#[test]
+fn ui_amounts_convert_to_base_units() {
+	assert_eq!(ui_amount_to_amount(5, 6).unwrap(), 5_000_000);
+	assert_eq!(ui_amount_to_amount(0, 6).unwrap(), 0);
+	assert_eq!(ui_amount_to_amount(7, 0).unwrap(), 7);
+	assert_eq!(
+		ui_amount_to_amount(1, 19).unwrap(),
+		10_000_000_000_000_000_000
+	);
+
+	// Amounts beyond the precision of an `f64` stay exact
+	assert_eq!(
+		ui_amount_to_amount(9_007_199_254_740, 6).unwrap(),
+		9_007_199_254_740_000_000
+	);
+
+	assert!(ui_amount_to_amount(u64::MAX, 6).is_err());
+	assert!(ui_amount_to_amount(1, 20).is_err());
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use {
+	common::{stake_pool_data_with_balances, STAKE_POOL_SIZE},
+	sclr_token::{
+		error::TokenizerError,
+		math::WAD,
+		rate::{check_rate_change, exchange_rate_from_data, MIN_RATE_CHANGE_WINDOW, SECONDS_PER_YEAR},
+	},
+	solana_program::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+fn out_of_bounds() -> Result<(), ProgramError> {
+	Err(TokenizerError::ExchangeRateOutOfBounds.into())
+}
+
+#[test]
+fn rate_increase_is_bounded_by_annual_growth() {
+	let year = SECONDS_PER_YEAR as i64;
+
+	assert_eq!(check_rate_change(WAD, WAD * 12 / 10, year), Ok(()));
+	assert_eq!(check_rate_change(WAD, WAD * 12 / 10 + 1, year), out_of_bounds());
+
+	// Half a year allows half the growth
+	assert_eq!(check_rate_change(WAD, WAD * 11 / 10, year / 2), Ok(()));
+	assert_eq!(check_rate_change(WAD, WAD * 11 / 10 + 1, year / 2), out_of_bounds());
+}
+
+#[test]
+fn rate_increase_is_measured_over_at_least_the_minimum_window() {
+	// 20% a year over two days
+	let max_increase = 1_095_890_410_958_904;
+
+	for elapsed in [0, 1, MIN_RATE_CHANGE_WINDOW] {
+		assert_eq!(check_rate_change(WAD, WAD + max_increase, elapsed), Ok(()));
+		assert_eq!(
+			check_rate_change(WAD, WAD + max_increase + 1, elapsed),
+			out_of_bounds()
+		);
+	}
+}
+
+#[test]
+fn rate_decrease_is_bounded_per_window() {
+	assert_eq!(check_rate_change(WAD, WAD, 0), Ok(()));
+	assert_eq!(check_rate_change(WAD, WAD * 99 / 100, 0), Ok(()));
+	assert_eq!(check_rate_change(WAD, WAD * 99 / 100 - 1, 0), out_of_bounds());
+
+	// A slash larger than the bound is accepted once enough time has passed
+	let elapsed = 10 * MIN_RATE_CHANGE_WINDOW;
+	assert_eq!(check_rate_change(WAD, WAD * 95 / 100, 0), out_of_bounds());
+	assert_eq!(check_rate_change(WAD, WAD * 90 / 100, elapsed), Ok(()));
+	assert_eq!(check_rate_change(WAD, WAD * 90 / 100 - 1, elapsed), out_of_bounds());
+}
+
+#[test]
+fn exchange_rate_is_total_lamports_per_pool_token() {
+	let pool_mint = Pubkey::new_unique();
+
+	let data = stake_pool_data_with_balances(&pool_mint, 1_050, 1_000);
+	assert_eq!(exchange_rate_from_data(&data, &pool_mint), Ok(WAD * 105 / 100));
+
+	let data = stake_pool_data_with_balances(&pool_mint, 1, 3);
+	assert_eq!(exchange_rate_from_data(&data, &pool_mint), Ok(WAD / 3));
+
+	// An empty pool is valued at par
+	let data = stake_pool_data_with_balances(&pool_mint, 0, 0);
+	assert_eq!(exchange_rate_from_data(&data, &pool_mint), Ok(WAD));
+}
+
+#[test]
+fn exchange_rate_rejects_invalid_stake_pools() {
+	let pool_mint = Pubkey::new_unique();
+	let data = stake_pool_data_with_balances(&pool_mint, 1_050, 1_000);
+
+	assert_eq!(
+		exchange_rate_from_data(&data[..STAKE_POOL_SIZE - 1], &pool_mint),
+		Err(ProgramError::InvalidAccountData)
+	);
+
+	let mut uninitialized = data.clone();
+	uninitialized[0] = 0;
+	assert_eq!(
+		exchange_rate_from_data(&uninitialized, &pool_mint),
+		Err(ProgramError::InvalidAccountData)
+	);
+
+	assert_eq!(
+		exchange_rate_from_data(&data, &Pubkey::new_unique()),
+		Err(TokenizerError::IncorrectUnderlyingMintAddress.into())
+	);
+
+	let data = stake_pool_data_with_balances(&pool_mint, 0, 1_000);
+	assert_eq!(
+		exchange_rate_from_data(&data, &pool_mint),
+		Err(TokenizerError::InvalidExchangeRate.into())
+	);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use {
+	common::{rate_bps, TestTokenizer, DAY, ONE},
+	sclr_token::{error::TokenizerError, state::PostExpiryYieldRecipient},
+	solana_sdk::{
+		instruction::InstructionError, signature::Signer, transaction::TransactionError,
+	},
+};
+
+const GRACE_PERIOD: i64 = 30 * DAY;
+
+#[tokio::test]
+async fn settle_sweeps_post_expiry_yield_to_treasury() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	// Settling at the expiry rate leaves nothing for the treasury
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.settle().await.unwrap();
+	assert_eq!(test.treasury_balance().await, 0);
+	assert_eq!(test.vault_balance().await, 100 * ONE);
+	assert_eq!(test.state().await.expiry_yield_reserve, 4_761_904);
+
+	test.redeem_mature_principal(&user, 50).await.unwrap();
+	assert_eq!(test.underlying_balance(&user.pubkey()).await, 47_619_047);
+	assert_eq!(test.vault_balance().await, 52_380_953);
+
+	// At 1.06 the remaining 50 principal tokens are backed by 47.169812 underlying, the
+	// treasury receives what is left over after the yield reserve
+	test.set_clock(test.expiry_date + 20 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_600));
+	test.settle().await.unwrap();
+	assert_eq!(test.treasury_balance().await, 449_237);
+	assert_eq!(test.vault_balance().await, 51_931_716);
+
+	// Settling again at the same rate sweeps nothing
+	test.settle().await.unwrap();
+	assert_eq!(test.treasury_balance().await, 449_237);
+
+	// Principal and yield are still paid in full
+	test.redeem_mature_principal(&user, 50).await.unwrap();
+	test.claim_yield(&user, 100).await.unwrap();
+	assert_eq!(
+		test.underlying_balance(&user.pubkey()).await,
+		47_619_047 + 47_169_811 + 4_761_904
+	);
+	assert_eq!(test.vault_balance().await, 1);
+}
+
+#[tokio::test]
+async fn settle_keeps_post_expiry_yield_for_principal_holders() {
+	let mut test =
+		TestTokenizer::new(PostExpiryYieldRecipient::PrincipalHolders, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.settle().await.unwrap();
+
+	test.set_clock(test.expiry_date + 10 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_600));
+	test.settle().await.unwrap();
+
+	assert_eq!(test.treasury_balance().await, 0);
+	assert_eq!(test.vault_balance().await, 100 * ONE);
+}
+
+#[tokio::test]
+async fn settle_fails_before_expiry() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+
+	let error = test.settle().await.unwrap_err().unwrap();
+
+	assert_eq!(
+		error,
+		TransactionError::InstructionError(
+			0,
+			InstructionError::Custom(TokenizerError::ExpiryDateNotElapsed as u32)
+		)
+	);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use {
+	common::{rate_bps, TestTokenizer, DAY, ONE},
+	sclr_token::{error::TokenizerError, state::PostExpiryYieldRecipient},
+	solana_sdk::{
+		instruction::InstructionError, program_option::COption, program_pack::Pack,
+		signature::Signer, transaction::TransactionError,
+	},
+	spl_token::state::Mint,
+};
+
+const GRACE_PERIOD: i64 = 30 * DAY;
+
+// The fee of a transaction signed only by the payer
+const FEE: u64 = 5_000;
+
+#[tokio::test]
+async fn terminate_closes_the_tokenizer_after_the_sweep() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	test.set_clock(test.expiry_date + GRACE_PERIOD + DAY).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.sweep_to_escrow().await.unwrap();
+
+	let tokenizer = test.tokenizer;
+	let state = test.state().await;
+	let authority = test.context.payer.pubkey();
+	let tokenizer_rent = test.account(&tokenizer).await.unwrap().lamports;
+	let vault_rent = test.account(&state.underlying_vault).await.unwrap().lamports;
+	let authority_balance = test.account(&authority).await.unwrap().lamports;
+
+	test.terminate().await.unwrap();
+
+	// The rent of the tokenizer and its vault reaches the authority
+	assert!(test.account(&tokenizer).await.is_none());
+	assert!(test.account(&state.underlying_vault).await.is_none());
+	assert_eq!(
+		test.account(&authority).await.unwrap().lamports,
+		authority_balance + tokenizer_rent + vault_rent - FEE
+	);
+
+	// spl-token mints cannot be closed, the tokenizer can no longer mint against them
+	for mint in [state.principal_token_mint, state.yield_token_mint] {
+		let mint = Mint::unpack(&test.account(&mint).await.unwrap().data).unwrap();
+		assert_eq!(mint.mint_authority, COption::None);
+	}
+
+	// Principal and yield tokens are still redeemed from the claims escrow
+	test.redeem_escrow_principal(&user, 100).await.unwrap();
+	test.claim_escrow_yield(&user, 100).await.unwrap();
+	assert_eq!(
+		test.underlying_balance(&user.pubkey()).await,
+		95_238_095 + 4_761_904
+	);
+}
+
+#[tokio::test]
+async fn terminate_requires_an_empty_vault() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	test.set_clock(test.expiry_date + GRACE_PERIOD + DAY).await;
+	let tokenizer = test.tokenizer;
+
+	assert_eq!(
+		test.terminate().await.unwrap_err().unwrap(),
+		TransactionError::InstructionError(
+			0,
+			InstructionError::Custom(TokenizerError::VaultNotEmpty as u32)
+		)
+	);
+	assert!(test.account(&tokenizer).await.is_some());
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use {
+	common::{rate_bps, TestTokenizer, DAY, ONE, START},
+	sclr_token::{
+		error::TokenizerError,
+		instruction::{self, ResetYieldIndexAccounts},
+		math::WAD,
+		state::PostExpiryYieldRecipient,
+	},
+	solana_sdk::{
+		instruction::InstructionError,
+		signature::{Keypair, Signer},
+		transaction::TransactionError,
+	},
+};
+
+const GRACE_PERIOD: i64 = 30 * DAY;
+
+fn tokenizer_error(error: TokenizerError) -> TransactionError {
+	TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+#[tokio::test]
+async fn tokenize_updates_yield_index() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+
+	let state = test.state().await;
+	assert_eq!(state.yield_index, WAD);
+	assert_eq!(state.last_update_timestamp, START);
+
+	test.set_clock(START + 30 * DAY).await;
+	test.set_exchange_rate(rate_bps(10_100));
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+
+	let state = test.state().await;
+	assert_eq!(state.yield_index, rate_bps(10_100));
+	assert_eq!(state.last_update_timestamp, START + 30 * DAY);
+}
+
+#[tokio::test]
+async fn rate_jump_is_rejected() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+
+	test.set_clock(START + DAY).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	let error = test.deposit_and_tokenize(&user, 100).await.unwrap_err().unwrap();
+
+	assert_eq!(error, tokenizer_error(TokenizerError::ExchangeRateOutOfBounds));
+	assert_eq!(test.state().await.yield_index, WAD);
+}
+
+#[tokio::test]
+async fn slash_is_accepted_once_enough_time_has_passed() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+
+	test.set_clock(START + DAY).await;
+	test.set_exchange_rate(rate_bps(9_500));
+	let error = test.checkpoint().await.unwrap_err().unwrap();
+	assert_eq!(error, tokenizer_error(TokenizerError::ExchangeRateOutOfBounds));
+
+	// Five windows since the last update allow a 5% decrease
+	test.set_clock(START + 10 * DAY).await;
+	test.checkpoint().await.unwrap();
+
+	let state = test.state().await;
+	assert_eq!(state.yield_index, rate_bps(9_500));
+	assert_eq!(state.last_update_timestamp, START + 10 * DAY);
+}
+
+#[tokio::test]
+async fn authority_resets_yield_index() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let user = test.add_user(100 * ONE);
+
+	test.set_clock(START + DAY).await;
+	test.set_exchange_rate(rate_bps(5_000));
+	let error = test.deposit_and_tokenize(&user, 100).await.unwrap_err().unwrap();
+	assert_eq!(error, tokenizer_error(TokenizerError::ExchangeRateOutOfBounds));
+
+	test.reset_yield_index().await.unwrap();
+	assert_eq!(test.state().await.yield_index, rate_bps(5_000));
+
+	// Deposits resume against the new index
+	test.deposit_and_tokenize(&user, 100).await.unwrap();
+}
+
+#[tokio::test]
+async fn reset_yield_index_fails_for_other_signers() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+	let other = Keypair::new();
+
+	let state = test.state().await;
+	let accounts =
+		ResetYieldIndexAccounts::resolve(&test.tokenizer, &other.pubkey(), &state.stake_pool);
+	let instruction = instruction::reset_yield_index(&accounts).unwrap();
+
+	let error = test
+		.process(&[instruction], &[&other])
+		.await
+		.unwrap_err()
+		.unwrap();
+
+	assert_eq!(error, tokenizer_error(TokenizerError::Unauthorised));
+}
+
+#[tokio::test]
+async fn checkpoint_after_expiry() {
+	let mut test = TestTokenizer::new(PostExpiryYieldRecipient::Treasury, GRACE_PERIOD).await;
+
+	test.set_clock(test.expiry_date + 1).await;
+	test.set_exchange_rate(rate_bps(10_500));
+	test.checkpoint().await.unwrap();
+
+	let state = test.state().await;
+	assert_eq!(state.yield_index, rate_bps(10_500));
+	assert_eq!(state.last_update_timestamp, test.expiry_date + 1);
+}
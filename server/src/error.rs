@@ -0,0 +1,62 @@
+use {
+	axum::{
+		http::StatusCode,
+		response::{IntoResponse, Response},
+		Json,
+	},
+	sclr_client::error::TokenizerClientError,
+	serde::Serialize,
+	solana_client::client_error::ClientError,
+	solana_program::pubkey::Pubkey,
+	thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+	#[error("Invalid Address: {0}")]
+	InvalidAddress(String),
+	#[error("Tokenizer Not Found: {0}")]
+	TokenizerNotFound(Pubkey),
+	#[error("RPC Error: {0}")]
+	Rpc(Box<ClientError>),
+	#[error("{0}")]
+	Client(#[from] TokenizerClientError),
+	#[error("Unable to read the cluster clock")]
+	InvalidClock,
+}
+
+impl From<ClientError> for ApiError {
+	fn from(err: ClientError) -> Self {
+		ApiError::Rpc(Box::new(err))
+	}
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+	error: String,
+}
+
+impl IntoResponse for ApiError {
+	fn into_response(self) -> Response {
+		let status = match self {
+			ApiError::InvalidAddress(_) => StatusCode::BAD_REQUEST,
+			ApiError::TokenizerNotFound(_)
+			| ApiError::Client(TokenizerClientError::AccountNotFound(_)) => StatusCode::NOT_FOUND,
+			// The cluster could not be reached or answered with an error
+			ApiError::Rpc(_) | ApiError::Client(TokenizerClientError::Rpc(_)) => {
+				StatusCode::BAD_GATEWAY
+			}
+			_ => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		(
+			status,
+			Json(ErrorBody {
+				error: self.to_string(),
+			}),
+		)
+			.into_response()
+	}
+}
+
+pub type Result<T> = std::result::Result<T, ApiError>;
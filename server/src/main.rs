@@ -0,0 +1,148 @@
+mod error;
+mod routes;
+
+use {
+	anyhow::{anyhow, Result},
+	axum::{routing::get, Router},
+	clap::Parser,
+	solana_cli_config::ConfigInput,
+	solana_client::nonblocking::rpc_client::RpcClient,
+	solana_sdk::commitment_config::CommitmentConfig,
+	std::{net::SocketAddr, sync::Arc},
+	tokio::net::TcpListener,
+};
+
+/// Serves the tokenizers of the cluster and the positions of wallets as JSON over HTTP, every
+/// request is answered from the accounts of the cluster at the confirmed commitment
+#[derive(Parser, Debug)]
+struct Cli {
+	/// Path to the Solana CLI configuration file
+	#[arg(short, long)]
+	config: Option<String>,
+	/// RPC endpoint URL or moniker: localhost, devnet, testnet or mainnet-beta
+	#[arg(short, long)]
+	rpc: Option<String>,
+	/// Address the service listens on
+	#[arg(short, long, default_value = "127.0.0.1:8080")]
+	bind: SocketAddr,
+}
+
+pub struct AppState {
+	pub rpc_client: RpcClient,
+}
+
+fn router(state: Arc<AppState>) -> Router {
+	Router::new()
+		.route("/tokenizers", get(routes::list_tokenizers))
+		.route("/tokenizers/:address", get(routes::show_tokenizer))
+		.route("/tokenizers/:address/apy", get(routes::show_apy))
+		.route("/positions/:wallet", get(routes::list_positions))
+		.with_state(state)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+	let args = Cli::parse();
+
+	let solana_config_file = if let Some(ref config) = args.config {
+		solana_cli_config::Config::load(config)
+			.map_err(|err| anyhow!("Unable to load config file {}: {}", config, err))?
+	} else if let Some(ref config) = *solana_cli_config::CONFIG_FILE {
+		solana_cli_config::Config::load(config).unwrap_or_default()
+	} else {
+		solana_cli_config::Config::default()
+	};
+
+	// Command line flags take precedence over the config file, monikers are resolved to URLs
+	let (_, json_rpc_url) = ConfigInput::compute_json_rpc_url_setting(
+		args.rpc.as_deref().unwrap_or_default(),
+		&solana_config_file.json_rpc_url,
+	);
+
+	let state = Arc::new(AppState {
+		rpc_client: RpcClient::new_with_commitment(
+			json_rpc_url.clone(),
+			CommitmentConfig::confirmed(),
+		),
+	});
+
+	let listener = TcpListener::bind(args.bind)
+		.await
+		.map_err(|err| anyhow!("Unable to bind {}: {}", args.bind, err))?;
+	println!("Serving {} on http://{}", json_rpc_url, args.bind);
+
+	axum::serve(listener, router(state))
+		.await
+		.map_err(|err| anyhow!("Unable to serve requests: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		axum::{
+			body::Body,
+			http::{Request, StatusCode},
+		},
+		http_body_util::BodyExt,
+		serde_json::{json, Value},
+		solana_sdk::pubkey::Pubkey,
+		tower::ServiceExt,
+	};
+
+	// Requests a route of a service whose RPC requests all succeed or all fail
+	async fn get(rpc: &str, uri: &str) -> (StatusCode, Value) {
+		let state = Arc::new(AppState {
+			rpc_client: RpcClient::new_mock(rpc.to_string()),
+		});
+
+		let response = router(state)
+			.oneshot(Request::get(uri).body(Body::empty()).unwrap())
+			.await
+			.unwrap();
+		let status = response.status();
+		let body = response.into_body().collect().await.unwrap().to_bytes();
+
+		(status, serde_json::from_slice(&body).unwrap())
+	}
+
+	#[tokio::test]
+	async fn invalid_addresses_are_bad_requests() {
+		for uri in ["/tokenizers/invalid", "/tokenizers/invalid/apy", "/positions/invalid"] {
+			assert_eq!(
+				get("succeeds", uri).await,
+				(
+					StatusCode::BAD_REQUEST,
+					json!({ "error": "Invalid Address: invalid" })
+				)
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn missing_tokenizers_are_not_found() {
+		let address = Pubkey::new_unique();
+
+		for uri in [
+			format!("/tokenizers/{}", address),
+			format!("/tokenizers/{}/apy", address),
+		] {
+			assert_eq!(
+				get("succeeds", &uri).await,
+				(
+					StatusCode::NOT_FOUND,
+					json!({ "error": format!("Tokenizer Not Found: {}", address) })
+				)
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn rpc_failures_are_bad_gateways() {
+		let (status, _) = get("fails", "/tokenizers").await;
+		assert_eq!(status, StatusCode::BAD_GATEWAY);
+
+		let (status, _) = get("fails", &format!("/positions/{}", Pubkey::new_unique())).await;
+		assert_eq!(status, StatusCode::BAD_GATEWAY);
+	}
+}
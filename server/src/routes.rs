@@ -0,0 +1,483 @@
+use {
+	crate::{
+		error::{ApiError, Result},
+		AppState,
+	},
+	axum::{
+		extract::{Path, Query, State},
+		Json,
+	},
+	borsh::BorshDeserialize,
+	futures::future::try_join_all,
+	sclr_client::{
+		deserialize_account,
+		error::TokenizerClientError,
+		position::{
+			redeemable_amounts, token_balances_by_mint, TokenizerBalances, TokenizerStatus,
+		},
+	},
+	sclr_token::{
+		get_escrow_address, get_principal_mint_address, get_yield_mint_address,
+		rate::{exchange_rate_from_data, BPS, SECONDS_PER_YEAR},
+		state::{TokenizerState, AUTHORITY_OFFSET, STATE_SIZE, UNDERLYING_MINT_OFFSET},
+	},
+	serde::{Deserialize, Serialize},
+	solana_account_decoder::UiAccountEncoding,
+	solana_client::{
+		rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+		rpc_filter::{Memcmp, RpcFilterType},
+		rpc_request::{TokenAccountsFilter, MAX_MULTIPLE_ACCOUNTS},
+	},
+	solana_program::{clock::Clock, pubkey::Pubkey, sysvar},
+	solana_sdk::account::{from_account, Account},
+	std::{str::FromStr, sync::Arc},
+};
+
+#[derive(Debug, Serialize)]
+pub struct TokenizerSummary {
+	pub address: String,
+	pub authority: String,
+	pub underlying_mint: String,
+	pub principal_token_mint: String,
+	pub yield_token_mint: String,
+	pub stake_pool: String,
+	pub expiry_date: i64,
+	pub seconds_to_maturity: i64,
+	pub fixed_apy: u64,
+	pub status: TokenizerStatus,
+}
+
+impl TokenizerSummary {
+	fn new(address: &Pubkey, state: &TokenizerState, timestamp: i64) -> Self {
+		TokenizerSummary {
+			address: address.to_string(),
+			authority: state.authority.to_string(),
+			underlying_mint: state.underlying_mint.to_string(),
+			principal_token_mint: state.principal_token_mint.to_string(),
+			yield_token_mint: state.yield_token_mint.to_string(),
+			stake_pool: state.stake_pool.to_string(),
+			expiry_date: state.expiry_date,
+			seconds_to_maturity: state.expiry_date.saturating_sub(timestamp).max(0),
+			fixed_apy: state.fixed_apy,
+			status: TokenizerStatus::from_state(state, timestamp),
+		}
+	}
+}
+
+/// The tokenizer state along with the balances its tokens are valued against, rates scaled
+/// by `WAD` are serialized as decimal strings as they do not fit in a JSON number
+#[derive(Debug, Serialize)]
+pub struct TokenizerDetails {
+	#[serde(flatten)]
+	pub summary: TokenizerSummary,
+	pub underlying_vault: String,
+	pub treasury: String,
+	pub escrow: String,
+	/// Whether the underlying has been swept to the claims escrow
+	pub swept_to_escrow: bool,
+	pub vault_balance: u64,
+	pub principal_supply: u64,
+	pub yield_supply: u64,
+	pub grace_period: i64,
+	pub post_expiry_yield: String,
+	pub exchange_rate: String,
+	pub yield_index: String,
+	pub last_update_timestamp: i64,
+	/// Zero until the expiry has been settled
+	pub expiry_exchange_rate: String,
+	pub expiry_yield_reserve: u64,
+}
+
+/// Principal and yield tokens held by a wallet and the underlying they can be redeemed for
+#[derive(Debug, Serialize)]
+pub struct Position {
+	pub tokenizer: String,
+	pub underlying_mint: String,
+	pub principal_balance: u64,
+	pub yield_balance: u64,
+	/// Underlying received for the principal tokens, before expiry only principal paired
+	/// with yield tokens can be redeemed
+	pub redeemable_principal: u64,
+	/// Underlying received for claiming the yield tokens
+	pub claimable_yield: u64,
+	pub seconds_to_maturity: i64,
+	pub status: TokenizerStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenizerApy {
+	pub address: String,
+	/// The fixed APY the tokenizer was initialized with
+	pub fixed_apy: u64,
+	/// Growth of the stake pool exchange rate since the last checkpoint annualised without
+	/// compounding, in basis points. Empty if no time has elapsed since the checkpoint
+	pub variable_apy_bps: Option<i64>,
+	pub exchange_rate: String,
+	pub yield_index: String,
+	pub last_update_timestamp: i64,
+	pub timestamp: i64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusFilter {
+	Active,
+	/// Any tokenizer past its expiry date
+	Expired,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenizerQuery {
+	pub underlying_mint: Option<String>,
+	pub authority: Option<String>,
+	pub status: Option<StatusFilter>,
+}
+
+fn parse_address(address: &str) -> Result<Pubkey> {
+	Pubkey::from_str(address).map_err(|_| ApiError::InvalidAddress(address.to_string()))
+}
+
+// Returns the unix timestamp of the cluster, the time the program compares the expiry against
+async fn get_timestamp(state: &AppState) -> Result<i64> {
+	let account = state.rpc_client.get_account(&sysvar::clock::id()).await?;
+	let clock = from_account::<Clock, _>(&account).ok_or(ApiError::InvalidClock)?;
+
+	Ok(clock.unix_timestamp)
+}
+
+// Annualises the growth of the exchange rate since the last checkpoint without compounding,
+// in basis points
+fn variable_apy_bps(yield_index: u128, exchange_rate: u128, elapsed: i64) -> Option<i64> {
+	if yield_index == 0 || elapsed <= 0 {
+		return None;
+	}
+
+	let growth = (exchange_rate as i128).checked_sub(yield_index as i128)?;
+	let apy = growth
+		.checked_mul((BPS * SECONDS_PER_YEAR) as i128)?
+		.checked_div((yield_index as i128).checked_mul(elapsed as i128)?)?;
+
+	i64::try_from(apy).ok()
+}
+
+async fn get_program_accounts(
+	state: &AppState,
+	program_id: &Pubkey,
+	filters: Vec<RpcFilterType>,
+) -> Result<Vec<(Pubkey, Account)>> {
+	Ok(state
+		.rpc_client
+		.get_program_accounts_with_config(
+			program_id,
+			RpcProgramAccountsConfig {
+				filters: Some(filters),
+				account_config: RpcAccountInfoConfig {
+					encoding: Some(UiAccountEncoding::Base64),
+					..RpcAccountInfoConfig::default()
+				},
+				..RpcProgramAccountsConfig::default()
+			},
+		)
+		.await?)
+}
+
+// Fetches every tokenizer matching the filters, in addition to the tokenizer state size
+async fn get_tokenizers(
+	state: &AppState,
+	mut filters: Vec<RpcFilterType>,
+) -> Result<Vec<(Pubkey, TokenizerState)>> {
+	filters.push(RpcFilterType::DataSize(STATE_SIZE as u64));
+
+	Ok(get_program_accounts(state, &sclr_token::id(), filters)
+		.await?
+		.into_iter()
+		.filter_map(|(address, account)| {
+			TokenizerState::try_from_slice(&account.data)
+				.ok()
+				.map(|tokenizer_state| (address, tokenizer_state))
+		})
+		.collect())
+}
+
+async fn get_tokenizer(state: &AppState, address: &Pubkey) -> Result<TokenizerState> {
+	let account = state
+		.rpc_client
+		.get_account_with_commitment(address, state.rpc_client.commitment())
+		.await?
+		.value
+		.ok_or(ApiError::TokenizerNotFound(*address))?;
+
+	deserialize_account(address, &account).map_err(|_| ApiError::TokenizerNotFound(*address))
+}
+
+// Fetches the accounts concurrently in chunks of the maximum accepted by `getMultipleAccounts`
+async fn get_multiple_accounts(
+	state: &AppState,
+	addresses: &[Pubkey],
+) -> Result<Vec<Option<Account>>> {
+	let chunks = try_join_all(addresses.chunks(MAX_MULTIPLE_ACCOUNTS).map(|chunk| {
+		state
+			.rpc_client
+			.get_multiple_accounts_with_commitment(chunk, state.rpc_client.commitment())
+	}))
+	.await?;
+
+	Ok(chunks
+		.into_iter()
+		.flat_map(|response| response.value)
+		.collect())
+}
+
+/// `GET /tokenizers`, lists the tokenizers ordered by underlying mint and expiry date,
+/// optionally filtered by `underlying_mint`, `authority` and `status`
+pub async fn list_tokenizers(
+	State(state): State<Arc<AppState>>,
+	Query(query): Query<TokenizerQuery>,
+) -> Result<Json<Vec<TokenizerSummary>>> {
+	let mut filters = vec![];
+
+	if let Some(ref underlying_mint) = query.underlying_mint {
+		filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+			UNDERLYING_MINT_OFFSET,
+			parse_address(underlying_mint)?.as_ref(),
+		)));
+	}
+
+	if let Some(ref authority) = query.authority {
+		filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+			AUTHORITY_OFFSET,
+			parse_address(authority)?.as_ref(),
+		)));
+	}
+
+	let timestamp = get_timestamp(&state).await?;
+
+	// The expiry date is compared against the cluster time so status is filtered locally
+	let mut tokenizers = get_tokenizers(&state, filters)
+		.await?
+		.iter()
+		.map(|(address, tokenizer_state)| {
+			TokenizerSummary::new(address, tokenizer_state, timestamp)
+		})
+		.filter(|tokenizer| match query.status {
+			Some(StatusFilter::Active) => tokenizer.status == TokenizerStatus::Active,
+			Some(StatusFilter::Expired) => tokenizer.status != TokenizerStatus::Active,
+			None => true,
+		})
+		.collect::<Vec<_>>();
+
+	tokenizers.sort_by(|a, b| {
+		a.underlying_mint
+			.cmp(&b.underlying_mint)
+			.then(a.expiry_date.cmp(&b.expiry_date))
+	});
+
+	Ok(Json(tokenizers))
+}
+
+/// `GET /tokenizers/{address}`
+pub async fn show_tokenizer(
+	State(state): State<Arc<AppState>>,
+	Path(address): Path<String>,
+) -> Result<Json<TokenizerDetails>> {
+	let address = parse_address(&address)?;
+	let tokenizer_state = get_tokenizer(&state, &address).await?;
+	let timestamp = get_timestamp(&state).await?;
+
+	let accounts = get_multiple_accounts(
+		&state,
+		&TokenizerBalances::addresses(&address, &tokenizer_state),
+	)
+	.await?;
+	let balances = TokenizerBalances::from_accounts(&tokenizer_state, &accounts)?;
+
+	Ok(Json(TokenizerDetails {
+		summary: TokenizerSummary::new(&address, &tokenizer_state, timestamp),
+		underlying_vault: tokenizer_state.underlying_vault.to_string(),
+		treasury: tokenizer_state.treasury.to_string(),
+		escrow: get_escrow_address(&address).0.to_string(),
+		swept_to_escrow: balances.escrow.is_some(),
+		vault_balance: balances.vault_balance,
+		principal_supply: balances.principal_supply,
+		yield_supply: balances.yield_supply,
+		grace_period: tokenizer_state.grace_period,
+		post_expiry_yield: format!("{:?}", tokenizer_state.post_expiry_yield),
+		exchange_rate: balances.exchange_rate.to_string(),
+		yield_index: tokenizer_state.yield_index.to_string(),
+		last_update_timestamp: tokenizer_state.last_update_timestamp,
+		expiry_exchange_rate: tokenizer_state.expiry_exchange_rate.to_string(),
+		expiry_yield_reserve: tokenizer_state.expiry_yield_reserve,
+	}))
+}
+
+/// `GET /tokenizers/{address}/apy`
+pub async fn show_apy(
+	State(state): State<Arc<AppState>>,
+	Path(address): Path<String>,
+) -> Result<Json<TokenizerApy>> {
+	let address = parse_address(&address)?;
+	let tokenizer_state = get_tokenizer(&state, &address).await?;
+	let timestamp = get_timestamp(&state).await?;
+
+	let stake_pool = state
+		.rpc_client
+		.get_account(&tokenizer_state.stake_pool)
+		.await?;
+	let exchange_rate = exchange_rate_from_data(&stake_pool.data, &tokenizer_state.underlying_mint)
+		.map_err(TokenizerClientError::from)?;
+
+	let elapsed = timestamp.saturating_sub(tokenizer_state.last_update_timestamp);
+
+	Ok(Json(TokenizerApy {
+		address: address.to_string(),
+		fixed_apy: tokenizer_state.fixed_apy,
+		variable_apy_bps: variable_apy_bps(tokenizer_state.yield_index, exchange_rate, elapsed),
+		exchange_rate: exchange_rate.to_string(),
+		yield_index: tokenizer_state.yield_index.to_string(),
+		last_update_timestamp: tokenizer_state.last_update_timestamp,
+		timestamp,
+	}))
+}
+
+/// `GET /positions/{wallet}`, lists the tokenizers the wallet holds principal or yield
+/// tokens of along with the underlying they can be redeemed for now
+pub async fn list_positions(
+	State(state): State<Arc<AppState>>,
+	Path(wallet): Path<String>,
+) -> Result<Json<Vec<Position>>> {
+	let wallet = parse_address(&wallet)?;
+
+	let token_accounts = state
+		.rpc_client
+		.get_token_accounts_by_owner(&wallet, TokenAccountsFilter::ProgramId(spl_token::id()))
+		.await?;
+	let token_balances = token_balances_by_mint(&token_accounts);
+
+	let held = get_tokenizers(&state, vec![])
+		.await?
+		.into_iter()
+		.filter_map(|(address, tokenizer_state)| {
+			let get_balance = |mint: Pubkey| token_balances.get(&mint).copied().unwrap_or_default();
+			let principal_balance = get_balance(get_principal_mint_address(&address).0);
+			let yield_balance = get_balance(get_yield_mint_address(&address).0);
+
+			(principal_balance != 0 || yield_balance != 0).then_some((
+				address,
+				tokenizer_state,
+				principal_balance,
+				yield_balance,
+			))
+		})
+		.collect::<Vec<_>>();
+
+	let timestamp = get_timestamp(&state).await?;
+	let addresses = held
+		.iter()
+		.flat_map(|(address, tokenizer_state, ..)| {
+			TokenizerBalances::addresses(address, tokenizer_state)
+		})
+		.collect::<Vec<_>>();
+	let accounts = get_multiple_accounts(&state, &addresses).await?;
+
+	// Every tokenizer fetches the five accounts returned by `TokenizerBalances::addresses`
+	let mut positions = vec![];
+	for ((address, tokenizer_state, principal_balance, yield_balance), accounts) in
+		held.iter().zip(accounts.chunks(5))
+	{
+		let balances = TokenizerBalances::from_accounts(tokenizer_state, accounts)?;
+		let redeemable = redeemable_amounts(
+			tokenizer_state,
+			&balances,
+			*principal_balance,
+			*yield_balance,
+			timestamp,
+		)?;
+
+		positions.push(Position {
+			tokenizer: address.to_string(),
+			underlying_mint: tokenizer_state.underlying_mint.to_string(),
+			principal_balance: *principal_balance,
+			yield_balance: *yield_balance,
+			redeemable_principal: redeemable.principal,
+			claimable_yield: redeemable.yield_claim,
+			seconds_to_maturity: tokenizer_state.expiry_date.saturating_sub(timestamp).max(0),
+			status: TokenizerStatus::from_state(tokenizer_state, timestamp),
+		});
+	}
+
+	Ok(Json(positions))
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		sclr_token::{math::WAD, state::PostExpiryYieldRecipient},
+	};
+
+	const YEAR: i64 = SECONDS_PER_YEAR as i64;
+
+	#[test]
+	fn variable_apy_annualises_growth_since_checkpoint() {
+		assert_eq!(variable_apy_bps(WAD, WAD * 105 / 100, YEAR), Some(500));
+		assert_eq!(variable_apy_bps(WAD, WAD * 101 / 100, YEAR / 2), Some(200));
+		assert_eq!(variable_apy_bps(WAD, WAD, YEAR), Some(0));
+
+		// A slashed stake pool has a negative APY
+		assert_eq!(variable_apy_bps(WAD, WAD * 99 / 100, YEAR), Some(-100));
+	}
+
+	#[test]
+	fn variable_apy_is_empty_without_a_period() {
+		assert_eq!(variable_apy_bps(WAD, WAD * 105 / 100, 0), None);
+		assert_eq!(variable_apy_bps(WAD, WAD * 105 / 100, -1), None);
+		assert_eq!(variable_apy_bps(0, WAD, YEAR), None);
+
+		// Growth that does not fit the response
+		assert_eq!(variable_apy_bps(1, WAD * 1_000, 1), None);
+	}
+
+	#[test]
+	fn summary_counts_down_to_maturity() {
+		let state = TokenizerState {
+			bump: 255,
+			authority: Pubkey::new_unique(),
+			principal_token_mint: Pubkey::new_unique(),
+			yield_token_mint: Pubkey::new_unique(),
+			underlying_mint: Pubkey::new_unique(),
+			underlying_vault: Pubkey::new_unique(),
+			expiry_date: 1_735_689_600,
+			fixed_apy: 500,
+			grace_period: 30 * 86_400,
+			stake_pool: Pubkey::new_unique(),
+			expiry_exchange_rate: 0,
+			post_expiry_yield: PostExpiryYieldRecipient::Treasury,
+			treasury: Pubkey::new_unique(),
+			expiry_yield_reserve: 0,
+			yield_index: WAD,
+			last_update_slot: 0,
+			last_update_timestamp: 0,
+		};
+		let address = Pubkey::new_unique();
+
+		let summary = TokenizerSummary::new(&address, &state, state.expiry_date - 60);
+		assert_eq!(summary.address, address.to_string());
+		assert_eq!(summary.seconds_to_maturity, 60);
+		assert_eq!(summary.status, TokenizerStatus::Active);
+
+		let summary = TokenizerSummary::new(&address, &state, state.expiry_date + 60);
+		assert_eq!(summary.seconds_to_maturity, 0);
+		assert_ne!(summary.status, TokenizerStatus::Active);
+	}
+
+	#[test]
+	fn addresses_are_validated() {
+		let address = Pubkey::new_unique();
+
+		assert_eq!(parse_address(&address.to_string()).unwrap(), address);
+		assert!(matches!(
+			parse_address("not-an-address"),
+			Err(ApiError::InvalidAddress(address)) if address == "not-an-address"
+		));
+	}
+}